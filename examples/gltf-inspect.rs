@@ -0,0 +1,168 @@
+//! Print a summary of a glTF file as loaded by easy-gltf.
+//!
+//! ```sh
+//! cargo run --example gltf-inspect --features names -- tests/head.glb
+//! ```
+
+use easy_gltf::model::{Material, Model};
+use easy_gltf::Scene;
+use std::env;
+use std::process;
+
+fn main() {
+    let path = match env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("Usage: gltf-inspect <file.gltf|file.glb>");
+            process::exit(2);
+        }
+    };
+
+    let scenes = match easy_gltf::load(&path) {
+        Ok(scenes) => scenes,
+        Err(err) => {
+            eprintln!("Failed to load {}: {}", path, err);
+            process::exit(1);
+        }
+    };
+
+    println!("{}: {} scene(s)", path, scenes.len());
+    for (index, scene) in scenes.iter().enumerate() {
+        print_scene(index, scene);
+    }
+}
+
+fn print_scene(index: usize, scene: &Scene) {
+    #[cfg(feature = "names")]
+    let name = scene.name.as_deref().unwrap_or("<unnamed>");
+    #[cfg(not(feature = "names"))]
+    let name = "<names feature disabled>";
+
+    println!("\nScene #{} ({})", index, name);
+    println!(
+        "  {} model(s), {} camera(s), {} light(s), {} marker(s), {} skin(s), {} animation(s)",
+        scene.models.len(),
+        scene.cameras.len(),
+        scene.lights.len(),
+        scene.markers.len(),
+        scene.skins.len(),
+        scene.animations.len()
+    );
+
+    println!("  Models:");
+    for (index, model) in scene.models.iter().enumerate() {
+        print_model(index, model);
+    }
+
+    println!("  Materials:");
+    for (index, material) in scene.materials.iter().enumerate() {
+        print_material(index, material);
+    }
+
+    let report = scene.memory_report();
+    println!(
+        "  Memory: {} bytes of geometry, {} texture(s) using {} bytes, ~{} bytes on GPU",
+        report.geometry,
+        report.texture_count,
+        report.total() - report.geometry,
+        report.gpu_estimate
+    );
+
+    let findings = scene.validate();
+    if findings.is_empty() {
+        println!("  Validation: no issue");
+    } else {
+        println!("  Validation:");
+        for finding in findings {
+            println!("    {}", finding);
+        }
+    }
+}
+
+fn print_model(index: usize, model: &Model) {
+    let mut attributes = vec!["position"];
+    if model.has_normals() {
+        attributes.push("normal");
+    }
+    if model.has_tangents() {
+        attributes.push("tangent");
+    }
+    if model.has_tex_coords() {
+        attributes.push("tex_coords");
+    }
+    if model.tex_coords_1().is_some() {
+        attributes.push("tex_coords_1");
+    }
+    #[cfg(feature = "vertex-color")]
+    if model.has_colors() {
+        attributes.push("color");
+    }
+
+    println!(
+        "    #{} mesh {} primitive {}: {:?}, {} vertices, {}, material #{}, [{}]",
+        index,
+        model.mesh_index(),
+        model.primitive_index(),
+        model.mode(),
+        model.vertices().len(),
+        match model.indices() {
+            Some(indices) => format!("{} indices", indices.len()),
+            None => "not indexed".to_string(),
+        },
+        model.material_index(),
+        attributes.join(", ")
+    );
+}
+
+fn print_material(index: usize, material: &Material) {
+    let mut textures = vec![];
+    if let Some(texture) = &material.pbr.base_color_texture {
+        textures.push(format!(
+            "base color {}x{}",
+            texture.width(),
+            texture.height()
+        ));
+    }
+    if let Some(texture) = &material.pbr.metallic_texture {
+        textures.push(format!("metallic {}x{}", texture.width(), texture.height()));
+    }
+    if let Some(texture) = &material.pbr.roughness_texture {
+        textures.push(format!(
+            "roughness {}x{}",
+            texture.width(),
+            texture.height()
+        ));
+    }
+    if let Some(normal) = &material.normal {
+        let texture = &normal.texture;
+        textures.push(format!("normal {}x{}", texture.width(), texture.height()));
+    }
+    if let Some(occlusion) = &material.occlusion {
+        let texture = &occlusion.texture;
+        textures.push(format!(
+            "occlusion {}x{}",
+            texture.width(),
+            texture.height()
+        ));
+    }
+    if let Some(texture) = &material.emissive.texture {
+        textures.push(format!("emissive {}x{}", texture.width(), texture.height()));
+    }
+
+    let factor = material.pbr.base_color_factor;
+    println!(
+        "    #{}: base color [{:.2}, {:.2}, {:.2}, {:.2}], metallic {:.2}, roughness {:.2}, textures: {}",
+        index,
+        factor.x,
+        factor.y,
+        factor.z,
+        factor.w,
+        material.pbr.metallic_factor,
+        material.pbr.roughness_factor,
+        if textures.is_empty() {
+            "none".to_string()
+        } else {
+            textures.join(", ")
+        }
+    );
+}
@@ -0,0 +1,92 @@
+//! Vulkan descriptions matching the easy-gltf data layout for
+//! [ash](https://github.com/ash-rs/ash). Requires the `ash` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use easy_gltf::ash::*;
+//!
+//! let binding = vertex_input_binding_description(0);
+//! let attributes = vertex_input_attribute_descriptions(0);
+//! let vertex_input = ::ash::vk::PipelineVertexInputStateCreateInfo::default()
+//!     .vertex_binding_descriptions(std::slice::from_ref(&binding))
+//!     .vertex_attribute_descriptions(&attributes);
+//! ```
+
+use crate::model::{Mode, Vertex};
+use ::ash::vk;
+
+/// Shader location of the position attribute.
+pub const POSITION_LOCATION: u32 = 0;
+/// Shader location of the normal attribute.
+pub const NORMAL_LOCATION: u32 = 1;
+/// Shader location of the tangent attribute.
+pub const TANGENT_LOCATION: u32 = 2;
+/// Shader location of the texture coordinates attribute.
+pub const TEX_COORDS_LOCATION: u32 = 3;
+/// Shader location of the color attribute. Requires the `vertex-color` feature.
+#[cfg(feature = "vertex-color")]
+pub const COLOR_LOCATION: u32 = 4;
+
+/// Index type of `Model::indices()`.
+pub const INDEX_TYPE: vk::IndexType = vk::IndexType::UINT32;
+
+/// Image format of `PbrMaterial::base_color_texture`.
+pub const BASE_COLOR_FORMAT: vk::Format = vk::Format::R8G8B8A8_SRGB;
+/// Image format of `PbrMaterial::metallic_texture`.
+pub const METALLIC_FORMAT: vk::Format = vk::Format::R8_UNORM;
+/// Image format of `PbrMaterial::roughness_texture`.
+pub const ROUGHNESS_FORMAT: vk::Format = vk::Format::R8_UNORM;
+/// Image format of `Occlusion::texture`.
+pub const OCCLUSION_FORMAT: vk::Format = vk::Format::R8_UNORM;
+/// Recommended image format of `NormalMap::texture`. RGB formats are rarely
+/// supported for sampling, so the texture must be expanded to RGBA.
+pub const NORMAL_FORMAT: vk::Format = vk::Format::R8G8B8A8_UNORM;
+/// Recommended image format of `Emissive::texture`. RGB formats are rarely
+/// supported for sampling, so the texture must be expanded to RGBA.
+pub const EMISSIVE_FORMAT: vk::Format = vk::Format::R8G8B8A8_SRGB;
+
+/// Binding description of a vertex buffer filled with `Model::vertices()`.
+pub fn vertex_input_binding_description(binding: u32) -> vk::VertexInputBindingDescription {
+    vk::VertexInputBindingDescription {
+        binding,
+        stride: std::mem::size_of::<Vertex>() as u32,
+        input_rate: vk::VertexInputRate::VERTEX,
+    }
+}
+
+/// Attribute descriptions matching the memory layout of
+/// [`Vertex`](../model/struct.Vertex.html).
+pub fn vertex_input_attribute_descriptions(
+    binding: u32,
+) -> Vec<vk::VertexInputAttributeDescription> {
+    let attribute = |location, format, offset| vk::VertexInputAttributeDescription {
+        location,
+        binding,
+        format,
+        offset,
+    };
+    vec![
+        attribute(POSITION_LOCATION, vk::Format::R32G32B32_SFLOAT, 0),
+        attribute(NORMAL_LOCATION, vk::Format::R32G32B32_SFLOAT, 12),
+        attribute(TANGENT_LOCATION, vk::Format::R32G32B32A32_SFLOAT, 24),
+        attribute(TEX_COORDS_LOCATION, vk::Format::R32G32_SFLOAT, 40),
+        #[cfg(feature = "vertex-color")]
+        attribute(COLOR_LOCATION, vk::Format::R16G16B16A16_UNORM, 48),
+    ]
+}
+
+/// Convert a mode to the corresponding Vulkan primitive topology.
+///
+/// **Note**: Vulkan has no line loop topology, `LineLoop` is mapped to
+/// `LINE_STRIP` so you have to close the loop yourself.
+pub fn primitive_topology(mode: Mode) -> vk::PrimitiveTopology {
+    match mode {
+        Mode::Points => vk::PrimitiveTopology::POINT_LIST,
+        Mode::Lines => vk::PrimitiveTopology::LINE_LIST,
+        Mode::LineLoop | Mode::LineStrip => vk::PrimitiveTopology::LINE_STRIP,
+        Mode::Triangles => vk::PrimitiveTopology::TRIANGLE_LIST,
+        Mode::TriangleStrip => vk::PrimitiveTopology::TRIANGLE_STRIP,
+        Mode::TriangleFan => vk::PrimitiveTopology::TRIANGLE_FAN,
+    }
+}
@@ -0,0 +1,252 @@
+//! Cache of decoded images shared by the files loaded by a
+//! [`Loader`](../struct.Loader.html).
+//!
+//! The default cache keeps the images in memory, implement
+//! [`ImageCache`](trait.ImageCache.html) to back it with a disk cache or an
+//! asset database. Implement [`ImageConsumer`](trait.ImageConsumer.html) to
+//! take over the images instead of keeping them in the materials.
+
+use crate::model::TextureSlot;
+use image::{GrayImage, RgbImage, RgbaImage};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Identifies the source of an image: external files can be shared between
+/// glTF files, embedded images only live in their own file.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ImageSource {
+    /// External image file, with its canonical path.
+    File(PathBuf),
+    /// Image embedded in a glTF file (buffer view or data URI), with the
+    /// canonical path of the glTF file and the index of the image.
+    Embedded(PathBuf, usize),
+}
+
+/// Pixel format an image is converted to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PixelFormat {
+    /// RGB 8 bits (normal maps, emissive).
+    Rgb,
+    /// RGB 8 bits with the green channel inverted, normal maps loaded with
+    /// `NormalConvention::DirectX` (see `LoadOptions::normal_convention`).
+    DirectXRgb,
+    /// RGBA 8 bits (base color).
+    Rgba,
+    /// RGBA 8 bits with premultiplied alpha, see
+    /// `LoadOptions::premultiply_alpha`.
+    PremultipliedRgba,
+    /// Single channel 8 bits extracted from the given channel of the image
+    /// (metallic, roughness, occlusion).
+    Gray(usize),
+}
+
+/// Key of a decoded image.
+pub type ImageKey = (ImageSource, PixelFormat);
+
+/// A decoded image, in the format of its key.
+#[derive(Clone, Debug)]
+pub enum CachedImage {
+    /// Image in the `Rgb` format.
+    Rgb(Arc<RgbImage>),
+    /// Image in the `Rgba` or `PremultipliedRgba` format.
+    Rgba(Arc<RgbaImage>),
+    /// Image in a `Gray` format.
+    Gray(Arc<GrayImage>),
+}
+
+impl CachedImage {
+    /// Size of the decoded pixels in bytes.
+    pub fn bytes(&self) -> usize {
+        match self {
+            CachedImage::Rgb(image) => image.len(),
+            CachedImage::Rgba(image) => image.len(),
+            CachedImage::Gray(image) => image.len(),
+        }
+    }
+
+    /// Address of the pixels, identifies the image while it is alive.
+    pub(crate) fn id(&self) -> usize {
+        match self {
+            CachedImage::Rgb(image) => Arc::as_ptr(image) as usize,
+            CachedImage::Rgba(image) => Arc::as_ptr(image) as usize,
+            CachedImage::Gray(image) => Arc::as_ptr(image) as usize,
+        }
+    }
+}
+
+/// Storage of decoded images.
+///
+/// # Example
+///
+/// ```
+/// use easy_gltf::cache::{CachedImage, ImageCache, ImageKey};
+/// use std::collections::HashMap;
+///
+/// /// Cache keeping every image forever.
+/// #[derive(Debug, Default)]
+/// struct Forever(HashMap<ImageKey, CachedImage>);
+///
+/// impl ImageCache for Forever {
+///     fn get(&mut self, key: &ImageKey) -> Option<CachedImage> {
+///         self.0.get(key).cloned()
+///     }
+///
+///     fn insert(&mut self, key: ImageKey, image: CachedImage) -> CachedImage {
+///         self.0.entry(key).or_insert(image).clone()
+///     }
+///
+///     fn clear(&mut self) {
+///         self.0.clear();
+///     }
+/// }
+///
+/// let mut loader = easy_gltf::Loader::with_cache(Default::default(), Forever::default());
+/// let scenes = loader.load("tests/cube.glb").expect("Failed to load glTF");
+/// ```
+pub trait ImageCache: Debug + Send {
+    /// Get a cached image.
+    fn get(&mut self, key: &ImageKey) -> Option<CachedImage>;
+
+    /// Cache a decoded image and return the image to use: files loaded
+    /// concurrently may decode the same image, the image already cached
+    /// should be returned so they share it.
+    fn insert(&mut self, key: ImageKey, image: CachedImage) -> CachedImage;
+
+    /// Drop every cached image, see
+    /// [`Loader::clear_cache`](../struct.Loader.html#method.clear_cache).
+    fn clear(&mut self);
+
+    /// Called when a glTF file is loaded, e.g. to drop its embedded images
+    /// which no other file can use. Does nothing by default.
+    fn file_loaded(&mut self, _file: &Path) {}
+
+    /// Called before loading a file with `LoadOptions::texture_budget`. Does
+    /// nothing by default.
+    fn set_budget(&mut self, _budget: Option<usize>) {}
+}
+
+/// Takes the decoded images of the materials, see
+/// `LoadOptions::image_consumer`.
+///
+/// # Example
+///
+/// ```
+/// use easy_gltf::cache::{CachedImage, ImageConsumer};
+/// use easy_gltf::model::TextureSlot;
+/// use easy_gltf::LoadOptions;
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::sync::Arc;
+///
+/// /// Uploads the images to the GPU, returns the texture id.
+/// #[derive(Debug, Default)]
+/// struct Uploader(AtomicU64);
+///
+/// impl ImageConsumer for Uploader {
+///     fn consume(&self, slot: TextureSlot, image: CachedImage) -> u64 {
+///         // upload `image` with the format of `slot.color_space()`...
+///         self.0.fetch_add(1, Ordering::Relaxed)
+///     }
+/// }
+///
+/// let options = LoadOptions {
+///     image_consumer: Some(Arc::new(Uploader::default())),
+///     ..Default::default()
+/// };
+/// let scenes = easy_gltf::load_with_options("tests/cube_classic.gltf", &options)
+///     .expect("Failed to load glTF");
+/// let material = scenes[0].models[0].material();
+/// assert!(material.texture_handle(TextureSlot::BaseColor).is_some());
+/// assert!(material.pbr.base_color_texture.is_none());
+/// ```
+pub trait ImageConsumer: Debug + Send + Sync {
+    /// Take an image of a material and return the handle stored in
+    /// `Material::texture_handles`. Called once per decoded image and file,
+    /// even if several materials use the image.
+    fn consume(&self, slot: TextureSlot, image: CachedImage) -> u64;
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    image: CachedImage,
+    last_use: u64,
+}
+
+/// Default cache, keeping decoded images in memory.
+///
+/// With a budget, the least recently used images are dropped from the cache
+/// once the decoded bytes exceed it. Embedded images are dropped once their
+/// file is loaded.
+#[derive(Default, Debug)]
+pub struct MemoryImageCache {
+    entries: HashMap<ImageKey, CacheEntry>,
+    budget: Option<usize>,
+    bytes: usize,
+    clock: u64,
+}
+
+impl MemoryImageCache {
+    /// Drop the least recently used images until the cache fits the budget.
+    fn evict(&mut self) {
+        let budget = match self.budget {
+            Some(budget) => budget,
+            None => return,
+        };
+        while self.bytes > budget {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_use)
+                .map(|(key, _)| key.clone());
+            match oldest.and_then(|key| self.entries.remove(&key)) {
+                Some(entry) => self.bytes -= entry.image.bytes(),
+                None => break,
+            }
+        }
+    }
+}
+
+impl ImageCache for MemoryImageCache {
+    fn get(&mut self, key: &ImageKey) -> Option<CachedImage> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.last_use = self.clock;
+        Some(entry.image.clone())
+    }
+
+    fn insert(&mut self, key: ImageKey, image: CachedImage) -> CachedImage {
+        if let Some(cached) = self.get(&key) {
+            return cached;
+        }
+        self.clock += 1;
+        self.bytes += image.bytes();
+        let entry = CacheEntry {
+            image: image.clone(),
+            last_use: self.clock,
+        };
+        self.entries.insert(key, entry);
+        self.evict();
+        image
+    }
+
+    fn clear(&mut self) {
+        *self = Self {
+            budget: self.budget,
+            ..Default::default()
+        };
+    }
+
+    fn file_loaded(&mut self, file: &Path) {
+        self.entries.retain(|(source, _), _| match source {
+            ImageSource::Embedded(path, _) => path != file,
+            ImageSource::File(_) => true,
+        });
+        self.bytes = self.entries.values().map(|e| e.image.bytes()).sum();
+    }
+
+    fn set_budget(&mut self, budget: Option<usize>) {
+        self.budget = budget;
+        self.evict();
+    }
+}
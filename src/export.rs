@@ -0,0 +1,644 @@
+use crate::model::{AlphaMode, Mapper, Material, Model, Triangle};
+use crate::{Camera, Light, LightKind, Projection, Scene};
+use cgmath::*;
+use gltf::binary::{Glb, Header};
+use gltf::json::Value;
+use image::{DynamicImage, GrayImage, ImageFormat, Luma, Rgb, RgbImage};
+use serde_json::json;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+#[cfg(feature = "vertex-color")]
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+
+/// Save scenes to a glTF 2.0 file.
+///
+/// The output format is selected from the file extension: `.glb` produces a
+/// single binary glTF, anything else produces a `.gltf` JSON file along with
+/// an external `.bin` buffer and PNG images written next to it.
+///
+/// Models are already baked in world space, so each of them is written as its
+/// own mesh on a node with an identity transform. Materials and textures shared
+/// between models are written only once.
+///
+/// # Example
+///
+/// ```
+/// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+/// let path = std::env::temp_dir().join("easy_gltf_doc_save.glb");
+/// easy_gltf::save(&path, &scenes).expect("Failed to save glTF");
+/// ```
+pub fn save<P>(path: P, scenes: &[Scene]) -> Result<(), Box<dyn Error + Send + Sync>>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let binary = path.extension() == Some(OsStr::new("glb"));
+
+    let mut writer = Writer::new(binary);
+    for scene in scenes {
+        writer.push_scene(scene)?;
+    }
+
+    if binary {
+        align(&mut writer.blob);
+        let blob = std::mem::take(&mut writer.blob);
+        let root = writer.finish(None);
+        let json = serde_json::to_vec(&root)?;
+        let glb = Glb {
+            header: Header {
+                magic: *b"glTF",
+                version: 2,
+                length: 0,
+            },
+            json: Cow::Owned(json),
+            bin: Some(Cow::Owned(blob)),
+        };
+        glb.to_writer(fs::File::create(path)?)?;
+    } else {
+        let stem = path
+            .file_stem()
+            .and_then(OsStr::to_str)
+            .unwrap_or("scene")
+            .to_string();
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let bin_name = format!("{}.bin", stem);
+        for (i, png) in writer.external_images.iter().enumerate() {
+            fs::write(dir.join(format!("{}_{}.png", stem, i)), png)?;
+        }
+        fs::write(dir.join(&bin_name), &writer.blob)?;
+        let root = writer.finish(Some((&bin_name, &stem)));
+        fs::write(path, serde_json::to_vec_pretty(&root)?)?;
+    }
+    Ok(())
+}
+
+/// Writes triangles as a binary STL.
+pub(crate) fn write_stl<W>(triangles: &[Triangle], mut writer: W) -> std::io::Result<()>
+where
+    W: Write,
+{
+    let mut header = [0u8; 80];
+    header[..15].copy_from_slice(b"easy-gltf model");
+    writer.write_all(&header)?;
+    writer.write_all(&(triangles.len() as u32).to_le_bytes())?;
+
+    for triangle in triangles {
+        let [a, b, c] = triangle.map(|v| v.position);
+        let normal = triangle.face_normal().unwrap_or_else(Vector3::zero);
+        for v in [normal, a, b, c] {
+            for x in Into::<[f32; 3]>::into(v) {
+                writer.write_all(&x.to_le_bytes())?;
+            }
+        }
+        // Attribute byte count
+        writer.write_all(&[0, 0])?;
+    }
+    writer.flush()
+}
+
+/// Accumulates the JSON arrays and binary blob of the exported document.
+struct Writer {
+    binary: bool,
+    blob: Vec<u8>,
+    external_images: Vec<Vec<u8>>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+    images: Vec<Value>,
+    textures: Vec<Value>,
+    materials: Vec<Value>,
+    meshes: Vec<Value>,
+    cameras: Vec<Value>,
+    lights: Vec<Value>,
+    nodes: Vec<Value>,
+    scenes: Vec<Value>,
+    material_ids: HashMap<*const Material, usize>,
+    texture_ids: HashMap<(usize, usize), usize>,
+    /// Whether a texture uses `KHR_texture_transform`.
+    texture_transforms: bool,
+}
+
+impl Writer {
+    fn new(binary: bool) -> Self {
+        Self {
+            binary,
+            blob: vec![],
+            external_images: vec![],
+            buffer_views: vec![],
+            accessors: vec![],
+            images: vec![],
+            textures: vec![],
+            materials: vec![],
+            meshes: vec![],
+            cameras: vec![],
+            lights: vec![],
+            nodes: vec![],
+            scenes: vec![],
+            material_ids: Default::default(),
+            texture_ids: Default::default(),
+            texture_transforms: false,
+        }
+    }
+
+    fn finish(mut self, external: Option<(&str, &str)>) -> Value {
+        let length = self.blob.len();
+        let buffers = if let Some((bin_name, image_prefix)) = external {
+            for (i, image) in self.images.iter_mut().enumerate() {
+                *image = json!({ "uri": format!("{}_{}.png", image_prefix, i) });
+            }
+            json!([{ "byteLength": length, "uri": bin_name }])
+        } else {
+            json!([{ "byteLength": length }])
+        };
+        self.root(buffers)
+    }
+
+    fn root(self, buffers: Value) -> Value {
+        let mut root = json!({
+            "asset": { "version": "2.0", "generator": "easy-gltf" },
+            "scene": 0,
+            "scenes": self.scenes,
+            "nodes": self.nodes,
+            "buffers": buffers,
+            "bufferViews": self.buffer_views,
+            "accessors": self.accessors,
+            "meshes": self.meshes,
+        });
+        let object = root.as_object_mut().unwrap();
+        for (key, values) in [
+            ("materials", self.materials),
+            ("textures", self.textures),
+            ("images", self.images),
+            ("cameras", self.cameras),
+        ] {
+            if !values.is_empty() {
+                object.insert(key.to_string(), Value::Array(values));
+            }
+        }
+        let mut extensions = vec![];
+        if self.texture_transforms {
+            extensions.push("KHR_texture_transform");
+        }
+        if !self.lights.is_empty() {
+            extensions.push("KHR_lights_punctual");
+            object.insert(
+                "extensions".into(),
+                json!({ "KHR_lights_punctual": { "lights": self.lights } }),
+            );
+        }
+        if !extensions.is_empty() {
+            object.insert("extensionsUsed".into(), json!(extensions));
+        }
+        root
+    }
+
+    fn push_scene(&mut self, scene: &Scene) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut nodes = vec![];
+        // An accessor can't be empty and its bounds must be finite
+        for model in scene
+            .models
+            .iter()
+            .filter(|model| !model.vertices().is_empty())
+        {
+            let mesh = self.push_model(model)?;
+            nodes.push(self.nodes.len());
+            #[allow(unused_mut)]
+            let mut node = json!({ "mesh": mesh });
+            #[cfg(feature = "names")]
+            if let Some(name) = model.mesh_name() {
+                node["name"] = json!(name);
+            }
+            self.nodes.push(node);
+        }
+        for camera in scene.cameras.iter() {
+            nodes.push(self.nodes.len());
+            let node = self.push_camera(camera);
+            self.nodes.push(node);
+        }
+        for light in scene.lights.iter() {
+            nodes.push(self.nodes.len());
+            let node = self.push_light(light);
+            self.nodes.push(node);
+        }
+
+        #[allow(unused_mut)]
+        let mut gltf_scene = json!({ "nodes": nodes });
+        #[cfg(feature = "names")]
+        if let Some(name) = &scene.name {
+            gltf_scene["name"] = json!(name);
+        }
+        self.scenes.push(gltf_scene);
+        Ok(())
+    }
+
+    fn push_model(&mut self, model: &Model) -> Result<usize, Box<dyn Error + Send + Sync>> {
+        let vertices = model.vertices();
+        let mut attributes = serde_json::Map::new();
+
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        for v in vertices.iter() {
+            for i in 0..3 {
+                min[i] = min[i].min(v.position[i]);
+                max[i] = max[i].max(v.position[i]);
+            }
+        }
+        let positions: Vec<f32> = vertices
+            .iter()
+            .flat_map(|v| Into::<[f32; 3]>::into(v.position))
+            .collect();
+        let accessor = self.push_accessor(
+            &f32_bytes(&positions),
+            ARRAY_BUFFER,
+            FLOAT,
+            vertices.len(),
+            "VEC3",
+        );
+        self.accessors[accessor]["min"] = json!(min);
+        self.accessors[accessor]["max"] = json!(max);
+        attributes.insert("POSITION".into(), json!(accessor));
+
+        if model.has_normals() {
+            let normals: Vec<f32> = vertices
+                .iter()
+                .flat_map(|v| Into::<[f32; 3]>::into(v.normal))
+                .collect();
+            let accessor = self.push_accessor(
+                &f32_bytes(&normals),
+                ARRAY_BUFFER,
+                FLOAT,
+                vertices.len(),
+                "VEC3",
+            );
+            attributes.insert("NORMAL".into(), json!(accessor));
+        }
+        if model.has_tangents() {
+            let tangents: Vec<f32> = vertices
+                .iter()
+                .flat_map(|v| Into::<[f32; 4]>::into(v.tangent))
+                .collect();
+            let accessor = self.push_accessor(
+                &f32_bytes(&tangents),
+                ARRAY_BUFFER,
+                FLOAT,
+                vertices.len(),
+                "VEC4",
+            );
+            attributes.insert("TANGENT".into(), json!(accessor));
+        }
+        if model.has_tex_coords() {
+            let tex_coords: Vec<f32> = vertices
+                .iter()
+                .flat_map(|v| Into::<[f32; 2]>::into(v.tex_coords))
+                .collect();
+            let accessor = self.push_accessor(
+                &f32_bytes(&tex_coords),
+                ARRAY_BUFFER,
+                FLOAT,
+                vertices.len(),
+                "VEC2",
+            );
+            attributes.insert("TEXCOORD_0".into(), json!(accessor));
+        }
+        if let Some(tex_coords_1) = model.tex_coords_1() {
+            let tex_coords: Vec<f32> = tex_coords_1
+                .iter()
+                .flat_map(|&t| Into::<[f32; 2]>::into(t))
+                .collect();
+            let accessor = self.push_accessor(
+                &f32_bytes(&tex_coords),
+                ARRAY_BUFFER,
+                FLOAT,
+                tex_coords_1.len(),
+                "VEC2",
+            );
+            attributes.insert("TEXCOORD_1".into(), json!(accessor));
+        }
+        #[cfg(feature = "vertex-color")]
+        if model.has_colors() {
+            let colors: Vec<u8> = vertices
+                .iter()
+                .flat_map(|v| Into::<[u16; 4]>::into(v.color))
+                .flat_map(u16::to_le_bytes)
+                .collect();
+            let accessor = self.push_accessor(
+                &colors,
+                ARRAY_BUFFER,
+                UNSIGNED_SHORT,
+                vertices.len(),
+                "VEC4",
+            );
+            self.accessors[accessor]["normalized"] = json!(true);
+            attributes.insert("COLOR_0".into(), json!(accessor));
+        }
+
+        let mut primitive = json!({
+            "attributes": attributes,
+            "mode": model.mode().as_gl_enum(),
+        });
+        if let Some(indices) = model.indices() {
+            let bytes: Vec<u8> = indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+            let accessor = self.push_accessor(
+                &bytes,
+                ELEMENT_ARRAY_BUFFER,
+                UNSIGNED_INT,
+                indices.len(),
+                "SCALAR",
+            );
+            primitive["indices"] = json!(accessor);
+        }
+        primitive["material"] = json!(self.push_material(&model.material)?);
+
+        #[allow(unused_mut)]
+        let mut mesh = json!({ "primitives": [primitive] });
+        #[cfg(feature = "names")]
+        if let Some(name) = model.mesh_name() {
+            mesh["name"] = json!(name);
+        }
+        self.meshes.push(mesh);
+        Ok(self.meshes.len() - 1)
+    }
+
+    fn push_material(
+        &mut self,
+        material: &Arc<Material>,
+    ) -> Result<usize, Box<dyn Error + Send + Sync>> {
+        if let Some(id) = self.material_ids.get(&Arc::as_ptr(material)) {
+            return Ok(*id);
+        }
+
+        let pbr = &material.pbr;
+        let mut pbr_json = json!({
+            "baseColorFactor": Into::<[f32; 4]>::into(pbr.base_color_factor),
+            "metallicFactor": pbr.metallic_factor,
+            "roughnessFactor": pbr.roughness_factor,
+        });
+        if let Some(texture) = &pbr.base_color_texture {
+            let id = self.push_texture(Arc::as_ptr(texture) as usize, || {
+                DynamicImage::ImageRgba8(texture.as_ref().clone())
+            })?;
+            pbr_json["baseColorTexture"] = self.texture_info(id, &pbr.base_color_mapper);
+        }
+        if pbr.metallic_texture.is_some() || pbr.roughness_texture.is_some() {
+            let key = pbr
+                .metallic_texture
+                .as_ref()
+                .map(|t| Arc::as_ptr(t) as usize);
+            let key2 = pbr
+                .roughness_texture
+                .as_ref()
+                .map(|t| Arc::as_ptr(t) as usize);
+            let id = self.push_texture_pair(key.unwrap_or(0), key2.unwrap_or(0), || {
+                DynamicImage::ImageRgb8(metallic_roughness_image(
+                    pbr.metallic_texture.as_deref(),
+                    pbr.roughness_texture.as_deref(),
+                ))
+            })?;
+            pbr_json["metallicRoughnessTexture"] =
+                self.texture_info(id, &pbr.metallic_roughness_mapper);
+        }
+
+        let mut mat_json = json!({
+            "pbrMetallicRoughness": pbr_json,
+            "emissiveFactor": Into::<[f32; 3]>::into(material.emissive.factor),
+        });
+        #[cfg(feature = "names")]
+        if let Some(name) = &material.name {
+            mat_json["name"] = json!(name);
+        }
+        match material.alpha_mode {
+            AlphaMode::Opaque => {}
+            AlphaMode::Mask { cutoff } => {
+                mat_json["alphaMode"] = json!("MASK");
+                mat_json["alphaCutoff"] = json!(cutoff);
+            }
+            AlphaMode::Blend => mat_json["alphaMode"] = json!("BLEND"),
+        }
+        if material.double_sided {
+            mat_json["doubleSided"] = json!(true);
+        }
+        if let Some(texture) = &material.emissive.texture {
+            let id = self.push_texture(Arc::as_ptr(texture) as usize, || {
+                DynamicImage::ImageRgb8(texture.as_ref().clone())
+            })?;
+            mat_json["emissiveTexture"] = self.texture_info(id, &material.emissive.mapper);
+        }
+        if let Some(normal) = &material.normal {
+            let id = self.push_texture(Arc::as_ptr(&normal.texture) as usize, || {
+                DynamicImage::ImageRgb8(normal.texture.as_ref().clone())
+            })?;
+            mat_json["normalTexture"] = self.texture_info(id, &normal.mapper);
+            mat_json["normalTexture"]["scale"] = json!(normal.factor);
+        }
+        if let Some(occlusion) = &material.occlusion {
+            let id = self.push_texture(Arc::as_ptr(&occlusion.texture) as usize, || {
+                DynamicImage::ImageLuma8(occlusion.texture.as_ref().clone())
+            })?;
+            mat_json["occlusionTexture"] = self.texture_info(id, &occlusion.mapper);
+            mat_json["occlusionTexture"]["strength"] = json!(occlusion.factor);
+        }
+
+        self.materials.push(mat_json);
+        let id = self.materials.len() - 1;
+        self.material_ids.insert(Arc::as_ptr(material), id);
+        Ok(id)
+    }
+
+    /// Texture reference of a material with the set of texture coordinates
+    /// and the transform of `mapper`.
+    fn texture_info(&mut self, id: usize, mapper: &Mapper) -> Value {
+        let mut info = json!({ "index": id });
+        if mapper.tex_coord != 0 {
+            info["texCoord"] = json!(mapper.tex_coord);
+        }
+        if !mapper.is_identity() {
+            self.texture_transforms = true;
+            info["extensions"] = json!({
+                "KHR_texture_transform": {
+                    "offset": Into::<[f32; 2]>::into(mapper.offset),
+                    "rotation": mapper.rotation,
+                    "scale": Into::<[f32; 2]>::into(mapper.scale),
+                }
+            });
+        }
+        info
+    }
+
+    fn push_texture<F>(
+        &mut self,
+        key: usize,
+        image: F,
+    ) -> Result<usize, Box<dyn Error + Send + Sync>>
+    where
+        F: FnOnce() -> DynamicImage,
+    {
+        self.push_texture_pair(key, 0, image)
+    }
+
+    fn push_texture_pair<F>(
+        &mut self,
+        key: usize,
+        key2: usize,
+        image: F,
+    ) -> Result<usize, Box<dyn Error + Send + Sync>>
+    where
+        F: FnOnce() -> DynamicImage,
+    {
+        if let Some(id) = self.texture_ids.get(&(key, key2)) {
+            return Ok(*id);
+        }
+
+        let mut png = vec![];
+        image().write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+        if self.binary {
+            let view = self.push_view(&png, None);
+            self.images
+                .push(json!({ "bufferView": view, "mimeType": "image/png" }));
+        } else {
+            // The uri is filled once the output file name is known
+            self.external_images.push(png);
+            self.images.push(Value::Null);
+        }
+        self.textures
+            .push(json!({ "source": self.images.len() - 1 }));
+        let id = self.textures.len() - 1;
+        self.texture_ids.insert((key, key2), id);
+        Ok(id)
+    }
+
+    fn push_camera(&mut self, camera: &Camera) -> Value {
+        #[allow(unused_mut)]
+        let mut cam_json = match &camera.projection {
+            Projection::Perspective { yfov, aspect_ratio } => {
+                let mut pers = json!({ "yfov": yfov.0, "znear": camera.znear });
+                if camera.zfar.is_finite() {
+                    pers["zfar"] = json!(camera.zfar);
+                }
+                if let Some(aspect_ratio) = aspect_ratio {
+                    pers["aspectRatio"] = json!(aspect_ratio);
+                }
+                json!({ "type": "perspective", "perspective": pers })
+            }
+            Projection::Orthographic { scale } => json!({
+                "type": "orthographic",
+                "orthographic": {
+                    "xmag": scale.x,
+                    "ymag": scale.y,
+                    "znear": camera.znear,
+                    "zfar": camera.zfar,
+                },
+            }),
+        };
+        #[cfg(feature = "names")]
+        if let Some(name) = &camera.name {
+            cam_json["name"] = json!(name);
+        }
+        self.cameras.push(cam_json);
+
+        let matrix: &[f32; 16] = camera.transform.as_ref();
+        json!({ "camera": self.cameras.len() - 1, "matrix": matrix })
+    }
+
+    fn push_light(&mut self, light: &Light) -> Value {
+        let mut light_json = json!({
+            "color": Into::<[f32; 3]>::into(light.color),
+            "intensity": light.intensity,
+        });
+        match light.kind {
+            LightKind::Directional => light_json["type"] = json!("directional"),
+            LightKind::Point { .. } => light_json["type"] = json!("point"),
+            LightKind::Spot {
+                inner_cone_angle,
+                outer_cone_angle,
+                ..
+            } => {
+                light_json["type"] = json!("spot");
+                light_json["spot"] = json!({
+                    "innerConeAngle": inner_cone_angle,
+                    "outerConeAngle": outer_cone_angle,
+                });
+            }
+        }
+        if let Some(range) = light.range() {
+            light_json["range"] = json!(range);
+        }
+        #[cfg(feature = "names")]
+        if let Some(name) = &light.name {
+            light_json["name"] = json!(name);
+        }
+        self.lights.push(light_json);
+
+        let matrix: &[f32; 16] = light.transform.as_ref();
+        json!({
+            "matrix": matrix,
+            "extensions": { "KHR_lights_punctual": { "light": self.lights.len() - 1 } },
+        })
+    }
+
+    fn push_accessor(
+        &mut self,
+        bytes: &[u8],
+        target: u32,
+        component_type: u32,
+        count: usize,
+        type_: &str,
+    ) -> usize {
+        let view = self.push_view(bytes, Some(target));
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": component_type,
+            "count": count,
+            "type": type_,
+        }));
+        self.accessors.len() - 1
+    }
+
+    fn push_view(&mut self, bytes: &[u8], target: Option<u32>) -> usize {
+        align(&mut self.blob);
+        let mut view = json!({
+            "buffer": 0,
+            "byteOffset": self.blob.len(),
+            "byteLength": bytes.len(),
+        });
+        if let Some(target) = target {
+            view["target"] = json!(target);
+        }
+        self.blob.extend_from_slice(bytes);
+        self.buffer_views.push(view);
+        self.buffer_views.len() - 1
+    }
+}
+
+/// Packs the metallic (blue) and roughness (green) channels back into a single
+/// texture as expected by glTF.
+fn metallic_roughness_image(
+    metallic: Option<&GrayImage>,
+    roughness: Option<&GrayImage>,
+) -> RgbImage {
+    let (width, height) = metallic.or(roughness).map(|t| t.dimensions()).unwrap();
+    let sample = |texture: Option<&GrayImage>, x: u32, y: u32| match texture {
+        Some(texture) if texture.dimensions() == (width, height) => texture[(x, y)],
+        _ => Luma([255]),
+    };
+    RgbImage::from_fn(width, height, |x, y| {
+        Rgb([0, sample(roughness, x, y)[0], sample(metallic, x, y)[0]])
+    })
+}
+
+fn f32_bytes(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn align(blob: &mut Vec<u8>) {
+    blob.resize(blob.len().next_multiple_of(4), 0);
+}
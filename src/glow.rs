@@ -0,0 +1,263 @@
+//! Helpers to upload models and materials with [glow](https://github.com/grovesNL/glow).
+//! Requires the `glow` feature.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use glow::HasContext;
+//! # unsafe fn render(gl: &glow::Context) {
+//! let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+//! let model = &scenes[0].models[0];
+//! let mesh = easy_gltf::glow::GlModel::new(gl, model).expect("Failed to upload model");
+//! let textures = easy_gltf::glow::GlMaterialTextures::new(gl, &model.material())
+//!     .expect("Failed to upload textures");
+//! // Bind your program and textures...
+//! mesh.draw(gl);
+//! # }
+//! ```
+
+use crate::model::{Material, Mode, Model, Vertex};
+use crate::utils::as_bytes;
+use ::glow::HasContext;
+use image::{ImageBuffer, Pixel};
+use std::ops::Deref;
+
+/// Shader location of the position attribute.
+pub const POSITION_LOCATION: u32 = 0;
+/// Shader location of the normal attribute.
+pub const NORMAL_LOCATION: u32 = 1;
+/// Shader location of the tangent attribute.
+pub const TANGENT_LOCATION: u32 = 2;
+/// Shader location of the texture coordinates attribute.
+pub const TEX_COORDS_LOCATION: u32 = 3;
+/// Shader location of the color attribute. Requires the `vertex-color` feature.
+#[cfg(feature = "vertex-color")]
+pub const COLOR_LOCATION: u32 = 4;
+
+/// Convert a mode to the corresponding OpenGL primitive (e.g. `GL_TRIANGLES`).
+pub fn gl_mode(mode: Mode) -> u32 {
+    mode.as_gl_enum()
+}
+
+/// Vertex array object of a model with its buffers.
+#[derive(Debug)]
+pub struct GlModel<G: HasContext> {
+    /// Vertex array object with the vertex attributes enabled.
+    pub vao: G::VertexArray,
+    /// Vertex buffer object.
+    pub vbo: G::Buffer,
+    /// Element buffer object (of `GL_UNSIGNED_INT`) if the model has indices.
+    pub ebo: Option<G::Buffer>,
+    /// OpenGL primitive mode.
+    pub mode: u32,
+    /// Number of indices, or vertices if there is no index.
+    pub count: i32,
+}
+
+impl<G: HasContext> GlModel<G> {
+    /// Create the VAO, VBO and EBO of a model.
+    ///
+    /// Attributes are bound to `POSITION_LOCATION`, `NORMAL_LOCATION`,
+    /// `TANGENT_LOCATION`, `TEX_COORDS_LOCATION` (and `COLOR_LOCATION`).
+    ///
+    /// # Safety
+    ///
+    /// The OpenGL context must be current on the calling thread.
+    pub unsafe fn new(gl: &G, model: &Model) -> Result<Self, String> {
+        let vao = gl.create_vertex_array()?;
+        gl.bind_vertex_array(Some(vao));
+
+        let vbo = gl.create_buffer()?;
+        gl.bind_buffer(::glow::ARRAY_BUFFER, Some(vbo));
+        gl.buffer_data_u8_slice(
+            ::glow::ARRAY_BUFFER,
+            as_bytes(model.vertices()),
+            ::glow::STATIC_DRAW,
+        );
+
+        let stride = std::mem::size_of::<Vertex>() as i32;
+        let attributes = [
+            (POSITION_LOCATION, 3, 0),
+            (NORMAL_LOCATION, 3, 12),
+            (TANGENT_LOCATION, 4, 24),
+            (TEX_COORDS_LOCATION, 2, 40),
+        ];
+        for (location, size, offset) in attributes {
+            gl.enable_vertex_attrib_array(location);
+            gl.vertex_attrib_pointer_f32(location, size, ::glow::FLOAT, false, stride, offset);
+        }
+        #[cfg(feature = "vertex-color")]
+        {
+            gl.enable_vertex_attrib_array(COLOR_LOCATION);
+            gl.vertex_attrib_pointer_f32(
+                COLOR_LOCATION,
+                4,
+                ::glow::UNSIGNED_SHORT,
+                true,
+                stride,
+                48,
+            );
+        }
+
+        let ebo = match model.indices() {
+            Some(indices) => {
+                let ebo = gl.create_buffer()?;
+                gl.bind_buffer(::glow::ELEMENT_ARRAY_BUFFER, Some(ebo));
+                gl.buffer_data_u8_slice(
+                    ::glow::ELEMENT_ARRAY_BUFFER,
+                    as_bytes(indices),
+                    ::glow::STATIC_DRAW,
+                );
+                Some(ebo)
+            }
+            None => None,
+        };
+        gl.bind_vertex_array(None);
+
+        Ok(Self {
+            vao,
+            vbo,
+            ebo,
+            mode: gl_mode(model.mode()),
+            count: model.indices().map_or(model.vertices().len(), Vec::len) as i32,
+        })
+    }
+
+    /// Draw the model with `glDrawElements` or `glDrawArrays`.
+    ///
+    /// # Safety
+    ///
+    /// The OpenGL context must be current and be the one used to create the model.
+    pub unsafe fn draw(&self, gl: &G) {
+        gl.bind_vertex_array(Some(self.vao));
+        if self.ebo.is_some() {
+            gl.draw_elements(self.mode, self.count, ::glow::UNSIGNED_INT, 0);
+        } else {
+            gl.draw_arrays(self.mode, 0, self.count);
+        }
+        gl.bind_vertex_array(None);
+    }
+
+    /// Delete the VAO and its buffers.
+    ///
+    /// # Safety
+    ///
+    /// The OpenGL context must be current and be the one used to create the model.
+    pub unsafe fn delete(self, gl: &G) {
+        gl.delete_vertex_array(self.vao);
+        gl.delete_buffer(self.vbo);
+        if let Some(ebo) = self.ebo {
+            gl.delete_buffer(ebo);
+        }
+    }
+}
+
+/// OpenGL textures of a material.
+///
+/// Color textures (base color and emissive) use `GL_SRGB8*` internal formats so
+/// the sampled values are already in linear space. Textures use the default
+/// glTF sampler state: `GL_REPEAT` wrapping and trilinear filtering.
+#[derive(Debug)]
+pub struct GlMaterialTextures<G: HasContext> {
+    /// Base color texture (`GL_SRGB8_ALPHA8`).
+    pub base_color: Option<G::Texture>,
+    /// Metallic texture (`GL_R8`).
+    pub metallic: Option<G::Texture>,
+    /// Roughness texture (`GL_R8`).
+    pub roughness: Option<G::Texture>,
+    /// Normal map (`GL_RGB8`).
+    pub normal: Option<G::Texture>,
+    /// Occlusion texture (`GL_R8`).
+    pub occlusion: Option<G::Texture>,
+    /// Emissive texture (`GL_SRGB8`).
+    pub emissive: Option<G::Texture>,
+}
+
+impl<G: HasContext> GlMaterialTextures<G> {
+    /// Upload every texture of the material.
+    ///
+    /// # Safety
+    ///
+    /// The OpenGL context must be current on the calling thread.
+    pub unsafe fn new(gl: &G, material: &Material) -> Result<Self, String> {
+        let pbr = &material.pbr;
+        Ok(Self {
+            base_color: pbr
+                .base_color_texture
+                .as_ref()
+                .map(|t| create_texture(gl, t, ::glow::SRGB8_ALPHA8, ::glow::RGBA))
+                .transpose()?,
+            metallic: pbr
+                .metallic_texture
+                .as_ref()
+                .map(|t| create_texture(gl, t, ::glow::R8, ::glow::RED))
+                .transpose()?,
+            roughness: pbr
+                .roughness_texture
+                .as_ref()
+                .map(|t| create_texture(gl, t, ::glow::R8, ::glow::RED))
+                .transpose()?,
+            normal: material
+                .normal
+                .as_ref()
+                .map(|n| create_texture(gl, &n.texture, ::glow::RGB8, ::glow::RGB))
+                .transpose()?,
+            occlusion: material
+                .occlusion
+                .as_ref()
+                .map(|o| create_texture(gl, &o.texture, ::glow::R8, ::glow::RED))
+                .transpose()?,
+            emissive: material
+                .emissive
+                .texture
+                .as_ref()
+                .map(|t| create_texture(gl, t, ::glow::SRGB8, ::glow::RGB))
+                .transpose()?,
+        })
+    }
+}
+
+/// Create a mipmapped 2D texture from an image.
+///
+/// # Safety
+///
+/// The OpenGL context must be current on the calling thread.
+pub unsafe fn create_texture<G, P, Container>(
+    gl: &G,
+    image: &ImageBuffer<P, Container>,
+    internal_format: u32,
+    format: u32,
+) -> Result<G::Texture, String>
+where
+    G: HasContext,
+    P: Pixel<Subpixel = u8>,
+    Container: Deref<Target = [u8]>,
+{
+    let texture = gl.create_texture()?;
+    gl.bind_texture(::glow::TEXTURE_2D, Some(texture));
+    // Rows of RGB and gray images are not 4 bytes aligned
+    gl.pixel_store_i32(::glow::UNPACK_ALIGNMENT, 1);
+    gl.tex_image_2d(
+        ::glow::TEXTURE_2D,
+        0,
+        internal_format as i32,
+        image.width() as i32,
+        image.height() as i32,
+        0,
+        format,
+        ::glow::UNSIGNED_BYTE,
+        Some(image.as_raw()),
+    );
+    gl.generate_mipmap(::glow::TEXTURE_2D);
+    let parameters = [
+        (::glow::TEXTURE_WRAP_S, ::glow::REPEAT),
+        (::glow::TEXTURE_WRAP_T, ::glow::REPEAT),
+        (::glow::TEXTURE_MIN_FILTER, ::glow::LINEAR_MIPMAP_LINEAR),
+        (::glow::TEXTURE_MAG_FILTER, ::glow::LINEAR),
+    ];
+    for (parameter, value) in parameters {
+        gl.tex_parameter_i32(::glow::TEXTURE_2D, parameter, value as i32);
+    }
+    gl.bind_texture(::glow::TEXTURE_2D, None);
+    Ok(texture)
+}
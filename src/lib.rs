@@ -19,13 +19,40 @@
 //! }
 //! ```
 
+#[cfg(feature = "ash")]
+pub mod ash;
+pub mod cache;
+mod export;
+#[cfg(feature = "glow")]
+pub mod glow;
+mod library;
+mod loader;
+mod options;
+#[cfg(feature = "parry3d")]
+pub mod parry3d;
 mod scene;
 mod utils;
+#[cfg(feature = "wgpu")]
+pub mod wgpu;
 
+use cache::{ImageCache, MemoryImageCache};
+use std::collections::HashSet;
 use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
 use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
 use utils::GltfData;
 
+/// Re-export of the [gltf](https://github.com/gltf-rs/gltf) crate used to
+/// parse files. Requires the `document` feature.
+#[cfg(feature = "document")]
+pub use gltf;
+
+pub use export::save;
+pub use library::Library;
+pub use loader::{LoadIter, Loader};
+pub use options::{Handedness, LoadOptions, NodeAction, PlaceholderTextures, UpAxis};
 pub use scene::*;
 
 /// Load scenes from path to a glTF 2.0.
@@ -46,23 +73,242 @@ pub fn load<P>(path: P) -> Result<Vec<Scene>, Box<dyn Error + Send + Sync>>
 where
     P: AsRef<Path>,
 {
-    // Run gltf
-    let (doc, buffers, _images) = gltf::import(&path)?;
+    load_with_options(path, &LoadOptions::default())
+}
 
-    // Init data and collection useful for conversion
-    let mut data = GltfData::new(buffers, &path);
+/// Load scenes from path to a glTF 2.0 with custom [`LoadOptions`](struct.LoadOptions.html).
+///
+/// # Example
+///
+/// ```
+/// use easy_gltf::{LoadOptions, UpAxis};
+///
+/// let options = LoadOptions {
+///     up_axis: UpAxis::Z,
+///     ..Default::default()
+/// };
+/// let scenes = easy_gltf::load_with_options("tests/cube.glb", &options)
+///     .expect("Failed to load glTF");
+/// ```
+pub fn load_with_options<P>(
+    path: P,
+    options: &LoadOptions,
+) -> Result<Vec<Scene>, Box<dyn Error + Send + Sync>>
+where
+    P: AsRef<Path>,
+{
+    import(path, options, &memory_cache()).map(|(scenes, _)| scenes)
+}
+
+/// Same as [`load_with_options`](fn.load_with_options.html) but also returns the
+/// parsed `gltf::Document`, to query data easy-gltf doesn't surface without
+/// parsing the file twice. Requires the `document` feature.
+///
+/// # Example
+///
+/// ```
+/// let (scenes, document) =
+///     easy_gltf::load_with_document("tests/cube.glb", &Default::default())
+///         .expect("Failed to load glTF");
+/// assert_eq!(scenes.len(), document.scenes().len());
+/// ```
+#[cfg(feature = "document")]
+pub fn load_with_document<P>(
+    path: P,
+    options: &LoadOptions,
+) -> Result<(Vec<Scene>, gltf::Document), Box<dyn Error + Send + Sync>>
+where
+    P: AsRef<Path>,
+{
+    import(path, options, &memory_cache())
+}
+
+/// Load several glTF 2.0 files concurrently, sharing the decoded images
+/// between them (see [`Loader`](struct.Loader.html)). Returns the scenes or
+/// the error of every file, in the order of `paths`.
+///
+/// # Example
+///
+/// ```
+/// let results = easy_gltf::load_many(
+///     &["tests/cube.glb", "tests/head.glb"],
+///     &Default::default(),
+/// );
+/// for result in results {
+///     let scenes = result.expect("Failed to load glTF");
+/// }
+/// ```
+pub fn load_many<P>(
+    paths: &[P],
+    options: &LoadOptions,
+) -> Vec<Result<Vec<Scene>, Box<dyn Error + Send + Sync>>>
+where
+    P: AsRef<Path> + Sync,
+{
+    Loader::new(options.clone()).load_many(paths)
+}
+
+/// Load every material and image of a glTF 2.0, including the ones not used
+/// by any scene.
+///
+/// # Example
+///
+/// ```
+/// let library = easy_gltf::load_library("tests/head.glb", &Default::default())
+///     .expect("Failed to load glTF");
+/// println!("Materials: #{}", library.materials.len());
+/// ```
+pub fn load_library<P>(
+    path: P,
+    options: &LoadOptions,
+) -> Result<Library, Box<dyn Error + Send + Sync>>
+where
+    P: AsRef<Path>,
+{
+    // The images of a library are usually not used by its scenes
+    let used = |doc: &gltf::Document, _: &LoadOptions| utils::image_buffers(doc);
+    let (doc, mut data) = read(path, options, memory_cache(), used)?;
+    Ok(Library::load(&doc, &mut data))
+}
+
+/// Load a glTF 2.0 lazily: models, cameras and lights are converted one at a
+/// time while iterating, so rendering and GPU uploads can start before the
+/// whole file is processed.
+///
+/// Items are yielded with the index of their scene, in the same order as in
+/// [`Scene`](struct.Scene.html).
+///
+/// **Note**: The buffers are still read upfront, unless
+/// `LoadOptions::stream_buffers` is set.
+///
+/// # Example
+///
+/// ```
+/// use easy_gltf::SceneItem;
+///
+/// for item in easy_gltf::load_iter("tests/cube.glb").expect("Failed to load glTF") {
+///     let (scene, item) = item.expect("Failed to read buffers");
+///     match item {
+///         SceneItem::Model(model) => { /* Upload the model... */ }
+///         SceneItem::Camera(camera) => {}
+///         SceneItem::Light(light) => {}
+///         SceneItem::Marker(marker) => {}
+///         SceneItem::Material(_) => {} // Only with `LoadOptions::materials_only`
+///     }
+/// }
+/// ```
+pub fn load_iter<P>(path: P) -> Result<LoadIter, Box<dyn Error + Send + Sync>>
+where
+    P: AsRef<Path>,
+{
+    load_iter_with_options(path, &LoadOptions::default())
+}
+
+/// Same as [`load_iter`](fn.load_iter.html) with custom
+/// [`LoadOptions`](struct.LoadOptions.html).
+pub fn load_iter_with_options<P>(
+    path: P,
+    options: &LoadOptions,
+) -> Result<LoadIter, Box<dyn Error + Send + Sync>>
+where
+    P: AsRef<Path>,
+{
+    let (doc, data) = read(path, options, memory_cache(), scene_buffers)?;
+    Ok(LoadIter::new(doc, data))
+}
+
+/// Image cache used by a single load.
+fn memory_cache() -> Arc<Mutex<dyn ImageCache>> {
+    Arc::new(Mutex::new(MemoryImageCache::default()))
+}
+
+fn import<P>(
+    path: P,
+    options: &LoadOptions,
+    images: &Arc<Mutex<dyn ImageCache>>,
+) -> Result<(Vec<Scene>, gltf::Document), Box<dyn Error + Send + Sync>>
+where
+    P: AsRef<Path>,
+{
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("load", path = %path.as_ref().display()).entered();
+    #[cfg(feature = "tracing")]
+    let start = std::time::Instant::now();
+
+    let (doc, mut data) = read(path, options, images.clone(), scene_buffers)?;
 
     // Convert gltf -> easy_gltf
     let mut res = vec![];
-    for scene in doc.scenes() {
-        res.push(Scene::load(scene, &mut data));
+    for scene in options.scenes(&doc) {
+        res.push(Scene::load(&doc, scene, &mut data)?);
     }
-    Ok(res)
+
+    // Only keep the images that other files can share
+    images
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .file_loaded(&data.file);
+
+    #[cfg(feature = "tracing")]
+    tracing::info!(scenes = res.len(), elapsed = ?start.elapsed(), "glTF loaded");
+    Ok((res, doc))
+}
+
+/// Buffers needed to load the scenes selected by the options.
+fn scene_buffers(doc: &gltf::Document, options: &LoadOptions) -> HashSet<usize> {
+    utils::used_buffers(doc, &options.scenes(doc), options)
+}
+
+/// Read a glTF and the buffers chosen by `used`.
+fn read<P>(
+    path: P,
+    options: &LoadOptions,
+    images: Arc<Mutex<dyn ImageCache>>,
+    used: impl FnOnce(&gltf::Document, &LoadOptions) -> HashSet<usize>,
+) -> Result<(gltf::Document, GltfData), Box<dyn Error + Send + Sync>>
+where
+    P: AsRef<Path>,
+{
+    #[cfg(feature = "tracing")]
+    let start = std::time::Instant::now();
+
+    // Run gltf, images are decoded later on demand
+    let base = path.as_ref().parent().unwrap_or_else(|| Path::new("./"));
+    let reader = BufReader::new(File::open(&path)?);
+    let (document, blob) = utils::read_gltf(reader)?;
+    let doc = validate(document)?;
+    let buffers = utils::import_buffers(&doc, &used(&doc, options), options, base, blob)?;
+
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        buffers = buffers.size().0,
+        bytes = buffers.size().1,
+        elapsed = ?start.elapsed(),
+        "glTF imported"
+    );
+
+    // Init data and collection useful for conversion
+    let data = GltfData::new(buffers, &path, options, images);
+    Ok((doc, data))
+}
+
+/// Extensions supported by easy-gltf on top of the gltf crate.
+const EXTENSIONS: &[&str] = &["KHR_mesh_quantization"];
+
+/// Validate a document, accepting the extensions supported by easy-gltf.
+fn validate(document: gltf::Document) -> Result<gltf::Document, gltf::Error> {
+    let mut json = document.into_json();
+    let required = json.extensions_required.clone();
+    json.extensions_required
+        .retain(|extension| !EXTENSIONS.contains(&extension.as_str()));
+    let mut json = gltf::Document::from_json(json)?.into_json();
+    json.extensions_required = required;
+    Ok(gltf::Document::from_json_without_validation(json))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::model::Mode;
+    use crate::model::{Mode, Vertex};
     use crate::*;
     use cgmath::*;
 
@@ -90,8 +336,34 @@ mod tests {
         assert_eq!(scenes.len(), 1);
         let scene = &scenes[0];
         assert_eq!(scene.models.len(), 1);
+        #[cfg(feature = "vertex-color")]
         assert!(scene.models[0].has_colors());
         assert_eq!(scene.models[0].indices().unwrap().len(), 36);
+        assert_eq!(scene.models[0].stats().primitive_count, 12);
+        #[cfg(feature = "vertex-color")]
+        {
+            let color = scene.models[0].vertices()[0].color_f32();
+            assert!(color.x >= 0. && color.x <= 1. && color.w == 1.);
+        }
+    }
+
+    #[test]
+    fn check_compact_colors() {
+        let options = LoadOptions {
+            compact_colors: true,
+            ..Default::default()
+        };
+        let scenes = load_with_options("tests/cube_color.glb", &options).unwrap();
+        let model = &scenes[0].models[0];
+        assert_eq!(
+            model.compact_colors().unwrap().len(),
+            model.vertices().len()
+        );
+        assert_eq!(model.compact_colors().unwrap()[0][3], 255);
+        assert!(load("tests/cube_color.glb").unwrap()[0].models[0]
+            .compact_colors()
+            .is_none());
+        assert_eq!(utils::linear_to_srgb(0.5), 188);
     }
 
     #[test]
@@ -114,22 +386,580 @@ mod tests {
         }
     }
 
+    #[test]
+    fn check_to_non_indexed() {
+        let model = &load("tests/cube.glb").unwrap()[0].models[0];
+        let flat = model.to_non_indexed();
+        assert!(flat.indices().is_none());
+        assert_eq!(flat.vertices().len(), model.indices().unwrap().len());
+        assert_eq!(flat.triangles().unwrap(), model.triangles().unwrap());
+
+        let strip = Model {
+            mode: Mode::TriangleStrip,
+            vertices: std::sync::Arc::new(vec![Vertex::default(); 4]),
+            indices: Some(std::sync::Arc::new(vec![0, 1, 2, 3])),
+            ..Default::default()
+        };
+        let flat = strip.to_non_indexed();
+        assert_eq!(flat.mode(), Mode::Triangles);
+        assert_eq!(flat.vertices().len(), 6);
+    }
+
     #[test]
     fn check_cube_gltf() {
         let _ = load("tests/cube_classic.gltf").unwrap();
     }
 
+    #[test]
+    fn check_loader() {
+        let mut loader = Loader::default();
+        let first = loader.load("tests/cube_classic.gltf").unwrap();
+        let second = loader.load("tests/cube_classic.gltf").unwrap();
+        let texture = |scenes: &[Scene]| {
+            let material = scenes[0].models[0].material();
+            material.pbr.base_color_texture.clone().unwrap()
+        };
+        assert!(std::sync::Arc::ptr_eq(&texture(&first), &texture(&second)));
+
+        // Nothing fits in the cache, images are decoded again
+        loader.options.texture_budget = Some(0);
+        let third = loader.load("tests/cube_classic.gltf").unwrap();
+        let fourth = loader.load("tests/cube_classic.gltf").unwrap();
+        assert!(!std::sync::Arc::ptr_eq(&texture(&third), &texture(&fourth)));
+        assert_eq!(texture(&third), texture(&fourth));
+    }
+
+    #[test]
+    fn check_load_many() {
+        let paths = [
+            "tests/cube_classic.gltf",
+            "tests/missing.glb",
+            "tests/cube_classic.gltf",
+            "tests/head.glb",
+        ];
+        let results = load_many(&paths, &Default::default());
+        assert_eq!(results.len(), paths.len());
+        assert!(results[1].is_err());
+        let texture = |i: usize| {
+            let scenes = results[i].as_ref().unwrap();
+            scenes[0].models[0]
+                .material()
+                .pbr
+                .base_color_texture
+                .clone()
+                .unwrap()
+        };
+        assert!(std::sync::Arc::ptr_eq(&texture(0), &texture(2)));
+        assert_eq!(texture(3).dimensions(), (1024, 1024));
+    }
+
+    #[test]
+    fn check_load_iter() {
+        let scenes = load("tests/cube.glb").unwrap();
+        let items: Vec<_> = load_iter("tests/cube.glb")
+            .unwrap()
+            .collect::<gltf::Result<_>>()
+            .unwrap();
+        let scene = &scenes[0];
+        assert_eq!(
+            items.len(),
+            scene.models.len() + scene.cameras.len() + scene.lights.len() + scene.markers.len()
+        );
+        let models: Vec<_> = items
+            .iter()
+            .filter_map(|(_, item)| match item {
+                SceneItem::Model(model) => Some(model),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(models[0].vertices(), scene.models[0].vertices());
+    }
+
     #[test]
     fn check_default_texture() {
         let _ = load("tests/box_sparse.glb").unwrap();
     }
 
+    #[test]
+    fn check_quantization() {
+        let scenes = load("tests/quantized.gltf").unwrap();
+        let vertices = scenes[0].models[0].vertices();
+        let close = |a: Vector3<f32>, b: Vector3<f32>| (a - b).magnitude() < 0.001;
+        assert!(close(vertices[1].position, Vector3::new(2., 0., 0.)));
+        assert!(close(vertices[2].position, Vector3::new(1., 1., 0.)));
+        assert!(close(vertices[0].normal, Vector3::unit_z()));
+        assert_eq!(vertices[2].tex_coords, Vector2::new(0., 1.));
+    }
+
+    #[test]
+    fn check_morph_targets() {
+        let scenes = load("tests/box_sparse.glb").unwrap();
+        let model = &scenes[0].models[0];
+        let targets = model.morph_targets();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].positions.len(), model.vertices().len());
+        #[cfg(feature = "extras")]
+        assert_eq!(targets[0].name, None);
+    }
+
+    #[test]
+    fn check_morph_weights() {
+        let scenes = load("tests/morph.gltf").unwrap();
+        let models = &scenes[0].models;
+        assert_eq!(models[0].morph_weights(), &[0.25]);
+        assert_eq!(models[1].morph_weights(), &[0.75]);
+        let box_sparse = load("tests/box_sparse.glb").unwrap();
+        assert_eq!(box_sparse[0].models[0].morph_weights(), &[0.]);
+    }
+
+    #[test]
+    fn check_capability_flags() {
+        let scenes = load("tests/skin.gltf").unwrap();
+        let model = &scenes[0].models[0];
+        assert!(model.has_joints() && model.is_skinned());
+        assert!(!model.has_morph_targets() && !model.has_tex_coords_1());
+        let scenes = load("tests/morph.gltf").unwrap();
+        let model = &scenes[0].models[0];
+        assert!(model.has_morph_targets());
+        assert!(!model.has_joints() && !model.is_skinned());
+    }
+
+    #[test]
+    fn check_skins() {
+        let scenes = load("tests/skin.gltf").unwrap();
+        let scene = &scenes[0];
+        assert_eq!(scene.models[0].skin_index(), Some(0));
+        let skin = &scene.skins[0];
+        #[cfg(feature = "names")]
+        assert_eq!(skin.name.as_deref(), Some("Armature"));
+        assert_eq!(skin.skeleton, Some(1));
+        assert_eq!(skin.joints, vec![1, 2]);
+        assert_eq!(skin.inverse_bind_matrices.len(), 2);
+    }
+
+    #[test]
+    fn check_animations() {
+        let scenes = load("tests/skin.gltf").unwrap();
+        let animation = &scenes[0].animations[0];
+        #[cfg(feature = "names")]
+        assert_eq!(animation.name.as_deref(), Some("Wave"));
+        assert_eq!(animation.duration, 1.);
+        assert!(animation.channels.contains(&Channel {
+            node: 2,
+            property: Property::Rotation
+        }));
+        assert!(animation.targets(1) && !animation.targets(0));
+    }
+
+    #[test]
+    fn check_interpolation() {
+        let scenes = load("tests/skin.gltf").unwrap();
+        let animation = &scenes[0].animations[0];
+        let interpolations: Vec<_> = animation.tracks.iter().map(|t| t.interpolation).collect();
+        assert_eq!(interpolations, [Interpolation::Linear, Interpolation::Step]);
+        let pose = animation.sample(0.5);
+        assert_eq!(pose.nodes[&1].translation, Some(Vector3::zero()));
+        let angle = Quaternion::from_angle_z(Deg(45.));
+        assert!((pose.nodes[&2].rotation.unwrap() - angle).magnitude() < 1e-5);
+
+        // Flat tangents ease in and out
+        let zero = Vector3::zero();
+        let clip = Animation {
+            channels: vec![Channel {
+                node: 0,
+                property: Property::Translation,
+            }],
+            tracks: vec![Track {
+                times: vec![0., 1.],
+                values: Keyframes::Translation(vec![
+                    zero,
+                    zero,
+                    zero,
+                    zero,
+                    Vector3::unit_x(),
+                    zero,
+                ]),
+                interpolation: Interpolation::CubicSpline,
+            }],
+            ..Default::default()
+        };
+        let x = clip.sample(0.25).nodes[&0].translation.unwrap().x;
+        assert!((x - 0.15625).abs() < 1e-6);
+    }
+
+    #[test]
+    fn check_resampled() {
+        let scenes = load("tests/skin.gltf").unwrap();
+        let animation = &scenes[0].animations[0];
+        let resampled = animation.resampled(30., 1e-3);
+        // Slerp and step tracks don't need extra keyframes
+        for track in resampled.tracks.iter() {
+            assert_eq!(track.times, [0., 1.]);
+        }
+        let (a, b) = (animation.sample(0.3), resampled.sample(0.3));
+        let (a, b) = (a.nodes[&2].rotation.unwrap(), b.nodes[&2].rotation.unwrap());
+        assert!((a - b).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn check_pose_blend() {
+        let scenes = load("tests/skin.gltf").unwrap();
+        let clip = &scenes[0].animations[0];
+        let rest = scenes[0].rest_pose();
+        assert_eq!(rest.nodes[&2].translation, Some(Vector3::unit_y()));
+
+        let (mut from, mut to) = (clip.sample(0.), clip.sample(1.));
+        from.fill(&rest);
+        to.fill(&rest);
+        let half = from.blend(&to, 0.5).nodes[&2].clone();
+        let angle = Quaternion::from_angle_z(Deg(45.));
+        assert!((half.rotation.unwrap() - angle).magnitude() < 1e-5);
+        assert_eq!(half.translation, Some(Vector3::unit_y()));
+        let root = from.blend(&to, 0.25).nodes[&1].translation.unwrap();
+        assert!((root - Vector3::new(0., 0.5, 0.)).magnitude() < 1e-6);
+    }
+
+    #[test]
+    fn check_root_motion() {
+        let mut scenes = load("tests/skin.gltf").unwrap();
+        let clip = &mut scenes[0].animations[0];
+        assert!(clip.extract_root_motion(0, Vector3::unit_y()).is_none());
+        let motion = clip.extract_root_motion(1, Vector3::unit_y()).unwrap();
+        assert_eq!(
+            motion.values,
+            Keyframes::Translation(vec![Vector3::zero(), Vector3::new(0., 2., 0.)])
+        );
+        let pose = clip.sample(1.);
+        assert_eq!(pose.nodes[&1].translation, Some(Vector3::zero()));
+    }
+
+    #[test]
+    fn check_pose_at() {
+        let scenes = load("tests/skin.gltf").unwrap();
+        let frame = scenes[0].pose_at(&scenes[0].animations[0], 1.);
+        let positions: Vec<_> = frame.models[0]
+            .vertices()
+            .iter()
+            .map(|v| v.position)
+            .collect();
+        let expected = [
+            Vector3::new(0., 2., 0.),
+            Vector3::new(0.5, 3., 0.),
+            Vector3::new(-1., 3., 0.),
+        ];
+        for (position, expected) in positions.iter().zip(expected.iter()) {
+            assert!((position - expected).magnitude() < 1e-5);
+        }
+        assert_eq!(frame.models[0].skin_index(), None);
+    }
+
+    #[test]
+    fn check_image_format_guess() {
+        let png = std::fs::read("tests/cube.png").unwrap();
+        let expected = utils::decode_image(&png, Some("image/png"))
+            .unwrap()
+            .width();
+        assert_eq!(
+            utils::decode_image(&png, Some("image/jpeg"))
+                .unwrap()
+                .width(),
+            expected
+        );
+        assert_eq!(utils::decode_image(&png, None).unwrap().width(), expected);
+        assert!(utils::decode_image(&png[..64], None).is_err());
+    }
+
+    #[test]
+    fn check_encoded_uri() {
+        let dir = std::env::temp_dir().join("easy_gltf_uri");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy("tests/cube.png", dir.join("cube texture é.png")).unwrap();
+        std::fs::copy("tests/cube_classic.bin", dir.join("cube data.bin")).unwrap();
+        let gltf = std::fs::read_to_string("tests/cube_classic.gltf")
+            .unwrap()
+            .replace("\"cube.png\"", "\"cube%20texture%20%C3%A9.png\"")
+            .replace("\"cube_classic.bin\"", "\"cube%20data.bin\"");
+        std::fs::write(dir.join("cube.gltf"), gltf).unwrap();
+        let scenes = load(dir.join("cube.gltf")).unwrap();
+        assert!(scenes[0].models[0]
+            .material()
+            .pbr
+            .base_color_texture
+            .is_some());
+    }
+
+    #[test]
+    fn check_missing_image() {
+        let dir = std::env::temp_dir().join("easy_gltf_missing_image");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy("tests/cube_classic.bin", dir.join("cube_classic.bin")).unwrap();
+        std::fs::write(dir.join("corrupt.png"), b"not a png").unwrap();
+        let gltf = std::fs::read_to_string("tests/cube_classic.gltf").unwrap();
+        for image in ["missing.png", "corrupt.png"] {
+            let path = dir.join("cube.gltf");
+            std::fs::write(
+                &path,
+                gltf.replace("\"cube.png\"", &format!("\"{}\"", image)),
+            )
+            .unwrap();
+            let scenes = load(&path).unwrap();
+            let material = scenes[0].models[0].material();
+            let texture = material.pbr.base_color_texture.as_ref().unwrap();
+            assert_eq!(texture.dimensions(), (1, 1));
+        }
+    }
+
     #[test]
     fn check_camera() {
         let scenes = load("tests/cube.glb").unwrap();
         let scene = &scenes[0];
         let cam = &scene.cameras[0];
         assert!((cam.position() - Vector3::new(7.3589, 4.9583, 6.9258)).magnitude() < 0.1);
+        assert_ne!(scene.models[0].node_index(), cam.node_index);
+    }
+
+    #[test]
+    fn check_camera_fov() {
+        let mut cam = load("tests/cube.glb").unwrap()[0].cameras[0].clone();
+        let aspect_ratio = cam.aspect_ratio().unwrap();
+        assert!((cam.focal_length(36. / aspect_ratio).unwrap() - 50.).abs() < 0.01);
+        assert!((cam.xfov(1.).unwrap().0 - 0.3996).abs() < 0.001);
+        assert!(cam.xfov(aspect_ratio).unwrap() < Rad(0.3996 * aspect_ratio));
+        cam.set_aspect_ratio(1.5);
+        assert_eq!(cam.aspect_ratio(), Some(1.5));
+    }
+
+    #[test]
+    fn check_frustum() {
+        let cam = &load("tests/cube.glb").unwrap()[0].cameras[0];
+        let frustum = cam.frustum(1.);
+        let behind = cam.position() + cam.forward() * 5.;
+        assert!(frustum.intersects_sphere(Vector3::zero(), 1.));
+        assert!(!frustum.intersects_sphere(behind, 1.));
+        let one = Vector3::new(1., 1., 1.);
+        assert!(frustum.intersects_aabb(-one, one));
+        assert!(!frustum.intersects_aabb(behind - one, behind + one));
+    }
+
+    #[test]
+    fn check_up_axis() {
+        let options = LoadOptions {
+            up_axis: UpAxis::Z,
+            ..Default::default()
+        };
+        let scenes = load_with_options("tests/cube.glb", &options).unwrap();
+        let cam = &scenes[0].cameras[0];
+        assert!((cam.position() - Vector3::new(7.3589, -6.9258, 4.9583)).magnitude() < 0.1);
+    }
+
+    #[test]
+    fn check_scale_factor() {
+        let options = LoadOptions {
+            scale_factor: 100.,
+            ..Default::default()
+        };
+        let scenes = load_with_options("tests/cube.glb", &options).unwrap();
+        let scene = &scenes[0];
+        let cam = &scene.cameras[0];
+        assert!((cam.position() - Vector3::new(735.89, 495.83, 692.58)).magnitude() < 10.);
+        assert!((cam.forward().magnitude() - 1.).abs() < 0.001);
+        for v in scene.models[0].vertices() {
+            assert!(v.position.x > -1. && v.position.x < 101.);
+        }
+    }
+
+    #[test]
+    fn check_origin() {
+        let model = &load("tests/cube.glb").unwrap()[0].models[0];
+        let options = LoadOptions {
+            origin: [1., 0., 0.],
+            f64_positions: true,
+            ..Default::default()
+        };
+        let moved = &load_with_options("tests/cube.glb", &options).unwrap()[0].models[0];
+        let positions = moved.positions_f64().unwrap();
+        for ((v, moved), p) in model.vertices().iter().zip(moved.vertices()).zip(positions) {
+            assert!((v.position.x - 1. - moved.position.x).abs() < 0.0001);
+            assert!((p.cast::<f32>().unwrap() - moved.position).magnitude() < 0.0001);
+        }
+        assert!(model.positions_f64().is_none());
+    }
+
+    #[test]
+    fn check_handedness() {
+        let options = LoadOptions {
+            handedness: Handedness::Left,
+            ..Default::default()
+        };
+        let scenes = load_with_options("tests/cube.glb", &options).unwrap();
+        let cam = &scenes[0].cameras[0];
+        assert!((cam.position() - Vector3::new(7.3589, 4.9583, -6.9258)).magnitude() < 0.1);
+        for t in scenes[0].models[0].triangles().unwrap() {
+            // Face normal must still agree with the vertex normals
+            let face = (t[1].position - t[0].position).cross(t[2].position - t[0].position);
+            assert!(face.dot(t[0].normal) > 0.);
+        }
+    }
+
+    #[test]
+    fn check_flip_v() {
+        let options = LoadOptions {
+            flip_v: true,
+            ..Default::default()
+        };
+        let flipped = load_with_options("tests/cube.glb", &options).unwrap();
+        let scenes = load("tests/cube.glb").unwrap();
+        let vertices = scenes[0].models[0].vertices();
+        for (v, flipped) in vertices.iter().zip(flipped[0].models[0].vertices()) {
+            assert_eq!(v.tex_coords.x, flipped.tex_coords.x);
+            assert_eq!(v.tex_coords.y, 1. - flipped.tex_coords.y);
+        }
+    }
+
+    #[cfg(feature = "names")]
+    #[test]
+    fn check_names() {
+        let scenes = load("tests/cube.glb").unwrap();
+        let scene = &scenes[0];
+        let name = scene.models[0].mesh_name().unwrap();
+        assert!(scene.model_by_name(name).is_some());
+        assert_eq!(scene.models_by_mesh_name(name).count(), 1);
+        assert!(scene.model_by_name("missing").is_none());
+        let name = scene.cameras[0].name.as_ref().unwrap();
+        assert!(scene.camera_by_name(name).is_some());
+        assert!(scene.cameras[0].node_name.is_some());
+        let name = scene.lights[0].name.as_ref().unwrap();
+        assert!(scene.light_by_name(name).is_some());
+
+        // Reordered objects are still found by name
+        let mut scene = scenes[0].clone();
+        scene.lights.reverse();
+        let names: Vec<_> = scene.lights.iter().map(|l| l.name.clone()).collect();
+        for name in names.iter().flatten() {
+            assert_eq!(scene.light_by_name(name).unwrap().name.as_ref(), Some(name));
+        }
+        let model = scene.models.remove(0);
+        let name = model.mesh_name().unwrap();
+        assert!(scene.model_by_name(name).is_none());
+        scene.models.push(model.clone());
+        assert_eq!(scene.models_by_mesh_name(name).count(), 1);
+    }
+
+    #[test]
+    fn check_selective_loading() {
+        let options = LoadOptions {
+            skip_cameras: true,
+            skip_lights: true,
+            ..Default::default()
+        };
+        let scene = &load_with_options("tests/cube.glb", &options).unwrap()[0];
+        assert!(scene.cameras.is_empty() && scene.lights.is_empty());
+        assert!(!scene.models.is_empty());
+
+        let options = LoadOptions {
+            materials_only: true,
+            ..Default::default()
+        };
+        let scene = &load_with_options("tests/head.glb", &options).unwrap()[0];
+        assert!(scene.models.is_empty());
+        assert!(scene.materials[0].pbr.base_color_texture.is_some());
+    }
+
+    #[test]
+    fn check_library() {
+        let library = load_library("tests/head.glb", &Default::default()).unwrap();
+        let scene = &load("tests/head.glb").unwrap()[0];
+        assert_eq!(library.materials.len(), scene.materials.len());
+        assert!(!library.images.is_empty());
+        assert!(library.materials[0].pbr.base_color_texture.is_some());
+
+        // Library file whose scenes are empty
+        let gltf = std::fs::read_to_string("tests/scenes.gltf").unwrap();
+        let mut json: serde_json::Value = serde_json::from_str(&gltf).unwrap();
+        for scene in json["scenes"].as_array_mut().unwrap() {
+            scene["nodes"] = serde_json::json!([]);
+        }
+        let path = std::env::temp_dir().join("easy_gltf_library.gltf");
+        std::fs::write(&path, json.to_string()).unwrap();
+        let library = load_library(&path, &Default::default()).unwrap();
+        let scenes = load("tests/scenes.gltf").unwrap();
+        let texture = scenes[1].models[0]
+            .material()
+            .pbr
+            .base_color_texture
+            .clone();
+        assert_eq!(library.images[0].as_ref(), texture.unwrap().as_ref());
+        assert_ne!(library.images[0].get_pixel(0, 0).0, [255; 4]);
+    }
+
+    #[test]
+    fn check_scene_index() {
+        let options = LoadOptions {
+            scene: Some(0),
+            ..Default::default()
+        };
+        let scenes = load_with_options("tests/cube.glb", &options).unwrap();
+        assert_eq!(scenes.len(), 1);
+        assert_eq!(scenes[0].models[0].vertices().len(), 24);
+        let options = LoadOptions {
+            scene: Some(1),
+            ..Default::default()
+        };
+        assert!(load_with_options("tests/cube.glb", &options)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn check_scene_resources() {
+        let doc = gltf::Gltf::open("tests/scenes.gltf").unwrap().document;
+        let scenes: Vec<_> = doc.scenes().collect();
+        let options = LoadOptions::default();
+        let used = utils::used_buffers(&doc, &scenes[..1], &options);
+        assert_eq!(used, [0].iter().copied().collect());
+        let used = utils::used_buffers(&doc, &scenes[1..], &options);
+        assert_eq!(used, [0, 1, 2].iter().copied().collect());
+
+        let options = LoadOptions {
+            scene: Some(1),
+            ..Default::default()
+        };
+        let scene = &load_with_options("tests/scenes.gltf", &options).unwrap()[0];
+        assert!(scene.materials[0].pbr.base_color_texture.is_some());
+        assert_eq!(scene.animations.len(), 1);
+    }
+
+    #[test]
+    fn check_node_filter() {
+        let options = LoadOptions {
+            node_filter: Some(|node| {
+                if node.mesh().is_some() {
+                    NodeAction::SkipSelf
+                } else {
+                    NodeAction::Load
+                }
+            }),
+            ..Default::default()
+        };
+        let scene = &load_with_options("tests/cube.glb", &options).unwrap()[0];
+        assert!(scene.models.is_empty());
+        assert_eq!(scene.lights.len(), 3);
+    }
+
+    #[test]
+    fn check_image_based_light() {
+        let scenes = load("tests/ibl.gltf").unwrap();
+        let light = scenes[0].image_based_light.as_ref().unwrap();
+        assert_eq!(light.intensity, 2.);
+        assert_eq!(light.specular_image_size, 256);
+        assert_eq!(
+            light.irradiance_coefficients[0],
+            Vector3::new(0.5, 0.5, 0.5)
+        );
+        assert_eq!(light.specular_images.len(), 1);
+        assert!(load("tests/cube.glb").unwrap()[0]
+            .image_based_light
+            .is_none());
     }
 
     #[test]
@@ -137,50 +967,141 @@ mod tests {
         let scenes = load("tests/cube.glb").unwrap();
         let scene = &scenes[0];
         for light in scene.lights.iter() {
-            match light {
-                Light::Directional {
-                    direction,
-                    color: _,
-                    intensity,
-                    ..
-                } => {
+            match light.kind {
+                LightKind::Directional => {
+                    let direction = light.direction();
                     assert!((direction - Vector3::new(0.6068, -0.7568, -0.2427)).magnitude() < 0.1);
-                    assert_delta!(intensity, 542., 0.01);
+                    assert_delta!(light.intensity, 542., 0.01);
                 }
-                Light::Point {
-                    position,
-                    color: _,
-                    intensity,
-                    ..
-                } => {
+                LightKind::Point { .. } => {
+                    let position = light.position();
                     assert!((position - Vector3::new(4.0762, 5.9039, -1.0055)).magnitude() < 0.1);
-                    assert_delta!(intensity, 1000., 0.01);
+                    assert_delta!(light.intensity, 1000., 0.01);
                 }
-                Light::Spot {
-                    position,
-                    direction,
-                    color: _,
-                    intensity,
-                    inner_cone_angle: _,
-                    outer_cone_angle,
-                    ..
+                LightKind::Spot {
+                    outer_cone_angle, ..
                 } => {
+                    let position = light.position();
                     assert!((position - Vector3::new(4.337, 15.541, -8.106)).magnitude() < 0.1);
+                    let direction = light.direction();
                     assert!(
                         (direction - Vector3::new(-0.0959, -0.98623, 0.1346)).magnitude() < 0.1
                     );
-                    assert_delta!(intensity, 42., 0.01);
+                    assert_delta!(light.intensity, 42., 0.01);
                     assert_delta!(outer_cone_angle, 40., 0.01);
                 }
             }
         }
     }
 
+    #[test]
+    fn check_apply_transform() {
+        let scene = &load("tests/cube.glb").unwrap()[0];
+        let mut moved = scene.clone();
+        let offset = Vector3::new(10., 0., 0.);
+        moved.apply_transform(&Matrix4::from_translation(offset));
+        let (before, after) = (
+            &scene.models[0].vertices()[0],
+            &moved.models[0].vertices()[0],
+        );
+        assert!((after.position - before.position - offset).magnitude() < 0.001);
+        assert!((after.normal - before.normal).magnitude() < 0.001);
+        let camera = moved.cameras[0].position() - scene.cameras[0].position();
+        assert!((camera - offset).magnitude() < 0.001);
+        let light = moved.lights[0].position() - scene.lights[0].position();
+        assert!((light - offset).magnitude() < 0.001);
+
+        let mut scaled = scene.clone();
+        scaled.cameras[0].projection = Projection::Orthographic {
+            scale: Vector2::new(2., 1.),
+        };
+        scaled.lights[0].kind = LightKind::Point { range: Some(5.) };
+        scaled.apply_transform(&Matrix4::from_scale(2.));
+        let (before, after) = (&scene.cameras[0], &scaled.cameras[0]);
+        assert!((after.znear - 2. * before.znear).abs() < 0.001);
+        assert_eq!(after.zfar.is_finite(), before.zfar.is_finite());
+        if before.zfar.is_finite() {
+            assert!((after.zfar - 2. * before.zfar).abs() < 0.001);
+        }
+        assert_eq!(after.aspect_ratio(), Some(2.));
+        match after.projection {
+            Projection::Orthographic { scale } => {
+                assert!((scale - Vector2::new(4., 2.)).magnitude() < 0.001)
+            }
+            _ => panic!("orthographic projection expected"),
+        }
+        match scaled.lights[0].kind {
+            LightKind::Point { range } => assert!((range.unwrap() - 10.).abs() < 0.001),
+            _ => panic!("point light expected"),
+        }
+    }
+
+    #[test]
+    fn check_transformed() {
+        let model = &load("tests/cube.glb").unwrap()[0].models[0];
+        let mirrored = model.transformed(&Matrix4::from_nonuniform_scale(-1., 1., 1.));
+        let (before, after) = (&model.vertices()[0], &mirrored.vertices()[0]);
+        assert_eq!(after.position.x, -before.position.x);
+        assert_eq!(after.normal.x, -before.normal.x);
+        assert_eq!(after.tangent.w, -before.tangent.w);
+        let triangles = (model.triangles().unwrap(), mirrored.triangles().unwrap());
+        assert_eq!(triangles.0[0][1].position.y, triangles.1[0][2].position.y);
+    }
+
+    #[test]
+    fn check_mirrored_node() {
+        let scenes = load("tests/mirrored.gltf").unwrap();
+        let (original, mirrored) = (&scenes[0].models[0], &scenes[0].models[1]);
+        assert!(mirrored.vertices()[1].position.x < 0.);
+        for model in [original, mirrored] {
+            // Counter-clockwise triangles face their normals
+            let triangle = &model.triangles().unwrap()[0];
+            let [a, b, c] = [0, 1, 2].map(|i| triangle[i].position);
+            assert!((b - a).cross(c - a).dot(triangle[0].normal) > 0.);
+        }
+        assert_eq!(original.vertices()[0].tangent.w, 1.);
+        assert_eq!(mirrored.vertices()[0].tangent.w, -1.);
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn check_half_vertices() {
+        let model = &load("tests/cube.glb").unwrap()[0].models[0];
+        let vertices = model.half_vertices();
+        assert_eq!(vertices.len(), model.vertices().len());
+        let (half, full) = (&vertices[0], &model.vertices()[0]);
+        assert_eq!(half.position, Into::<[f32; 3]>::into(full.position));
+        assert!((half.normal[1].to_f32() - full.normal.y).abs() < 0.001);
+        assert!((half.tex_coords[0].to_f32() - full.tex_coords.x).abs() < 0.001);
+    }
+
+    #[test]
+    fn check_merge() {
+        let mut loader = Loader::default();
+        let cube = loader.load("tests/cube.glb").unwrap().remove(0);
+        let head = loader.load("tests/head.glb").unwrap().remove(0);
+        let (models, materials) = (
+            2 * cube.models.len() + head.models.len(),
+            cube.materials.len() + head.materials.len(),
+        );
+        let merged = Scene::merge_all(vec![cube.clone(), head, cube]);
+        assert_eq!(merged.models.len(), models);
+        assert_eq!(merged.materials.len(), materials);
+        for model in merged.models.iter() {
+            assert!(std::sync::Arc::ptr_eq(
+                &merged.materials[model.material_index()],
+                &model.material()
+            ));
+        }
+    }
+
     #[test]
     fn check_model() {
         let scenes = load("tests/cube.glb").unwrap();
         let scene = &scenes[0];
         let model = &scene.models[0];
+        assert_eq!(model.mesh_index(), 0);
+        assert_eq!(model.primitive_index(), 0);
         assert!(model.has_normals());
         assert!(model.has_tex_coords());
         assert!(model.has_tangents());
@@ -193,6 +1114,57 @@ mod tests {
             // Check that the tangent w component is 1 or -1
             assert_eq!(t.tangent.w.abs(), 1.);
         }
+        assert!(scene.validate().is_empty());
+        for (raw, vertex) in model.raw_vertices().iter().zip(model.vertices()) {
+            assert_eq!(Vertex::from(*raw), *vertex);
+        }
+    }
+
+    #[test]
+    fn check_default_material() {
+        let scene = &load("tests/quantized.gltf").unwrap()[0];
+        assert!(std::sync::Arc::ptr_eq(
+            &scene.models[0].material(),
+            &scene.default_material
+        ));
+        assert_eq!(scene.default_material.pbr.roughness_factor, 1.);
+
+        let mut fallback = Material::default();
+        fallback.pbr.base_color_factor = Vector4::new(1., 0., 1., 1.);
+        let options = LoadOptions {
+            default_material: Some(std::sync::Arc::new(fallback)),
+            ..Default::default()
+        };
+        let scene = &load_with_options("tests/quantized.gltf", &options).unwrap()[0];
+        assert_eq!(scene.models[0].material().pbr.base_color_factor.y, 0.);
+    }
+
+    #[test]
+    fn check_placeholder_textures() {
+        let options = LoadOptions {
+            placeholder_textures: PlaceholderTextures::FromFactors,
+            ..Default::default()
+        };
+        let scene = &load_with_options("tests/cube.glb", &options).unwrap()[0];
+        let material = scene.models[0].material();
+        assert!(material.normal.is_some() && material.occlusion.is_some());
+        let roughness = material.pbr.roughness_texture.as_ref().unwrap();
+        assert_eq!(roughness.dimensions(), (1, 1));
+        assert_eq!(material.pbr.roughness_factor, 1.);
+    }
+
+    #[test]
+    fn check_material_override() {
+        let options = LoadOptions {
+            material_override: Some(|gltf_material, mut material| {
+                assert!(gltf_material.index().is_some());
+                material.pbr.metallic_factor = 0.5;
+                material
+            }),
+            ..Default::default()
+        };
+        let scene = &load_with_options("tests/head.glb", &options).unwrap()[0];
+        assert_eq!(scene.models[0].material().pbr.metallic_factor, 0.5);
     }
 
     #[test]
@@ -202,6 +1174,1039 @@ mod tests {
         let mat = &scene.models[0].material;
         assert!(mat.pbr.base_color_texture.is_some());
         assert_eq!(mat.pbr.metallic_factor, 0.);
+        let index = scene.models[0].material_index();
+        assert!(std::sync::Arc::ptr_eq(&scene.materials[index], mat));
+
+        let report = scene.memory_report();
+        let texture = mat.pbr.base_color_texture.as_ref().unwrap();
+        assert!(report.rgba8_textures >= (texture.width() * texture.height() * 4) as usize);
+        assert!(report.gpu_estimate > report.total());
+    }
+
+    #[test]
+    fn check_normal_convention() {
+        let mut mat = model::Material::gltf_default();
+        mat.normal = Some(model::NormalMap {
+            texture: std::sync::Arc::new(image::RgbImage::from_pixel(
+                1,
+                1,
+                image::Rgb([128, 200, 255]),
+            )),
+            factor: 1.,
+            convention: model::NormalConvention::OpenGl,
+            mapper: Default::default(),
+        });
+        let mut flipped = mat.clone();
+        flipped
+            .normal
+            .as_mut()
+            .unwrap()
+            .convert_to(model::NormalConvention::DirectX);
+        let normal = flipped.normal.as_ref().unwrap();
+        assert_eq!(normal.convention, model::NormalConvention::DirectX);
+        assert_eq!(normal.texture[(0, 0)][1], 55);
+        let coords = Vector2::new(0.5, 0.5);
+        assert_eq!(mat.get_normal(coords), flipped.get_normal(coords));
+    }
+
+    #[test]
+    fn check_shared_normal_map() {
+        // Two materials using the same normal map
+        let gltf = std::fs::read_to_string("tests/cube_classic.gltf").unwrap();
+        let mut json: serde_json::Value = serde_json::from_str(&gltf).unwrap();
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+        json["buffers"][0]["uri"] = serde_json::json!(dir.join("cube_classic.bin"));
+        json["images"][0]["uri"] = serde_json::json!(dir.join("cube.png"));
+        json["materials"][0]["normalTexture"] = serde_json::json!({ "index": 0 });
+        let mut material = json["materials"][0].clone();
+        material["pbrMetallicRoughness"]["roughnessFactor"] = serde_json::json!(1.);
+        json["materials"].as_array_mut().unwrap().push(material);
+        let mut mesh = json["meshes"][0].clone();
+        mesh["primitives"][0]["material"] = serde_json::json!(1);
+        json["meshes"].as_array_mut().unwrap().push(mesh);
+        let nodes = json["nodes"].as_array_mut().unwrap();
+        nodes.push(serde_json::json!({ "mesh": 1, "translation": [3., 0., 0.] }));
+        let node = nodes.len() - 1;
+        json["scenes"][0]["nodes"]
+            .as_array_mut()
+            .unwrap()
+            .push(serde_json::json!(node));
+        let path = std::env::temp_dir().join("easy_gltf_shared_normal.gltf");
+        std::fs::write(&path, json.to_string()).unwrap();
+
+        let options = LoadOptions {
+            normal_convention: model::NormalConvention::DirectX,
+            ..Default::default()
+        };
+        let scenes = load_with_options(&path, &options).unwrap();
+        let (a, b) = (
+            scenes[0].models[0].material(),
+            scenes[0].models[1].material(),
+        );
+        assert!(!std::sync::Arc::ptr_eq(&a, &b));
+        let (a, b) = (a.normal.as_ref().unwrap(), b.normal.as_ref().unwrap());
+        assert_eq!(a.convention, model::NormalConvention::DirectX);
+        assert!(std::sync::Arc::ptr_eq(&a.texture, &b.texture));
+        assert_eq!(scenes[0].memory_report().texture_count, 2);
+
+        let path = std::env::temp_dir().join("easy_gltf_shared_normal.glb");
+        save(&path, &scenes).unwrap();
+        assert_eq!(gltf::Gltf::open(&path).unwrap().images().count(), 2);
+    }
+
+    #[test]
+    fn check_mislabeled_normal_map() {
+        // DirectX normal map pointing up, exported as an OpenGL one
+        let mut mat = model::Material::gltf_default();
+        mat.normal = Some(model::NormalMap {
+            texture: std::sync::Arc::new(image::RgbImage::from_pixel(
+                1,
+                1,
+                image::Rgb([128, 55, 255]),
+            )),
+            factor: 1.,
+            convention: model::NormalConvention::OpenGl,
+            mapper: Default::default(),
+        });
+        let coords = Vector2::new(0.5, 0.5);
+        assert!(mat.get_normal(coords).unwrap().y < 0.);
+
+        let normal = mat.normal.as_mut().unwrap();
+        normal.convention = model::NormalConvention::DirectX;
+        assert!(mat.get_normal(coords).unwrap().y > 0.);
+
+        let normal = mat.normal.as_mut().unwrap();
+        normal.convert_to(model::NormalConvention::OpenGl);
+        assert_eq!(normal.texture[(0, 0)][1], 200);
+        assert!(mat.get_normal(coords).unwrap().y > 0.);
+    }
+
+    #[test]
+    fn check_occlusion_tex_coord() {
+        assert!(load("tests/cube.glb").unwrap()[0].models[0]
+            .tex_coords_1()
+            .is_none());
+        let mut mat = model::Material::gltf_default();
+        mat.occlusion = Some(model::Occlusion {
+            texture: std::sync::Arc::new(image::GrayImage::from_pixel(1, 1, image::Luma([255]))),
+            factor: 0.5,
+            mapper: model::Mapper {
+                tex_coord: 1,
+                ..Default::default()
+            },
+        });
+        let uv = Vector2::new(0.5, 0.5);
+        assert_eq!(mat.get_occlusion_from_sets(&[uv]), None);
+        assert_eq!(mat.get_occlusion_from_sets(&[uv, uv]), Some(0.5));
+    }
+
+    #[test]
+    fn check_save() {
+        let mut scenes = load("tests/cube.glb").unwrap();
+        // Empty models are skipped
+        scenes[0].models.push(crate::model::Model::default());
+        let path = std::env::temp_dir().join("easy_gltf_cube.glb");
+        save(&path, &scenes).unwrap();
+        let saved = load(&path).unwrap();
+        assert_eq!(saved[0].models.len(), scenes[0].models.len() - 1);
+        assert_eq!(saved[0].cameras.len(), 1);
+        assert_eq!(saved[0].lights.len(), 3);
+        assert!(
+            (saved[0].cameras[0].position() - scenes[0].cameras[0].position()).magnitude() < 0.01
+        );
+
+        let mut scenes = load("tests/head.glb").unwrap();
+        let material = std::sync::Arc::make_mut(&mut scenes[0].models[0].material);
+        material.alpha_mode = model::AlphaMode::Mask { cutoff: 0.25 };
+        material.double_sided = true;
+        material.pbr.base_color_mapper.offset = Vector2::new(0.5, 0.);
+        material.pbr.base_color_mapper.scale = Vector2::new(2., 2.);
+        material.pbr.base_color_mapper.rotation = 0.5;
+        for file in ["easy_gltf_head.glb", "easy_gltf_head.gltf"] {
+            let path = std::env::temp_dir().join(file);
+            save(&path, &scenes).unwrap();
+            let saved = load(&path).unwrap();
+            assert_eq!(saved.len(), scenes.len());
+            let (model, saved_model) = (&scenes[0].models[0], &saved[0].models[0]);
+            assert_eq!(saved_model.vertices().len(), model.vertices().len());
+            assert_eq!(saved_model.indices(), model.indices());
+            assert!(saved_model.material.pbr.base_color_texture.is_some());
+            let (material, saved_material) = (&model.material, &saved_model.material);
+            assert_eq!(saved_material.alpha_mode, material.alpha_mode);
+            assert!(saved_material.double_sided);
+            let (mapper, saved_mapper) = (
+                &material.pbr.base_color_mapper,
+                &saved_material.pbr.base_color_mapper,
+            );
+            assert_eq!(saved_mapper.offset, mapper.offset);
+            assert_eq!(saved_mapper.scale, mapper.scale);
+            assert_eq!(saved_mapper.rotation, mapper.rotation);
+        }
+
+        let material = std::sync::Arc::make_mut(&mut scenes[0].models[0].material);
+        material.alpha_mode = model::AlphaMode::Blend;
+        material.double_sided = false;
+        let path = std::env::temp_dir().join("easy_gltf_head_blend.glb");
+        save(&path, &scenes).unwrap();
+        let saved = load(&path).unwrap();
+        assert_eq!(
+            saved[0].models[0].material.alpha_mode,
+            model::AlphaMode::Blend
+        );
+        assert!(!saved[0].models[0].material.double_sided);
+    }
+
+    #[test]
+    fn check_export_stl() {
+        let scenes = load("tests/cube.glb").unwrap();
+        let path = std::env::temp_dir().join("easy_gltf_cube.stl");
+        scenes[0].models[0].export_stl(&path).unwrap();
+        let size = std::fs::metadata(&path).unwrap().len();
+        assert_eq!(size, 84 + 50 * 12);
+    }
+
+    #[test]
+    fn check_tangent_basis() {
+        let model = &load("tests/cube.glb").unwrap()[0].models[0];
+        for triangle in model.triangles().unwrap() {
+            let tbn = triangle.tangent_basis().unwrap();
+            assert!(tbn.z.dot(triangle[0].normal) > 0.99);
+            let tangent = triangle.tangent().unwrap();
+            assert!(tangent.truncate().dot(triangle[0].tangent.truncate()) > 0.99);
+            assert_eq!(tangent.w, triangle[0].tangent.w);
+        }
+        let degenerate = model::Triangle([Vertex::default(); 3]);
+        assert!(degenerate.tangent_basis().is_none());
+    }
+
+    #[test]
+    fn check_triangle_geometry() {
+        let model = &load("tests/cube.glb").unwrap()[0].models[0];
+        let triangles = model.triangles().unwrap();
+        let area: f32 = triangles.iter().map(|t| t.area()).sum();
+        assert!((area - 6.).abs() < 0.01);
+        assert!((model.surface_area() - area).abs() < 0.01);
+        assert!((model.signed_volume() - 1.).abs() < 0.01);
+        for t in triangles.iter() {
+            assert!(t.face_normal().unwrap().dot(t[0].normal) > 0.99);
+            let centroid = t.centroid();
+            assert!(centroid.x > -0.01 && centroid.x < 1.01);
+        }
+
+        let mut line = model::Line([Vertex::default(); 2]);
+        assert!(line.direction().is_none());
+        line[1].position = Vector3::new(0., 2., 0.);
+        assert_eq!(line.length(), 2.);
+        assert_eq!(line.direction(), Some(Vector3::unit_y()));
+        assert_eq!(line.midpoint(), Vector3::unit_y());
+    }
+
+    #[test]
+    fn check_vertex_bytes() {
+        let model = &load("tests/cube.glb").unwrap()[0].models[0];
+        let layout = model.vertex_layout();
+        let bytes = model.vertex_bytes();
+        assert_eq!(bytes.len(), model.vertices().len() * layout.stride);
+        let last = layout.attributes.last().unwrap();
+        assert_eq!(last.offset + last.format.size(), layout.stride);
+        let tex_coords = &layout.attributes[3];
+        let offset = layout.stride + tex_coords.offset;
+        let u = f32::from_ne_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ]);
+        assert_eq!(u, model.vertices()[1].tex_coords.x);
+
+        assert_eq!(model.pack_vertices(&layout), bytes);
+        let layout = model::VertexLayout::packed(&[
+            model::VertexAttribute::TexCoords,
+            model::VertexAttribute::Position,
+        ]);
+        let packed = model.pack_vertices(&layout);
+        assert_eq!(packed.len(), model.vertices().len() * 20);
+        assert_eq!(packed[20..28], bytes[offset..offset + 8]);
+    }
+
+    #[test]
+    fn check_line_list_indices() {
+        let mut model = model::Model {
+            vertices: std::sync::Arc::new(vec![Vertex::default(); 3]),
+            mode: Mode::LineLoop,
+            ..Default::default()
+        };
+        assert_eq!(model.line_list_indices().unwrap(), vec![0, 1, 1, 2, 2, 0]);
+        assert_eq!(model.lines().unwrap().len(), 3);
+        model.mode = Mode::LineStrip;
+        assert_eq!(model.line_list_indices().unwrap(), vec![0, 1, 1, 2]);
+        model.mode = Mode::Triangles;
+        assert!(model.line_list_indices().is_err());
+    }
+
+    #[test]
+    fn check_premultiply_alpha() {
+        let mut image =
+            image::RgbaImage::from_raw(2, 1, vec![255, 188, 0, 128, 10, 20, 30, 255]).unwrap();
+        utils::premultiply_alpha(&mut image);
+        // Half of linear white and ~half of linear 0.5 (188 in sRGB)
+        assert_eq!(image.get_pixel(0, 0).0, [188, 138, 0, 128]);
+        assert_eq!(image.get_pixel(1, 0).0, [10, 20, 30, 255]);
+
+        let options = LoadOptions {
+            premultiply_alpha: true,
+            ..Default::default()
+        };
+        let scenes = load_with_options("tests/head.glb", &options).unwrap();
+        let texture = scenes[0].materials[0].pbr.base_color_texture.as_ref();
+        assert!(texture.is_some());
+    }
+
+    #[test]
+    fn check_alpha_mask() {
+        let scenes = load("tests/complete.glb").unwrap();
+        let modes: Vec<_> = scenes[0].materials.iter().map(|m| m.alpha_mode).collect();
+        assert!(modes.contains(&model::AlphaMode::Blend));
+
+        let options = LoadOptions {
+            material_override: Some(|_, mut material| {
+                material.alpha_mode = model::AlphaMode::Mask { cutoff: 0.5 };
+                material
+            }),
+            alpha_masks: true,
+            ..Default::default()
+        };
+        let scenes = load_with_options("tests/complete.glb", &options).unwrap();
+        let material = scenes[0]
+            .materials
+            .iter()
+            .find(|m| m.pbr.base_color_texture.is_some())
+            .unwrap();
+        let texture = material.pbr.base_color_texture.as_ref().unwrap();
+        let mask = material.alpha_mask.as_ref().unwrap();
+        assert_eq!(mask.dimensions(), texture.dimensions());
+        for (alpha, mask) in texture.pixels().zip(mask.pixels()) {
+            let visible = alpha[3] as f32 / 255. * material.pbr.base_color_factor.w >= 0.5;
+            assert_eq!(mask[0] == 255, visible);
+        }
+    }
+
+    #[test]
+    fn check_alpha_class() {
+        let mut material = model::Material {
+            alpha_mode: model::AlphaMode::Blend,
+            ..Default::default()
+        };
+        assert!(!material.needs_blending());
+        material.pbr.base_color_factor.w = 0.5;
+        assert!(material.needs_blending());
+        material.alpha_mode = model::AlphaMode::Mask { cutoff: 0.6 };
+        assert_eq!(material.alpha_class(), model::AlphaClass::AlphaTested);
+    }
+
+    #[test]
+    fn check_get_alpha() {
+        let mut material = model::Material {
+            alpha_mode: model::AlphaMode::Mask { cutoff: 0.6 },
+            ..Default::default()
+        };
+        material.pbr.base_color_factor.w = 0.5;
+        assert_eq!(material.get_alpha(Vector2::new(0., 0.)), 0.);
+        material.alpha_mode = model::AlphaMode::Blend;
+        assert_eq!(material.get_alpha(Vector2::new(0., 0.)), 0.5);
+    }
+
+    #[cfg(feature = "wgpu")]
+    #[test]
+    fn check_padded_rows() {
+        let image = image::GrayImage::from_raw(3, 2, vec![1, 2, 3, 4, 5, 6]).unwrap();
+        let (data, bytes_per_row) = wgpu::padded_rows(&image, 4);
+        assert_eq!(bytes_per_row, 4);
+        assert_eq!(data, vec![1, 2, 3, 0, 4, 5, 6, 0]);
+    }
+
+    #[test]
+    fn check_texture_transform() {
+        let texture = image::RgbaImage::from_raw(2, 1, vec![255, 0, 0, 255, 0, 255, 0, 255]);
+        let mut material = model::Material::default();
+        material.pbr.base_color_texture = texture.map(std::sync::Arc::new);
+        material.pbr.base_color_mapper.offset = Vector2::new(0.5, 0.);
+
+        let uv = Vector2::new(0.1, 0.);
+        assert_eq!(material.get_base_color(uv), Vector3::new(0., 1., 0.));
+        let raw = material.without_texture_transforms();
+        assert_eq!(raw.get_base_color(uv), Vector3::new(1., 0., 0.));
+    }
+
+    #[test]
+    fn check_normal_occlusion_transform() {
+        let extension = serde_json::json!({ "offset": [0.5, 0.], "texCoord": 1 });
+        let mapper = model::Mapper::from_extension(0, Some(&extension));
+        assert_eq!(mapper.tex_coord, 1);
+        assert_eq!(mapper.offset, Vector2::new(0.5, 0.));
+        assert_eq!(mapper.scale, Vector2::new(1., 1.));
+
+        let texture = image::GrayImage::from_raw(2, 1, vec![255, 0]).unwrap();
+        let material = model::Material {
+            occlusion: Some(model::Occlusion {
+                texture: std::sync::Arc::new(texture),
+                factor: 1.,
+                mapper,
+            }),
+            ..Default::default()
+        };
+        let uv = Vector2::new(0.1, 0.);
+        assert_eq!(material.get_occlusion(uv), Some(0.));
+        assert_eq!(material.get_occlusion_from_sets(&[uv, uv]), Some(0.));
+        let raw = material.without_texture_transforms();
+        assert_eq!(raw.get_occlusion(uv), Some(1.));
+    }
+
+    #[test]
+    fn check_material_eq() {
+        let scenes = load("tests/complete.glb").unwrap();
+        let materials = &scenes[0].materials;
+        let copy = materials[0].as_ref().clone();
+        assert_eq!(&copy, materials[0].as_ref());
+        let mut set = std::collections::HashSet::new();
+        set.insert(copy);
+        assert!(set.contains(materials[0].as_ref()));
+
+        let mut other = materials[0].as_ref().clone();
+        other.double_sided = !other.double_sided;
+        assert_ne!(&other, materials[0].as_ref());
+        assert_ne!(other.render_sort_key(), materials[0].render_sort_key());
+
+        let blended = model::Material {
+            alpha_mode: model::AlphaMode::Blend,
+            pbr: model::PbrMaterial {
+                base_color_factor: Vector4::new(1., 1., 1., 0.5),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(blended.render_sort_key() > model::Material::default().render_sort_key());
+    }
+
+    #[test]
+    fn check_split() {
+        let scenes = load("tests/head.glb").unwrap();
+        let model = &scenes[0].models[0];
+        let parts = model.split(500);
+        assert!(parts.len() > 1);
+        let mut triangles = 0;
+        for part in parts.iter() {
+            assert!(part.vertices().len() <= 500);
+            triangles += part.triangles().unwrap().len();
+        }
+        assert_eq!(triangles, model.triangles().unwrap().len());
+        assert_eq!(
+            parts[0].triangles().unwrap()[0],
+            model.triangles().unwrap()[0]
+        );
+        assert_eq!(model.split(usize::MAX).len(), 1);
+    }
+
+    #[test]
+    fn check_flip_winding() {
+        let scenes = load("tests/cube.glb").unwrap();
+        let model = &scenes[0].models[0];
+        let mut flipped = model.clone();
+        flipped.flip_winding();
+        let (triangles, flipped_triangles) =
+            (model.triangles().unwrap(), flipped.triangles().unwrap());
+        for (a, b) in triangles.iter().zip(flipped_triangles.iter()) {
+            let (na, nb) = (a.face_normal().unwrap(), b.face_normal().unwrap());
+            assert!((na + nb).magnitude() < 0.001);
+            assert_eq!(a[0].tangent.w, -b[0].tangent.w);
+        }
+
+        // Even strips, indexed or not
+        let positions = [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [1., 1., 0.]];
+        let vertices: Vec<_> = positions
+            .iter()
+            .map(|&position| Vertex {
+                position: position.into(),
+                ..Default::default()
+            })
+            .collect();
+        for indices in [None, Some(vec![0, 1, 2, 3])] {
+            let mut strip = model::Model {
+                mode: Mode::TriangleStrip,
+                vertices: std::sync::Arc::new(vertices.clone()),
+                indices: indices.map(std::sync::Arc::new),
+                ..Default::default()
+            };
+            let normals = |strip: &model::Model| -> Vec<_> {
+                let triangles = strip.triangles().unwrap();
+                triangles.iter().filter_map(|t| t.face_normal()).collect()
+            };
+            assert!(normals(&strip).iter().all(|n| n.z > 0.));
+            strip.flip_winding();
+            let flipped = normals(&strip);
+            assert_eq!(flipped.len(), 2);
+            assert!(flipped.iter().all(|n| n.z < 0.));
+        }
+
+        let mut lines = model.clone();
+        lines.mode = Mode::Lines;
+        lines.flip_winding();
+        assert_eq!(lines.vertices(), model.vertices());
+    }
+
+    #[test]
+    fn check_flip_winding_influences() {
+        let model = &load("tests/skin.gltf").unwrap()[0].models[0];
+        assert!(model.indices().is_none());
+        let mut flipped = model.clone();
+        flipped.flip_winding();
+        // Influences stay on their vertex
+        let influences = |model: &model::Model, i: usize| {
+            (
+                model.vertices()[i].position,
+                model.joints().unwrap()[i],
+                model.weights().unwrap()[i],
+            )
+        };
+        assert_eq!(influences(&flipped, 1), influences(model, 2));
+        assert_eq!(influences(&flipped, 2), influences(model, 1));
+    }
+
+    #[test]
+    fn check_fit_unit_cube() {
+        let scenes = load("tests/head.glb").unwrap();
+        let mut model = scenes[0].models[0].clone();
+        let transform = model.fit_unit_cube();
+        let original = scenes[0].models[0].vertices()[0].position;
+        let moved = transform * original.extend(1.);
+        assert!((moved.truncate() - model.vertices()[0].position).magnitude() < 1e-5);
+
+        let mut min = Vector3::new(f32::MAX, f32::MAX, f32::MAX);
+        let mut max = -min;
+        for v in model.vertices() {
+            for i in 0..3 {
+                min[i] = min[i].min(v.position[i]);
+                max[i] = max[i].max(v.position[i]);
+            }
+        }
+        let size = max - min;
+        assert!((size.x.max(size.y).max(size.z) - 1.).abs() < 1e-5);
+        assert!((min + max).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn check_lods() {
+        let scenes = load("tests/head.glb").unwrap();
+        let model = &scenes[0].models[0];
+        let count = model.triangles().unwrap().len();
+        let lods = model.generate_lods(&[1., 0.5, 0.1]).unwrap();
+        assert_eq!(lods[0].error, 0.);
+        assert_eq!(lods[0].model.triangles().unwrap().len(), count);
+        let mut previous = count;
+        for lod in lods[1..].iter() {
+            let triangles = lod.model.triangles().unwrap().len();
+            assert!(triangles > 0 && triangles < previous);
+            assert!(lod.error > 0. && lod.error < 1.);
+            previous = triangles;
+        }
+        assert!(previous as f32 <= count as f32 * 0.1 + 1.);
+
+        // Full detail is an indexed triangle list too
+        let strip = model::Model {
+            vertices: std::sync::Arc::new(model.vertices()[..4].to_vec()),
+            indices: None,
+            mode: Mode::TriangleStrip,
+            ..model.clone()
+        };
+        let lod = &strip.generate_lods(&[1.]).unwrap()[0].model;
+        assert_eq!(lod.mode(), Mode::Triangles);
+        assert_eq!(lod.indices().unwrap().len(), 6);
+        assert!(std::sync::Arc::ptr_eq(
+            lod.shared_vertices(),
+            strip.shared_vertices()
+        ));
+    }
+
+    #[test]
+    fn check_main_camera() {
+        let scenes = load("tests/head.glb").unwrap();
+        assert_eq!(
+            scenes[0].main_camera().node_index,
+            scenes[0].cameras[0].node_index
+        );
+
+        let mut scene = load("tests/cube.glb").unwrap().remove(0);
+        scene.cameras.clear();
+        let camera = scene.main_camera();
+        assert_eq!(camera.node_index, usize::MAX);
+        let (min, max) = scene.models[0].bounds().unwrap();
+        let center = (min + max) / 2.;
+        assert!((camera.position().truncate() - center.truncate()).magnitude() < 1e-5);
+        assert!(camera.position().z > max.z);
+        assert!(camera.znear > 0. && camera.zfar.is_finite());
+    }
+
+    #[test]
+    fn check_retain_models() {
+        let mut scene = load("tests/complete.glb").unwrap().remove(0);
+        let count = scene.models.len();
+        let first = scene.models[0].material();
+        let removed =
+            scene.remove_models(|model| std::sync::Arc::ptr_eq(&model.material(), &first));
+        assert!(!removed.is_empty());
+        assert_eq!(scene.models.len() + removed.len(), count);
+        assert!(!scene
+            .materials
+            .iter()
+            .any(|m| std::sync::Arc::ptr_eq(m, &first)));
+        for model in scene.models.iter() {
+            assert!(std::sync::Arc::ptr_eq(
+                &scene.materials[model.material_index()],
+                &model.material()
+            ));
+        }
+        scene.retain_models(|_| false);
+        assert!(scene.models.is_empty() && scene.materials.is_empty());
+    }
+
+    #[test]
+    fn check_markers() {
+        let scenes = load("tests/markers.gltf").unwrap();
+        let markers = &scenes[0].markers;
+        assert_eq!(markers.len(), 3);
+        assert_eq!(markers[0].node_index, 1);
+        assert_eq!(markers[0].position(), Vector3::new(2., 0., 6.));
+        // Socket holding a mesh, then the grouping node
+        assert_eq!(markers[1].node_index, 2);
+        assert_eq!(markers[1].position(), Vector3::new(0., 4., 0.));
+        assert_eq!(scenes[0].models[0].node_index(), 3);
+        assert_eq!(markers[2].node_index, 0);
+        #[cfg(feature = "names")]
+        assert_eq!(markers[0].name.as_deref(), Some("Spawn"));
+        #[cfg(feature = "extras")]
+        assert!(markers[0].extras.as_ref().unwrap().get().contains("red"));
+
+        // Joints are not markers
+        assert!(load("tests/skin.gltf").unwrap()[0].markers.is_empty());
+    }
+
+    #[test]
+    fn check_node_metadata() {
+        let scenes = load("tests/cube_classic.gltf").unwrap();
+        let light = &scenes[0].lights[0];
+        assert_eq!(light.node_index, 1);
+        #[cfg(feature = "names")]
+        {
+            assert_eq!(light.node_name.as_deref(), Some("Light_Orientation"));
+            assert_eq!(scenes[0].models[0].node_name(), Some("Cube"));
+        }
+
+        #[cfg(feature = "extras")]
+        {
+            let scenes = load("tests/box_sparse.glb").unwrap();
+            let extras = scenes[0].models[0].node_extras().as_ref().unwrap();
+            assert_eq!(extras.get(), "{}");
+        }
+    }
+
+    #[test]
+    fn check_custom_cache() {
+        use crate::cache::{CachedImage, ImageCache, ImageKey};
+
+        #[derive(Debug, Default)]
+        struct Counting(usize);
+
+        impl ImageCache for Counting {
+            fn get(&mut self, _key: &ImageKey) -> Option<CachedImage> {
+                None
+            }
+
+            fn insert(&mut self, _key: ImageKey, image: CachedImage) -> CachedImage {
+                self.0 += 1;
+                image
+            }
+
+            fn clear(&mut self) {}
+        }
+
+        let cache = Counting::default();
+        let mut loader = Loader::with_cache(Default::default(), cache);
+        let scenes = loader.load("tests/cube_classic.gltf").unwrap();
+        assert!(scenes[0].models[0]
+            .material()
+            .pbr
+            .base_color_texture
+            .is_some());
+        assert!(format!("{:?}", loader).contains("Counting(1)"));
+    }
+
+    #[test]
+    fn check_image_consumer() {
+        use crate::cache::{CachedImage, ImageConsumer};
+        use crate::model::TextureSlot;
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        #[derive(Debug, Default)]
+        struct Counter(AtomicU64);
+
+        impl ImageConsumer for Counter {
+            fn consume(&self, _slot: TextureSlot, _image: CachedImage) -> u64 {
+                self.0.fetch_add(1, Ordering::Relaxed)
+            }
+        }
+
+        let options = LoadOptions {
+            placeholder_textures: PlaceholderTextures::Neutral,
+            image_consumer: Some(std::sync::Arc::new(Counter::default())),
+            ..Default::default()
+        };
+        let scenes = load_with_options("tests/cube.glb", &options).unwrap();
+        let material = scenes[0].models[0].material();
+        for slot in TextureSlot::ALL.iter().copied() {
+            let expected = slot != TextureSlot::AlphaMask;
+            assert_eq!(material.texture_handle(slot).is_some(), expected);
+        }
+        assert!(material.pbr.base_color_texture.is_none());
+        assert_eq!(
+            material.normal.as_ref().unwrap().texture.dimensions(),
+            (1, 1)
+        );
+
+        // Two materials with the same base color image get the same handle
+        let dir = std::env::temp_dir().join("easy_gltf_image_consumer");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy("tests/cube_classic.bin", dir.join("cube_classic.bin")).unwrap();
+        std::fs::copy("tests/cube.png", dir.join("cube.png")).unwrap();
+        let gltf = std::fs::read_to_string("tests/cube_classic.gltf").unwrap();
+        let mut json: serde_json::Value = serde_json::from_str(&gltf).unwrap();
+        let mut material = json["materials"][0].clone();
+        material["pbrMetallicRoughness"]["metallicFactor"] = 0.5.into();
+        json["materials"].as_array_mut().unwrap().push(material);
+        let mut primitive = json["meshes"][0]["primitives"][0].clone();
+        primitive["material"] = 1.into();
+        json["meshes"][0]["primitives"]
+            .as_array_mut()
+            .unwrap()
+            .push(primitive);
+        let path = dir.join("cube.gltf");
+        std::fs::write(&path, json.to_string()).unwrap();
+        let options = LoadOptions {
+            image_consumer: Some(std::sync::Arc::new(Counter::default())),
+            ..Default::default()
+        };
+        let scenes = load_with_options(&path, &options).unwrap();
+        let handle = |i: usize| {
+            let material = scenes[0].models[i].material();
+            material.texture_handle(TextureSlot::BaseColor).unwrap()
+        };
+        assert_eq!(handle(0), handle(1));
+    }
+
+    #[test]
+    fn check_skinned_vertex_bytes() {
+        let scenes = load("tests/skin.gltf").unwrap();
+        let model = &scenes[0].models[0];
+        let layout = model::VertexLayout::of_skinned_vertex();
+        let bytes = model.skinned_vertex_bytes().unwrap();
+        let vertex = &bytes[layout.stride..][..layout.stride];
+        let offset = |attribute| {
+            let layout = layout.attributes.iter().find(|a| a.attribute == attribute);
+            layout.unwrap().offset
+        };
+        let joints = offset(model::VertexAttribute::Joints);
+        assert_eq!(vertex[joints..joints + 4], [0, 0, 1, 0]);
+        let weights = offset(model::VertexAttribute::Weights);
+        assert_eq!(vertex[weights..weights + 4], 0.5f32.to_ne_bytes());
+        assert!(load("tests/cube.glb").unwrap()[0].models[0]
+            .skinned_vertex_bytes()
+            .is_none());
+    }
+
+    #[test]
+    fn check_max_influences() {
+        let options = LoadOptions {
+            max_influences: 1,
+            ..Default::default()
+        };
+        let scenes = load_with_options("tests/skin.gltf", &options).unwrap();
+        let model = &scenes[0].models[0];
+        assert_eq!(model.weights().unwrap()[1], [1., 0., 0., 0.]);
+        assert_eq!(model.joints().unwrap()[1], [0; 4]);
+        assert_eq!(
+            model.validate(),
+            [Issue::DroppedInfluences {
+                count: 1,
+                max_weight: 0.5
+            }]
+        );
+    }
+
+    #[cfg(feature = "parry3d")]
+    #[test]
+    fn check_parry3d() {
+        let scenes = load("tests/cube.glb").unwrap();
+        let model = &scenes[0].models[0];
+        let mesh = parry3d::trimesh(model).unwrap();
+        assert_eq!(mesh.indices().len(), model.triangles().unwrap().len());
+        let shapes = parry3d::shapes(&scenes[0]);
+        assert_eq!(shapes.len(), scenes[0].models.len());
+        let (isometry, shape) = &shapes[0];
+        let aabb = shape.compute_aabb(isometry);
+        let (min, max) = model.bounds().unwrap();
+        assert!((aabb.mins.x - min.x).abs() < 1e-4 && (aabb.maxs.y - max.y).abs() < 1e-4);
+    }
+
+    #[test]
+    fn check_mode_conversions() {
+        let scenes = load("tests/cube.glb").unwrap();
+        let mode = scenes[0].models[0].mode();
+        assert_eq!(mode.as_gl_enum(), 4);
+        assert_eq!(Mode::from(gltf::mesh::Mode::from(mode)), mode);
+        assert_eq!(Mode::LineStrip.as_gl_enum(), 3);
+    }
+
+    #[test]
+    fn check_bad_mode() {
+        let scenes = load("tests/cube.glb").unwrap();
+        let error = scenes[0].models[0].lines().unwrap_err();
+        assert_eq!(error.method, "lines");
+        assert_eq!(error.mode, Mode::Triangles);
+        let message = error.to_string();
+        assert!(message.contains("lines()") && message.ends_with("primitive #0"));
+        #[cfg(feature = "names")]
+        assert!(message.contains("\"Cube.002\" #0"));
+        let _: Box<dyn Error> = Box::new(error);
+    }
+
+    #[test]
+    fn check_stream_buffers() {
+        let loaded = load("tests/cube_classic.gltf").unwrap();
+        for threads in [1, 2] {
+            let options = LoadOptions {
+                stream_buffers: true,
+                threads,
+                ..Default::default()
+            };
+            let streamed = load_with_options("tests/cube_classic.gltf", &options).unwrap();
+            let (a, b) = (&loaded[0].models[0], &streamed[0].models[0]);
+            assert_eq!(a.vertices(), b.vertices());
+            assert_eq!(a.indices(), b.indices());
+        }
+    }
+
+    #[test]
+    fn check_stream_buffers_error() {
+        let dir = std::env::temp_dir().join("easy_gltf_stream_error");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy("tests/cube_classic.gltf", dir.join("cube.gltf")).unwrap();
+        std::fs::copy("tests/cube_classic.bin", dir.join("cube_classic.bin")).unwrap();
+        std::fs::copy("tests/cube.png", dir.join("cube.png")).unwrap();
+        let options = LoadOptions {
+            stream_buffers: true,
+            ..Default::default()
+        };
+        let items = load_iter_with_options(dir.join("cube.gltf"), &options).unwrap();
+        // Truncated once imported, the buffer is only read by the iteration
+        std::fs::write(dir.join("cube_classic.bin"), b"").unwrap();
+        assert!(items.collect::<gltf::Result<Vec<_>>>().is_err());
+    }
+
+    #[test]
+    fn check_threads() {
+        let sequential = load("tests/complete.glb").unwrap();
+        let options = LoadOptions {
+            threads: 4,
+            ..Default::default()
+        };
+        let parallel = load_with_options("tests/complete.glb", &options).unwrap();
+        let (sequential, parallel) = (&sequential[0], &parallel[0]);
+        assert_eq!(sequential.models.len(), parallel.models.len());
+        for (a, b) in sequential.models.iter().zip(parallel.models.iter()) {
+            assert_eq!(a.vertices(), b.vertices());
+            assert_eq!(a.indices(), b.indices());
+            // Textures are compared by identity, each load has its own
+            let (a_pbr, b_pbr) = (&a.material().pbr, &b.material().pbr);
+            assert_eq!(a_pbr.base_color_factor, b_pbr.base_color_factor);
+            assert_eq!(a.material_index, b.material_index);
+        }
+        assert_eq!(sequential.materials.len(), parallel.materials.len());
+        let scenes = load_with_options("tests/cube_classic.gltf", &options).unwrap();
+        assert!(!scenes[0].models[0].vertices().is_empty());
+    }
+
+    #[test]
+    fn check_normalize_directions() {
+        // The cube node is scaled by 0.5, scaling normals by 2
+        let options = LoadOptions {
+            normalize_directions: false,
+            ..Default::default()
+        };
+        let scenes = load_with_options("tests/cube.glb", &options).unwrap();
+        let normal = scenes[0].models[0].vertices()[0].normal;
+        assert!((normal.magnitude() - 2.).abs() < 1e-4);
+        let scenes = load("tests/cube.glb").unwrap();
+        let normal = scenes[0].models[0].vertices()[0].normal;
+        assert!((normal.magnitude() - 1.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn check_scratch_reuse() {
+        let (doc, buffers, _) = gltf::import("tests/head.glb").unwrap();
+        let accessors: Vec<_> = doc
+            .meshes()
+            .flat_map(|mesh| mesh.primitives().collect::<Vec<_>>())
+            .filter_map(|primitive| primitive.get(&gltf::Semantic::Positions))
+            .collect();
+        let buffers = utils::BufferData::from(buffers);
+        let mut positions: Vec<[f32; 3]> = vec![];
+        assert!(utils::read_f32_into(
+            &accessors[0],
+            &buffers,
+            &mut positions
+        ));
+        assert_eq!(positions.capacity(), accessors[0].count());
+        let ptr = positions.as_ptr();
+        assert!(utils::read_f32_into(
+            &accessors[0],
+            &buffers,
+            &mut positions
+        ));
+        assert_eq!(positions.as_ptr(), ptr);
+        assert_eq!(positions.len(), accessors[0].count());
+    }
+
+    #[test]
+    fn check_read_glb() {
+        let bytes = std::fs::read("tests/cube.glb").unwrap();
+        let gltf = gltf::Gltf::from_slice_without_validation(&bytes).unwrap();
+        let (doc, blob) = utils::read_gltf(std::io::Cursor::new(&bytes)).unwrap();
+        assert_eq!(blob, gltf.blob);
+        assert_eq!(doc.meshes().count(), gltf.meshes().count());
+        let truncated = std::io::Cursor::new(&bytes[..bytes.len() - 8]);
+        assert!(utils::read_gltf(truncated).is_err());
+    }
+
+    #[test]
+    fn check_degenerate_primitives() {
+        let scenes = load("tests/degenerate.gltf").unwrap();
+        let models = &scenes[0].models;
+        assert_eq!(models.len(), 3);
+        for model in models.iter() {
+            assert!(model.triangles().unwrap().is_empty());
+            assert!(model.convex_hull().is_none());
+            assert!(model
+                .validate()
+                .contains(&Issue::IncompletePrimitive { count: 2 }));
+        }
+        let empty = model::Model::default();
+        assert!(empty.triangles().unwrap().is_empty());
+        assert_eq!(empty.validate(), [Issue::EmptyPrimitive]);
+    }
+
+    #[test]
+    fn check_convex_hull() {
+        let scenes = load("tests/cube.glb").unwrap();
+        let model = &scenes[0].models[0];
+        let hull = model.convex_hull().unwrap();
+        assert_eq!(hull.vertices().len(), 8);
+        assert_eq!(hull.indices().unwrap().len(), 12 * 3);
+        assert!((hull.signed_volume() - model.signed_volume()).abs() < 1e-4);
+        let mut plane = model.clone();
+        plane
+            .vertices_mut()
+            .iter_mut()
+            .for_each(|v| v.position.y = 0.);
+        assert!(plane.convex_hull().is_none());
+    }
+
+    #[test]
+    fn check_index_buffer() {
+        let model = &load("tests/cube.glb").unwrap()[0].models[0];
+        let buffer = model.index_buffer().unwrap();
+        assert_eq!(buffer.index_size(), 2);
+        assert_eq!(buffer.len(), 36);
+        assert_eq!(buffer.as_bytes().len(), 72);
+        let expected: Vec<u16> = model.indices().unwrap().iter().map(|&i| i as u16).collect();
+        assert_eq!(buffer, model::IndexBuffer::U16(expected));
+        assert!(model.to_non_indexed().index_buffer().is_none());
+
+        // 0xFFFF is the primitive restart index
+        let large = model::Model {
+            vertices: std::sync::Arc::new(vec![Default::default(); u16::MAX as usize + 1]),
+            indices: Some(std::sync::Arc::new(vec![0, 1, u16::MAX as u32])),
+            ..Default::default()
+        };
+        assert_eq!(large.index_buffer().unwrap().index_size(), 4);
+        let invalid = model::Model {
+            indices: Some(std::sync::Arc::new(vec![0, 1, 70000])),
+            ..model.clone()
+        };
+        assert_eq!(invalid.index_buffer().unwrap().index_size(), 4);
+    }
+
+    #[test]
+    fn check_normal_matrix() {
+        let transform = Matrix4::from_nonuniform_scale(2., 1., 1.);
+        let normal = utils::normal_matrix(&transform) * Vector3::new(1., 1., 0.).normalize();
+        // The plane x + y = 0 becomes x / 2 + y = 0 once scaled
+        assert!((normal.normalize() - Vector3::new(1., 2., 0.).normalize()).magnitude() < 0.001);
+    }
+
+    #[test]
+    fn check_transform_points() {
+        let transform =
+            Matrix4::from_translation(Vector3::new(1., 2., 3.)) * Matrix4::from_scale(2.);
+        let mut points = [[1., 0., 0.], [0., 1., 1.]];
+        utils::transform_points(&mut points, &transform);
+        assert_eq!(points, [[3., 2., 3.], [1., 4., 5.]]);
+
+        let mut normals = [[2., 0., 0., -1.]];
+        utils::transform_directions(&mut normals, &Matrix3::from_angle_z(Deg(90.)));
+        assert!((Vector4::from(normals[0]) - Vector4::new(0., 1., 0., -1.)).magnitude() < 0.001);
+    }
+
+    #[test]
+    fn check_validate() {
+        let mut model = load("tests/cube.glb").unwrap()[0].models[0].clone();
+        model.vertices_mut()[0].position.x = f32::NAN;
+        model.indices_mut().unwrap().push(1000);
+        let issues = model.validate();
+        assert!(issues.contains(&Issue::NonFinitePositions { count: 1 }));
+        assert!(issues.contains(&Issue::IndicesOutOfRange { count: 1 }));
+        assert!(issues.contains(&Issue::IncompletePrimitive { count: 37 }));
+    }
+
+    #[test]
+    fn check_shared_geometry() {
+        let scene_model = load("tests/cube.glb").unwrap()[0].models[0].clone();
+        let mut model = scene_model.clone();
+        assert!(std::sync::Arc::ptr_eq(
+            model.shared_vertices(),
+            scene_model.shared_vertices()
+        ));
+        // Copied on write
+        model.vertices_mut()[0].position.x = f32::NAN;
+        model.indices_mut().unwrap().push(1000);
+        assert!(!std::sync::Arc::ptr_eq(
+            model.shared_vertices(),
+            scene_model.shared_vertices()
+        ));
+        assert_ne!(model.vertices(), scene_model.vertices());
+        assert_ne!(model.indices(), scene_model.indices());
+    }
+
+    #[test]
+    fn check_clamped_tex_coords() {
+        let mut model = load("tests/cube_classic.gltf").unwrap()[0].models[0].clone();
+        model.vertices_mut()[0].tex_coords.x = 5.;
+        // Tiling textures use coordinates outside [0, 1] on purpose
+        let issue = Issue::TexCoordsOutOfRange { count: 1 };
+        assert!(model.material().pbr.base_color_mapper.repeat);
+        assert!(!model.validate().contains(&issue));
+        let mut material = model.material().as_ref().clone();
+        material.pbr.base_color_mapper.repeat = false;
+        model.material = std::sync::Arc::new(material);
+        assert!(model.validate().contains(&issue));
     }
 
     #[test]
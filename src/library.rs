@@ -0,0 +1,34 @@
+use crate::utils::GltfData;
+use crate::Material;
+use image::RgbaImage;
+use std::sync::Arc;
+
+/// Every material and image of a glTF document, whether or not a scene uses
+/// them. Useful for material libraries stored in files with empty scenes.
+///
+/// Created by [`load_library`](fn.load_library.html).
+#[derive(Clone, Debug, Default)]
+pub struct Library {
+    /// Materials of the document, in the order of the glTF `materials` array.
+    pub materials: Vec<Arc<Material>>,
+
+    /// Images of the document as RGBA, in the order of the glTF `images` array.
+    /// Materials using an image share the same `Arc` only for base color
+    /// textures, other textures are decoded to their own pixel format.
+    pub images: Vec<Arc<RgbaImage>>,
+}
+
+impl Library {
+    pub(crate) fn load(doc: &gltf::Document, data: &mut GltfData) -> Self {
+        Self {
+            materials: doc
+                .materials()
+                .map(|material| Material::load(material, data))
+                .collect(),
+            images: doc
+                .images()
+                .map(|image| data.load_rgba_image(&image))
+                .collect(),
+        }
+    }
+}
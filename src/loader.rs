@@ -0,0 +1,183 @@
+use crate::cache::{ImageCache, MemoryImageCache};
+use crate::utils::GltfData;
+use crate::{import, LoadOptions, Scene, SceneItem, Traversal};
+use std::error::Error;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+
+/// Loads several glTF files sharing a cache of decoded images.
+///
+/// Images referenced by an external file (e.g. `textures/wood.png`) are decoded
+/// once for the whole session, every material using them shares the same
+/// `Arc`. This is useful for kit assets where many files use the same textures.
+/// Images embedded in a file are never shared.
+///
+/// The images are kept in memory by default, see
+/// [`with_cache`](#method.with_cache) to store them elsewhere.
+///
+/// # Example
+///
+/// ```
+/// let mut loader = easy_gltf::Loader::default();
+/// let head = loader.load("tests/head.glb").expect("Failed to load glTF");
+/// let cube = loader.load("tests/cube_classic.gltf").expect("Failed to load glTF");
+/// ```
+#[derive(Debug)]
+pub struct Loader {
+    /// Options used to load every file.
+    pub options: LoadOptions,
+    images: Arc<Mutex<dyn ImageCache>>,
+}
+
+impl Default for Loader {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl Loader {
+    /// Create a loader with custom [`LoadOptions`](struct.LoadOptions.html).
+    pub fn new(options: LoadOptions) -> Self {
+        Self::with_cache(options, MemoryImageCache::default())
+    }
+
+    /// Create a loader storing the decoded images in a custom
+    /// [`ImageCache`](cache/trait.ImageCache.html).
+    pub fn with_cache<C>(options: LoadOptions, cache: C) -> Self
+    where
+        C: ImageCache + 'static,
+    {
+        Self {
+            options,
+            images: Arc::new(Mutex::new(cache)),
+        }
+    }
+
+    /// Load scenes from path to a glTF 2.0, reusing the images decoded by the
+    /// previous calls.
+    pub fn load<P>(&mut self, path: P) -> Result<Vec<Scene>, Box<dyn Error + Send + Sync>>
+    where
+        P: AsRef<Path>,
+    {
+        import(path, &self.options, &self.images).map(|(scenes, _)| scenes)
+    }
+
+    /// Load several files concurrently (one thread per core at most), reusing
+    /// and sharing the decoded images. Returns the scenes or the error of
+    /// every file, in the order of `paths`. A file whose loading panics gets
+    /// an error, the other files are still loaded.
+    pub fn load_many<P>(
+        &mut self,
+        paths: &[P],
+    ) -> Vec<Result<Vec<Scene>, Box<dyn Error + Send + Sync>>>
+    where
+        P: AsRef<Path> + Sync,
+    {
+        let threads = thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(paths.len());
+        let next = AtomicUsize::new(0);
+        let (options, images) = (&self.options, &self.images);
+        let results: Vec<_> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut results = vec![];
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            let path = match paths.get(i) {
+                                Some(path) => path,
+                                None => return results,
+                            };
+                            let scenes = panic::catch_unwind(AssertUnwindSafe(|| {
+                                import(path, options, images).map(|(scenes, _)| scenes)
+                            }))
+                            .unwrap_or_else(|payload| Err(panic_error(payload)));
+                            results.push((i, scenes));
+                        }
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().unwrap_or_default())
+                .collect()
+        });
+        let mut scenes: Vec<_> = paths.iter().map(|_| None).collect();
+        for (i, result) in results.into_iter().flatten() {
+            scenes[i] = Some(result);
+        }
+        scenes
+            .into_iter()
+            .map(|scenes| scenes.unwrap_or_else(|| Err("loader thread panicked".into())))
+            .collect()
+    }
+
+    /// Drop every cached image. Images still used by loaded scenes stay alive.
+    pub fn clear_cache(&mut self) {
+        self.images
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+/// Iterator over the objects of a glTF, converted on demand.
+///
+/// Created by [`load_iter`](fn.load_iter.html), yields the index of the scene
+/// along with each object, or the error met while reading its buffers.
+pub struct LoadIter {
+    doc: gltf::Document,
+    data: GltfData,
+    scene: usize,
+    traversal: Option<Traversal>,
+}
+
+impl LoadIter {
+    pub(crate) fn new(doc: gltf::Document, data: GltfData) -> Self {
+        Self {
+            scene: data.options.scene.unwrap_or(0),
+            doc,
+            data,
+            traversal: None,
+        }
+    }
+}
+
+impl Iterator for LoadIter {
+    type Item = gltf::Result<(usize, SceneItem)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.traversal.is_none() {
+                let scene = self.doc.scenes().nth(self.scene)?;
+                self.traversal = Some(Traversal::new(&scene, &self.data));
+            }
+            let traversal = self.traversal.as_mut().unwrap();
+            if let Some(item) = traversal.next(&self.doc, &mut self.data) {
+                let scene = self.scene;
+                return Some(item.map(|item| (scene, item)));
+            }
+            self.traversal = None;
+            if self.data.options.scene.is_some() {
+                return None;
+            }
+            self.scene += 1;
+        }
+    }
+}
+
+/// Error of a panic caught while loading a file, with its message.
+fn panic_error(payload: Box<dyn std::any::Any + Send>) -> Box<dyn Error + Send + Sync> {
+    let message = match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    };
+    format!("panicked while loading: {}", message).into()
+}
@@ -0,0 +1,314 @@
+use crate::cache::ImageConsumer;
+use crate::model::NormalConvention;
+use crate::Material;
+use cgmath::*;
+use std::sync::Arc;
+
+/// Options to customize how a glTF is loaded.
+///
+/// # Example
+///
+/// ```
+/// use easy_gltf::{LoadOptions, UpAxis};
+///
+/// let options = LoadOptions {
+///     up_axis: UpAxis::Z,
+///     ..Default::default()
+/// };
+/// let scenes = easy_gltf::load_with_options("tests/cube.glb", &options)
+///     .expect("Failed to load glTF");
+/// ```
+#[derive(Clone, Debug)]
+pub struct LoadOptions {
+    /// Up axis of the loaded scenes. glTF is `Y` up, choosing `Z` converts
+    /// every model, camera and light to a Z-up right-handed coordinate system
+    /// (Blender, Source...).
+    pub up_axis: UpAxis,
+
+    /// Factor applied to every distance: vertex positions, camera positions
+    /// and clipping planes, light positions and ranges. glTF units are meters,
+    /// use `100.` to get centimeters for instance. Defaults to `1.`.
+    pub scale_factor: f32,
+
+    /// Handedness of the loaded scenes. glTF is right-handed, choosing `Left`
+    /// mirrors the Z axis (for DirectX style engines), flips the triangle
+    /// winding and the tangent handedness so faces still point outward.
+    pub handedness: Handedness,
+
+    /// Output texture coordinates with the V axis flipped (`1 - v`), for
+    /// engines using an OpenGL style bottom-left texture origin.
+    ///
+    /// **Note**: The `Material::get_*` samplers expect glTF texture
+    /// coordinates, so flip V back before using them.
+    pub flip_v: bool,
+
+    /// Don't load cameras.
+    pub skip_cameras: bool,
+
+    /// Don't load lights.
+    pub skip_lights: bool,
+
+    /// Don't load models, their materials and textures are not loaded either.
+    pub skip_geometry: bool,
+
+    /// Don't load materials nor decode textures, every model uses the default
+    /// material. Useful when only the geometry is needed (e.g. for physics).
+    pub skip_materials: bool,
+
+    /// Only load the materials used by the scene in `Scene::materials`,
+    /// without any model, camera or light.
+    pub materials_only: bool,
+
+    /// Material of the primitives without material, e.g. a bright magenta to
+    /// make untextured assets obvious. Defaults to the glTF default material
+    /// (`Material::gltf_default()`).
+    pub default_material: Option<Arc<Material>>,
+
+    /// Called for every material of the file before it is cached, to patch
+    /// materials at import time (clamp roughness, remap texture slots...).
+    /// The default material isn't passed to the hook, see `default_material`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_gltf::LoadOptions;
+    ///
+    /// let options = LoadOptions {
+    ///     material_override: Some(|_, mut material| {
+    ///         material.pbr.roughness_factor = material.pbr.roughness_factor.max(0.1);
+    ///         material
+    ///     }),
+    ///     ..Default::default()
+    /// };
+    /// ```
+    pub material_override: Option<fn(&gltf::Material, Material) -> Material>,
+
+    /// Textures to synthesize for the texture slots of materials that only
+    /// have factors, for renderers that always bind every texture.
+    pub placeholder_textures: PlaceholderTextures,
+
+    /// Also store the vertex colors as sRGB encoded `[u8; 4]` in
+    /// `Model::compact_colors()`. Without the `vertex-color` feature vertices
+    /// don't carry colors at all, so this is the memory-friendly way to get
+    /// them.
+    ///
+    /// **Note**: With the `vertex-color` feature this is an extra copy, the
+    /// colors are still stored in `Vertex::color` (4 bytes per vertex more).
+    pub compact_colors: bool,
+
+    /// Called for every node before loading it, to exclude nodes (collision
+    /// meshes, editor helpers, high-poly variants...) by name or extras.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_gltf::{LoadOptions, NodeAction};
+    ///
+    /// let options = LoadOptions {
+    ///     node_filter: Some(|node| match node.name() {
+    ///         Some(name) if name.ends_with("_collision") => NodeAction::SkipSubtree,
+    ///         _ => NodeAction::Load,
+    ///     }),
+    ///     ..Default::default()
+    /// };
+    /// ```
+    pub node_filter: Option<fn(&gltf::Node) -> NodeAction>,
+
+    /// Only load the scene with this index. Buffers only referenced by the
+    /// other scenes of the file are not read.
+    pub scene: Option<usize>,
+
+    /// Maximum size in bytes of the decoded images kept in the image cache
+    /// (see [`Loader`](struct.Loader.html)). The least recently used images
+    /// are dropped from the cache beyond it, and decoded again if another
+    /// material needs them. Images used by loaded scenes stay alive.
+    /// Defaults to `None` (unlimited).
+    pub texture_budget: Option<usize>,
+
+    /// Point of the glTF coordinate system moved to the origin of the loaded
+    /// scenes. Node transforms are combined in `f64` and the origin is removed
+    /// before converting to `f32`, so geospatial or CAD models with large
+    /// coordinates keep their precision. Defaults to `[0., 0., 0.]`.
+    pub origin: [f64; 3],
+
+    /// Also keep the model positions in `f64`, see `Model::positions_f64()`.
+    pub f64_positions: bool,
+
+    /// Convention of the loaded normal maps. glTF normal maps are OpenGL
+    /// style, choosing `DirectX` inverts their green channel like
+    /// `NormalMap::convert_to`, once per image shared by several materials.
+    pub normal_convention: NormalConvention,
+
+    /// Multiply the color of the base color textures by their alpha (in
+    /// linear space, the result is still sRGB encoded), for renderers
+    /// blending with premultiplied alpha.
+    ///
+    /// **Note**: `Material::get_base_color_alpha` then returns premultiplied
+    /// colors as well.
+    pub premultiply_alpha: bool,
+
+    /// Generate `Material::alpha_mask` for the materials using the `Mask`
+    /// alpha mode with a base color texture.
+    pub alpha_masks: bool,
+
+    /// Maximum number of joints influencing a vertex, at most (and by default)
+    /// `4`. The strongest influences of every `JOINTS_n`/`WEIGHTS_n` set are
+    /// kept, `Model::validate()` reports the dropped ones
+    /// (`Issue::DroppedInfluences`).
+    pub max_influences: usize,
+
+    /// Normalize the kept joint weights of every vertex to a sum of `1`.
+    /// Defaults to `true`.
+    pub normalize_weights: bool,
+
+    /// Normalize the normals and tangents of every vertex after applying the
+    /// node transforms. Defaults to `true`, disable it to save time on large
+    /// assets known to have unit normals and tangents.
+    ///
+    /// **Note**: Without normalization, node transforms with a scale give
+    /// normals and tangents that aren't unit length.
+    pub normalize_directions: bool,
+
+    /// Number of threads converting a file: external buffers are read
+    /// concurrently, then primitives are converted by worker threads while
+    /// the materials and their images are loaded. `1` (the default) loads
+    /// everything on the calling thread, `0` uses one thread per core.
+    ///
+    /// **Note**: Models are in the same order whatever the number of threads.
+    pub threads: usize,
+
+    /// Read the buffers of `.gltf` files on demand instead of upfront: each
+    /// primitive, skin, animation and embedded image only reads the part of
+    /// the `.bin` files it uses, so assets larger than the available memory
+    /// can be loaded. Only applies when all the used buffers are external
+    /// files, other files are loaded as usual.
+    ///
+    /// **Note**: Reading many small ranges is slower than reading whole
+    /// files, keep it for huge assets.
+    pub stream_buffers: bool,
+
+    /// Hand the images of the materials (placeholders and alpha masks
+    /// included) to a consumer, e.g. to upload them to the GPU, instead of
+    /// keeping them in the materials. The handles returned by the consumer are
+    /// stored in `Material::texture_handles` and the texture slots are
+    /// emptied: normal and occlusion maps get a neutral 1x1 texture, the other
+    /// slots are `None`.
+    ///
+    /// **Note**: The decoded images are not kept in the image cache of a
+    /// [`Loader`](struct.Loader.html), an image used by several materials is
+    /// decoded for each of them but consumed once.
+    pub image_consumer: Option<Arc<dyn ImageConsumer>>,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            up_axis: UpAxis::default(),
+            scale_factor: 1.,
+            handedness: Handedness::default(),
+            flip_v: false,
+            skip_cameras: false,
+            skip_lights: false,
+            skip_geometry: false,
+            skip_materials: false,
+            materials_only: false,
+            default_material: None,
+            material_override: None,
+            placeholder_textures: PlaceholderTextures::default(),
+            compact_colors: false,
+            node_filter: None,
+            scene: None,
+            texture_budget: None,
+            origin: [0.; 3],
+            f64_positions: false,
+            normal_convention: NormalConvention::default(),
+            premultiply_alpha: false,
+            alpha_masks: false,
+            max_influences: 4,
+            normalize_weights: true,
+            normalize_directions: true,
+            threads: 1,
+            stream_buffers: false,
+            image_consumer: None,
+        }
+    }
+}
+
+/// Up axis of a coordinate system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum UpAxis {
+    /// Y-up, the glTF convention.
+    #[default]
+    Y,
+    /// Z-up, `(x, y, z)` in glTF becomes `(x, -z, y)`.
+    Z,
+}
+
+/// What to do with a node, returned by `LoadOptions::node_filter`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum NodeAction {
+    /// Load the node and visit its children.
+    #[default]
+    Load,
+    /// Skip the node and all its descendants.
+    SkipSubtree,
+    /// Skip the mesh, camera and light of the node but still visit its
+    /// children (which keep the node transform).
+    SkipSelf,
+}
+
+/// Textures synthesized for the missing texture slots of materials, see
+/// `LoadOptions::placeholder_textures`. Placeholders are 1x1 images.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PlaceholderTextures {
+    /// Leave missing textures to `None`.
+    #[default]
+    None,
+    /// White textures (flat normal map) so factors keep working unchanged.
+    Neutral,
+    /// Textures encoding the factors (e.g. the base color texture has the
+    /// `base_color_factor` color), the factors are then reset to `1`.
+    FromFactors,
+}
+
+/// Handedness of a coordinate system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Handedness {
+    /// Right-handed, the glTF convention.
+    #[default]
+    Right,
+    /// Left-handed, the Z axis is mirrored.
+    Left,
+}
+
+impl LoadOptions {
+    /// Transform applied to the root nodes of every scene.
+    pub(crate) fn root_transform(&self) -> Matrix4<f64> {
+        let up_axis = match self.up_axis {
+            UpAxis::Y => One::one(),
+            UpAxis::Z => Matrix4::from_angle_x(Deg(90.)),
+        };
+        let handedness = match self.handedness {
+            Handedness::Right => One::one(),
+            Handedness::Left => Matrix4::from_nonuniform_scale(1., 1., -1.),
+        };
+        let origin = Matrix4::from_translation(-Vector3::from(self.origin));
+        handedness * up_axis * origin
+    }
+
+    /// Number of threads to use, see `threads`.
+    pub(crate) fn thread_count(&self) -> usize {
+        match self.threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            threads => threads,
+        }
+    }
+
+    /// Scenes of the document to load.
+    pub(crate) fn scenes<'a>(&self, doc: &'a gltf::Document) -> Vec<gltf::Scene<'a>> {
+        match self.scene {
+            Some(index) => doc.scenes().nth(index).into_iter().collect(),
+            None => doc.scenes().collect(),
+        }
+    }
+}
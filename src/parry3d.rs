@@ -0,0 +1,153 @@
+//! Collision shapes for [parry3d](https://parry.rs) (and so
+//! [rapier](https://rapier.rs)) built from models and scenes.
+//! Requires the `parry3d` feature.
+//!
+//! # Example
+//!
+//! ```
+//! let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+//! let mesh = easy_gltf::parry3d::trimesh(&scenes[0].models[0]).expect("No triangles");
+//! for (isometry, shape) in easy_gltf::parry3d::shapes(&scenes[0]) {
+//!     // ColliderBuilder::new(shape).position(isometry)...
+//! }
+//! ```
+
+use crate::model::{Mode, Model};
+use crate::Scene;
+use ::parry3d::math::{Isometry, Point, Real};
+use ::parry3d::na::{Matrix3, Rotation3, Translation3, UnitQuaternion, Vector3};
+use ::parry3d::shape::{SharedShape, TriMesh};
+use ::parry3d::transformation::vhacd::VHACDParameters;
+use cgmath::{InnerSpace, Matrix, Matrix4, SquareMatrix};
+
+/// Tolerance used to decide whether a transform is rigid.
+const EPSILON: f32 = 1e-4;
+
+/// Triangle mesh of a model, in scene space. Strips and fans are
+/// triangulated.
+///
+/// **Note**: Returns `None` if the mode isn't `Triangles`, `TriangleFan` or
+/// `TriangleStrip` or if the model has no triangle.
+pub fn trimesh(model: &Model) -> Option<TriMesh> {
+    let (positions, indices) = geometry(model, None)?;
+    TriMesh::new(positions, indices).ok()
+}
+
+/// Convex hull of the vertices of a model, in scene space, see
+/// [`Model::convex_hull`](../model/struct.Model.html#method.convex_hull).
+///
+/// **Note**: Returns `None` if the vertices are flat.
+pub fn convex_hull(model: &Model) -> Option<SharedShape> {
+    let points: Vec<_> = model
+        .vertices()
+        .iter()
+        .map(|v| Point::new(v.position.x, v.position.y, v.position.z))
+        .collect();
+    SharedShape::convex_hull(&points)
+}
+
+/// Approximate decomposition of the triangles of a model into a compound of
+/// convex parts (V-HACD), in scene space. Unlike triangle meshes, the
+/// result is solid and cheap to collide with dynamic bodies.
+///
+/// **Note**: The decomposition is slow, run it at import time and keep the
+/// result. Returns `None` if the mode isn't `Triangles`, `TriangleFan` or
+/// `TriangleStrip` or if the model has no triangle.
+///
+/// # Example
+///
+/// ```no_run
+/// use parry3d::transformation::vhacd::VHACDParameters;
+///
+/// let scenes = easy_gltf::load("tests/head.glb").expect("Failed to load glTF");
+/// let params = VHACDParameters {
+///     resolution: 32,
+///     ..Default::default()
+/// };
+/// let shape = easy_gltf::parry3d::convex_decomposition(&scenes[0].models[0], &params);
+/// ```
+pub fn convex_decomposition(model: &Model, params: &VHACDParameters) -> Option<SharedShape> {
+    let (positions, indices) = geometry(model, None)?;
+    Some(SharedShape::convex_decomposition_with_params(
+        &positions, &indices, params,
+    ))
+}
+
+/// Triangle meshes of every model of a scene, each placed with the transform
+/// of its node, in the layout of `SharedShape::compound`.
+///
+/// Models whose node transform is rigid (rotation and translation only) are
+/// kept in node space and placed with the matching isometry. Other models
+/// are kept in scene space with an identity isometry, since parry shapes
+/// can't be scaled.
+///
+/// **Note**: Parry compounds can't contain triangle meshes, create one
+/// collider per shape instead. Models without triangles are skipped.
+pub fn shapes(scene: &Scene) -> Vec<(Isometry<Real>, SharedShape)> {
+    scene
+        .models
+        .iter()
+        .filter_map(|model| {
+            let isometry = model.transform.as_ref().and_then(isometry);
+            let (positions, indices) = geometry(model, isometry.as_ref())?;
+            let shape = SharedShape::trimesh(positions, indices).ok()?;
+            Some((isometry.unwrap_or_else(Isometry::identity), shape))
+        })
+        .collect()
+}
+
+/// Vertex and index buffers of a triangle mesh.
+type GeometryBuffers = (Vec<Point<Real>>, Vec<[u32; 3]>);
+
+/// Positions and triangles of a model, moved back in the space of
+/// `isometry` if any.
+fn geometry(model: &Model, isometry: Option<&Isometry<Real>>) -> Option<GeometryBuffers> {
+    if !matches!(
+        model.mode(),
+        Mode::Triangles | Mode::TriangleStrip | Mode::TriangleFan
+    ) {
+        return None;
+    }
+    let (_, corners) = model.list_indices();
+    if corners.is_empty() {
+        return None;
+    }
+    let positions = model
+        .vertices()
+        .iter()
+        .map(|v| {
+            let p = Point::new(v.position.x, v.position.y, v.position.z);
+            match isometry {
+                Some(isometry) => isometry.inverse_transform_point(&p),
+                None => p,
+            }
+        })
+        .collect();
+    let indices = corners
+        .chunks_exact(3)
+        .map(|t| [t[0], t[1], t[2]])
+        .collect();
+    Some((positions, indices))
+}
+
+/// Isometry matching a transform, `None` if it scales, shears or mirrors.
+fn isometry(transform: &Matrix4<f32>) -> Option<Isometry<Real>> {
+    let [x, y, z] = [transform.x, transform.y, transform.z].map(|c| c.truncate());
+    let unit = |v: cgmath::Vector3<f32>| (v.magnitude() - 1.).abs() < EPSILON;
+    let orthogonal =
+        x.dot(y).abs() < EPSILON && y.dot(z).abs() < EPSILON && z.dot(x).abs() < EPSILON;
+    let linear = cgmath::Matrix3::from_cols(x, y, z);
+    if !(unit(x) && unit(y) && unit(z) && orthogonal && linear.determinant() > 0.) {
+        return None;
+    }
+    let linear = linear.transpose();
+    let rotation = Rotation3::from_matrix_unchecked(Matrix3::new(
+        linear.x.x, linear.x.y, linear.x.z, linear.y.x, linear.y.y, linear.y.z, linear.z.x,
+        linear.z.y, linear.z.z,
+    ));
+    let translation = Translation3::from(Vector3::new(transform.w.x, transform.w.y, transform.w.z));
+    Some(Isometry::from_parts(
+        translation,
+        UnitQuaternion::from_rotation_matrix(&rotation),
+    ))
+}
@@ -0,0 +1,295 @@
+use super::{NodePose, Pose};
+use crate::utils::{accessor, accessor_views, GltfData};
+use cgmath::*;
+use gltf::animation::util::ReadOutputs;
+use std::ops::{Add, Mul};
+
+/// Animation clip: name, duration, animated nodes and keyframes.
+#[derive(Clone, Debug, Default)]
+pub struct Animation {
+    #[cfg(feature = "names")]
+    /// Animation name. Requires the `names` feature.
+    pub name: Option<String>,
+
+    #[cfg(feature = "extras")]
+    /// Animation extra data. Requires the `extras` feature.
+    pub extras: gltf::json::extras::Extras,
+
+    /// Index of the glTF animation.
+    pub index: usize,
+
+    /// Duration of the clip in seconds (last keyframe time).
+    pub duration: f32,
+
+    /// Properties animated by the clip.
+    pub channels: Vec<Channel>,
+
+    /// Keyframes of every channel: `tracks[i]` animates `channels[i]`.
+    pub tracks: Vec<Track>,
+}
+
+/// Node property animated by a clip.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Channel {
+    /// Index of the targeted node.
+    pub node: usize,
+    /// Animated property of the node.
+    pub property: Property,
+}
+
+/// Animated property of a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Property {
+    /// Translation of the node.
+    Translation,
+    /// Rotation of the node.
+    Rotation,
+    /// Scale of the node.
+    Scale,
+    /// Morph target weights of the node mesh.
+    MorphTargetWeights,
+}
+
+/// Keyframes of an animated property.
+///
+/// **Note**: Values are given as in the glTF, `LoadOptions` transforms (up
+/// axis, scale...) are not applied to them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Track {
+    /// Time of every keyframe in seconds, in increasing order.
+    pub times: Vec<f32>,
+    /// Value of every keyframe.
+    pub values: Keyframes,
+    /// Interpolation between the keyframes.
+    pub interpolation: Interpolation,
+}
+
+/// Interpolation between the keyframes of a track.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum Interpolation {
+    /// Linear interpolation, spherical linear interpolation (`slerp`) for
+    /// rotations.
+    #[default]
+    Linear,
+    /// The value of a keyframe is kept until the next keyframe.
+    Step,
+    /// Cubic Hermite spline, with an in-tangent and an out-tangent for every
+    /// keyframe.
+    CubicSpline,
+}
+
+impl From<gltf::animation::Interpolation> for Interpolation {
+    fn from(interpolation: gltf::animation::Interpolation) -> Self {
+        use gltf::animation::Interpolation as I;
+        match interpolation {
+            I::Linear => Interpolation::Linear,
+            I::Step => Interpolation::Step,
+            I::CubicSpline => Interpolation::CubicSpline,
+        }
+    }
+}
+
+/// Values of the keyframes of a track.
+///
+/// With the `CubicSpline` interpolation, every keyframe has three values as in
+/// the glTF: its in-tangent, its value and its out-tangent.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Keyframes {
+    /// Translations of the node.
+    Translation(Vec<Vector3<f32>>),
+    /// Rotations of the node.
+    Rotation(Vec<Quaternion<f32>>),
+    /// Scales of the node.
+    Scale(Vec<Vector3<f32>>),
+    /// Weights of the morph targets, one run of weights per keyframe.
+    MorphTargetWeights(Vec<f32>),
+}
+
+impl From<gltf::animation::Property> for Property {
+    fn from(property: gltf::animation::Property) -> Self {
+        use gltf::animation::Property as P;
+        match property {
+            P::Translation => Property::Translation,
+            P::Rotation => Property::Rotation,
+            P::Scale => Property::Scale,
+            P::MorphTargetWeights => Property::MorphTargetWeights,
+        }
+    }
+}
+
+impl Animation {
+    pub(crate) fn load(gltf_animation: gltf::Animation, data: &GltfData) -> gltf::Result<Self> {
+        let views = gltf_animation.samplers().flat_map(|sampler| {
+            let mut views = accessor_views(&sampler.input());
+            views.extend(accessor_views(&sampler.output()));
+            views
+        });
+        let buffers = data.buffers.get(views)?;
+        let mut duration = 0f32;
+        let mut channels = vec![];
+        let mut tracks = vec![];
+        for channel in gltf_animation.channels() {
+            let times: Vec<_> = accessor::iter(&channel.sampler().input(), &buffers)
+                .map(Iterator::collect)
+                .unwrap_or_default();
+            if let Some(&last) = times.last() {
+                duration = duration.max(last);
+            }
+            let values = match accessor::read_outputs(&channel, &buffers) {
+                Some(ReadOutputs::Translations(values)) => {
+                    Keyframes::Translation(values.map(Vector3::from).collect())
+                }
+                Some(ReadOutputs::Rotations(values)) => Keyframes::Rotation(
+                    values
+                        .into_f32()
+                        .map(|[x, y, z, w]| Quaternion::new(w, x, y, z))
+                        .collect(),
+                ),
+                Some(ReadOutputs::Scales(values)) => {
+                    Keyframes::Scale(values.map(Vector3::from).collect())
+                }
+                Some(ReadOutputs::MorphTargetWeights(values)) => {
+                    Keyframes::MorphTargetWeights(values.into_f32().collect())
+                }
+                None => continue,
+            };
+            channels.push(Channel {
+                node: channel.target().node().index(),
+                property: channel.target().property().into(),
+            });
+            let interpolation = channel.sampler().interpolation().into();
+            tracks.push(Track {
+                times,
+                values,
+                interpolation,
+            });
+        }
+        Ok(Self {
+            #[cfg(feature = "names")]
+            name: gltf_animation.name().map(String::from),
+            #[cfg(feature = "extras")]
+            extras: gltf_animation.extras().clone(),
+            index: gltf_animation.index(),
+            duration,
+            channels,
+            tracks,
+        })
+    }
+
+    /// Whether the clip animates the given node.
+    pub fn targets(&self, node: usize) -> bool {
+        self.channels.iter().any(|channel| channel.node == node)
+    }
+
+    /// Values of the animated properties at `time` (in seconds, clamped to
+    /// the keyframes), following the interpolation of every track.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/skin.gltf").expect("Failed to load glTF");
+    /// let pose = scenes[0].animations[0].sample(0.5);
+    /// let rotation = pose.nodes[&2].rotation.unwrap();
+    /// ```
+    pub fn sample(&self, time: f32) -> Pose {
+        let mut pose = Pose::default();
+        for (channel, track) in self.channels.iter().zip(self.tracks.iter()) {
+            if track.times.is_empty() {
+                continue;
+            }
+            let node = pose
+                .nodes
+                .entry(channel.node)
+                .or_insert_with(NodePose::default);
+            let lerp = |a: Vector3<f32>, b, t| a.lerp(b, t);
+            match &track.values {
+                Keyframes::Translation(values) => {
+                    node.translation = track.interpolate(values, 1, 0, time, lerp);
+                }
+                Keyframes::Rotation(values) => {
+                    let rotation = track.interpolate(values, 1, 0, time, slerp);
+                    node.rotation = rotation.map(|rotation| rotation.normalize());
+                }
+                Keyframes::Scale(values) => {
+                    node.scale = track.interpolate(values, 1, 0, time, lerp);
+                }
+                Keyframes::MorphTargetWeights(values) => {
+                    let width = values.len() / track.runs();
+                    node.weights = (0..width)
+                        .map(|i| {
+                            track.interpolate(values, width, i, time, |a, b, t| a + (b - a) * t)
+                        })
+                        .collect();
+                }
+            }
+        }
+        pose
+    }
+}
+
+impl Track {
+    /// Number of runs of values: one per keyframe, three with tangents.
+    pub(crate) fn runs(&self) -> usize {
+        match self.interpolation {
+            Interpolation::CubicSpline => self.times.len() * 3,
+            _ => self.times.len(),
+        }
+    }
+
+    /// Interpolate the element `index` of the keyframes with `width`
+    /// elements (the morph target weights), `linear` interpolates two values.
+    /// Returns `None` if values are missing.
+    pub(crate) fn interpolate<T, F>(
+        &self,
+        values: &[T],
+        width: usize,
+        index: usize,
+        time: f32,
+        linear: F,
+    ) -> Option<T>
+    where
+        T: Copy + Add<Output = T> + Mul<f32, Output = T>,
+        F: Fn(T, T, f32) -> T,
+    {
+        let (a, b, t) = self.locate(time)?;
+        // Element of a keyframe: in-tangent (0), value (1) or out-tangent (2)
+        let value = |key: usize, part: usize| match self.interpolation {
+            Interpolation::CubicSpline => values.get((key * 3 + part) * width + index).copied(),
+            _ => values.get(key * width + index).copied(),
+        };
+        Some(match self.interpolation {
+            Interpolation::Linear => linear(value(a, 1)?, value(b, 1)?, t),
+            Interpolation::Step => value(a, 1)?,
+            Interpolation::CubicSpline => {
+                let duration = self.times[b] - self.times[a];
+                let (t2, t3) = (t * t, t * t * t);
+                value(a, 1)? * (2. * t3 - 3. * t2 + 1.)
+                    + value(a, 2)? * (duration * (t3 - 2. * t2 + t))
+                    + value(b, 1)? * (-2. * t3 + 3. * t2)
+                    + value(b, 0)? * (duration * (t3 - t2))
+            }
+        })
+    }
+
+    /// Keyframes surrounding `time` and the interpolation factor between
+    /// them, `None` for a track without keyframes.
+    fn locate(&self, time: f32) -> Option<(usize, usize, f32)> {
+        let last = self.times.len().checked_sub(1)?;
+        let next = self.times.partition_point(|&t| t <= time);
+        Some(match next {
+            0 => (0, 0, 0.),
+            n if n > last => (last, last, 0.),
+            n => {
+                let (start, end) = (self.times[n - 1], self.times[n]);
+                (n - 1, n, (time - start) / (end - start))
+            }
+        })
+    }
+}
+
+/// Shortest path interpolation between two rotations.
+pub(crate) fn slerp(a: Quaternion<f32>, b: Quaternion<f32>, t: f32) -> Quaternion<f32> {
+    // Quaternions q and -q are the same rotation
+    let b = if a.dot(b) < 0. { -b } else { b };
+    a.slerp(b, t).normalize()
+}
@@ -1,3 +1,6 @@
+use super::Scene;
+use crate::utils::uniform_scale;
+use crate::LoadOptions;
 use cgmath::*;
 use gltf::camera::Projection as GltfProjection;
 
@@ -12,6 +15,18 @@ pub struct Camera {
     /// Scene extra data. Requires the `extras` feature.
     pub extras: gltf::json::extras::Extras,
 
+    /// Index of the glTF node hosting the camera.
+    pub node_index: usize,
+
+    #[cfg(feature = "names")]
+    /// Name of the glTF node hosting the camera. Requires the `names` feature.
+    pub node_name: Option<String>,
+
+    #[cfg(feature = "extras")]
+    /// Extra data of the glTF node hosting the camera. Requires the `extras`
+    /// feature.
+    pub node_extras: gltf::json::extras::Extras,
+
     /// Transform matrix (also called world to camera matrix)
     pub transform: Matrix4<f32>,
 
@@ -92,6 +107,11 @@ impl Camera {
         .normalize()
     }
 
+    /// Transform matrix as a plain column-major array.
+    pub fn raw_transform(&self) -> [[f32; 4]; 4] {
+        self.transform.into()
+    }
+
     /// Apply the transformation matrix on a vector.
     ///
     /// # Example
@@ -107,19 +127,101 @@ impl Camera {
         (self.transform * pos).truncate()
     }
 
-    pub(crate) fn load(gltf_cam: gltf::Camera, transform: &Matrix4<f32>) -> Self {
+    /// Aspect ratio (width over height) of the camera: the one of a
+    /// perspective projection if specified, `xmag / ymag` for an orthographic
+    /// one.
+    pub fn aspect_ratio(&self) -> Option<f32> {
+        match self.projection {
+            Projection::Perspective { aspect_ratio, .. } => aspect_ratio,
+            Projection::Orthographic { scale } => Some(scale.x / scale.y),
+        }
+    }
+
+    /// Override the aspect ratio (width over height) with the one of the
+    /// viewport. The vertical field of view or magnification is kept, an
+    /// orthographic camera gets wider or narrower.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// let camera = &mut scenes[0].cameras[0];
+    /// camera.set_aspect_ratio(800. / 600.);
+    /// ```
+    pub fn set_aspect_ratio(&mut self, aspect: f32) {
+        match &mut self.projection {
+            Projection::Perspective { aspect_ratio, .. } => *aspect_ratio = Some(aspect),
+            Projection::Orthographic { scale } => scale.x = scale.y * aspect,
+        }
+    }
+
+    /// Horizontal field of view of a perspective camera rendered with the given
+    /// aspect ratio (width over height), `None` for an orthographic camera.
+    ///
+    /// **Note**: The field of view is not proportional to the aspect ratio,
+    /// `xfov = 2 * atan(tan(yfov / 2) * aspect_ratio)`.
+    pub fn xfov(&self, aspect_ratio: f32) -> Option<Rad<f32>> {
+        match self.projection {
+            Projection::Perspective { yfov, .. } => {
+                Some(Rad::atan((yfov / 2.).tan() * aspect_ratio) * 2.)
+            }
+            Projection::Orthographic { .. } => None,
+        }
+    }
+
+    /// Focal length of a perspective camera with a sensor of the given height,
+    /// in the unit of the sensor. `None` for an orthographic camera.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// let camera = &scenes[0].cameras[0];
+    /// // Full frame equivalent, 36 mm wide
+    /// let aspect_ratio = camera.aspect_ratio().unwrap();
+    /// let millimeters = camera.focal_length(36. / aspect_ratio).unwrap();
+    /// ```
+    pub fn focal_length(&self, sensor_height: f32) -> Option<f32> {
+        match self.projection {
+            Projection::Perspective { yfov, .. } => Some(sensor_height / 2. / (yfov / 2.).tan()),
+            Projection::Orthographic { .. } => None,
+        }
+    }
+
+    /// Transform the camera, scaling its clip planes and orthographic
+    /// magnification like the distances of the scene.
+    pub(crate) fn apply_transform(&mut self, transform: &Matrix4<f32>) {
+        let scale = uniform_scale(transform);
+        self.transform = transform * self.transform;
+        self.znear *= scale;
+        self.zfar *= scale;
+        if let Projection::Orthographic { scale: mag } = &mut self.projection {
+            *mag *= scale;
+        }
+    }
+
+    pub(crate) fn load(
+        node: &gltf::Node,
+        gltf_cam: gltf::Camera,
+        transform: &Matrix4<f32>,
+        options: &LoadOptions,
+    ) -> Self {
         let mut cam = Self {
+            node_index: node.index(),
             transform: *transform,
             ..Default::default()
         };
+        cam.transform.w = (transform.w.truncate() * options.scale_factor).extend(1.);
 
         #[cfg(feature = "names")]
         {
             cam.name = gltf_cam.name().map(String::from);
+            cam.node_name = node.name().map(String::from);
         }
         #[cfg(feature = "extras")]
         {
             cam.extras = gltf_cam.extras().clone();
+            cam.node_extras = node.extras().clone();
         }
 
         match gltf_cam.projection() {
@@ -127,22 +229,81 @@ impl Camera {
                 cam.projection = Projection::Orthographic {
                     scale: Vector2::new(ortho.xmag(), ortho.ymag()),
                 };
-                cam.zfar = ortho.zfar();
-                cam.znear = ortho.znear();
+                cam.zfar = ortho.zfar() * options.scale_factor;
+                cam.znear = ortho.znear() * options.scale_factor;
             }
             GltfProjection::Perspective(pers) => {
                 cam.projection = Projection::Perspective {
                     yfov: Rad(pers.yfov()),
                     aspect_ratio: pers.aspect_ratio(),
                 };
-                cam.zfar = pers.zfar().unwrap_or(f32::INFINITY);
-                cam.znear = pers.znear();
+                cam.zfar = pers.zfar().unwrap_or(f32::INFINITY) * options.scale_factor;
+                cam.znear = pers.znear() * options.scale_factor;
             }
         };
         cam
     }
 }
 
+impl Scene {
+    /// Camera to render the scene from: the first camera of the scene, or a
+    /// perspective camera looking down `-Z` at the bounding box of the models
+    /// so they fill the view when there is no camera.
+    ///
+    /// The synthesized camera isn't hosted by a node, its `node_index` is
+    /// `usize::MAX`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// let camera = scenes[0].main_camera();
+    /// let eye = camera.position();
+    /// ```
+    pub fn main_camera(&self) -> Camera {
+        if let Some(camera) = self.cameras.first() {
+            return camera.clone();
+        }
+
+        let mut camera = Camera {
+            node_index: usize::MAX,
+            transform: Matrix4::identity(),
+            znear: 0.01,
+            zfar: 100.,
+            ..Default::default()
+        };
+        let bounds = self
+            .models
+            .iter()
+            .filter_map(|model| model.bounds())
+            .reduce(|(min_a, max_a), (min_b, max_b)| {
+                (
+                    Vector3::new(
+                        min_a.x.min(min_b.x),
+                        min_a.y.min(min_b.y),
+                        min_a.z.min(min_b.z),
+                    ),
+                    Vector3::new(
+                        max_a.x.max(max_b.x),
+                        max_a.y.max(max_b.y),
+                        max_a.z.max(max_b.z),
+                    ),
+                )
+            });
+        if let (Some((min, max)), Projection::Perspective { yfov, .. }) =
+            (bounds, &camera.projection)
+        {
+            let center = (min + max) / 2.;
+            let radius = ((max - min).magnitude() / 2.).max(f32::EPSILON);
+            let distance = radius / (*yfov / 2.).sin();
+            camera.transform = Matrix4::from_translation(center + Vector3::unit_z() * distance);
+            camera.znear = (distance - radius).max(distance / 1000.);
+            camera.zfar = distance + radius;
+        }
+        camera
+    }
+}
+
 impl Default for Camera {
     fn default() -> Self {
         Camera {
@@ -150,6 +311,11 @@ impl Default for Camera {
             name: None,
             #[cfg(feature = "extras")]
             extras: None,
+            node_index: 0,
+            #[cfg(feature = "names")]
+            node_name: None,
+            #[cfg(feature = "extras")]
+            node_extras: None,
             transform: Zero::zero(),
             projection: Projection::default(),
             zfar: f32::INFINITY,
@@ -0,0 +1,107 @@
+use crate::{Camera, Projection};
+use cgmath::*;
+
+/// View frustum of a camera, used to cull objects outside of the view.
+///
+/// # Example
+///
+/// ```
+/// # use cgmath::*;
+/// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+/// let frustum = scenes[0].cameras[0].frustum(16. / 9.);
+/// assert!(frustum.intersects_sphere(Vector3::zero(), 1.));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frustum {
+    /// Left, right, bottom, top, near and far planes in world space.
+    ///
+    /// A plane is `(normal, distance)` with its normal pointing inside the
+    /// frustum: a point `p` is inside when `normal.dot(p) + distance >= 0`.
+    /// The far plane of an infinite perspective is `(0, 0, 0, 1)`.
+    pub planes: [Vector4<f32>; 6],
+}
+
+impl Frustum {
+    /// Frustum of a camera rendered with the given aspect ratio (width over
+    /// height). Orthographic cameras use their own `xmag` and `ymag`.
+    pub fn new(camera: &Camera, aspect_ratio: f32) -> Self {
+        let near = Vector4::new(0., 0., -1., -camera.znear);
+        let far = if camera.zfar.is_finite() {
+            Vector4::new(0., 0., 1., camera.zfar)
+        } else {
+            Vector4::new(0., 0., 0., 1.)
+        };
+        let planes = match camera.projection {
+            Projection::Perspective { yfov, .. } => {
+                let tan_y = (yfov / 2.).tan();
+                let tan_x = tan_y * aspect_ratio;
+                [
+                    Vector4::new(1., 0., -tan_x, 0.),
+                    Vector4::new(-1., 0., -tan_x, 0.),
+                    Vector4::new(0., 1., -tan_y, 0.),
+                    Vector4::new(0., -1., -tan_y, 0.),
+                    near,
+                    far,
+                ]
+            }
+            Projection::Orthographic { scale } => [
+                Vector4::new(1., 0., 0., scale.x),
+                Vector4::new(-1., 0., 0., scale.x),
+                Vector4::new(0., 1., 0., scale.y),
+                Vector4::new(0., -1., 0., scale.y),
+                near,
+                far,
+            ],
+        };
+
+        // Planes are transformed by the inverse transpose of the camera matrix
+        let to_camera = camera
+            .transform
+            .invert()
+            .unwrap_or_else(Matrix4::identity)
+            .transpose();
+        let mut frustum = Self { planes };
+        for plane in frustum.planes.iter_mut() {
+            let plane_world = to_camera * *plane;
+            let length = plane_world.truncate().magnitude();
+            *plane = if length > 0. {
+                plane_world / length
+            } else {
+                plane_world
+            };
+        }
+        frustum
+    }
+
+    /// Check if a sphere is at least partially inside the frustum.
+    pub fn intersects_sphere(&self, center: Vector3<f32>, radius: f32) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.truncate().dot(center) + plane.w >= -radius)
+    }
+
+    /// Check if an axis-aligned bounding box is at least partially inside the
+    /// frustum.
+    ///
+    /// **Note**: This test is conservative, large boxes near the frustum
+    /// corners may be reported as intersecting.
+    pub fn intersects_aabb(&self, min: Vector3<f32>, max: Vector3<f32>) -> bool {
+        self.planes.iter().all(|plane| {
+            // Corner of the box the furthest along the plane normal
+            let corner = Vector3::new(
+                if plane.x >= 0. { max.x } else { min.x },
+                if plane.y >= 0. { max.y } else { min.y },
+                if plane.z >= 0. { max.z } else { min.z },
+            );
+            plane.truncate().dot(corner) + plane.w >= 0.
+        })
+    }
+}
+
+impl Camera {
+    /// View frustum of the camera for the given aspect ratio (width over
+    /// height), see [`Frustum::new`](struct.Frustum.html#method.new).
+    pub fn frustum(&self, aspect_ratio: f32) -> Frustum {
+        Frustum::new(self, aspect_ratio)
+    }
+}
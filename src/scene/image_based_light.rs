@@ -0,0 +1,133 @@
+use crate::utils::GltfData;
+use cgmath::*;
+use image::RgbaImage;
+use serde_json::Value;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+/// Image-based light of a scene, from the `EXT_lights_image_based` extension.
+///
+/// **Note**: The rotation is given as in the glTF, `LoadOptions` transforms
+/// (up axis, handedness) are not applied to it.
+#[derive(Clone, Debug)]
+pub struct ImageBasedLight {
+    #[cfg(feature = "names")]
+    /// Light name. Requires the `names` feature.
+    pub name: Option<String>,
+
+    /// Rotation of the environment.
+    pub rotation: Quaternion<f32>,
+
+    /// Brightness multiplier of the environment.
+    pub intensity: f32,
+
+    /// Spherical harmonics coefficients (3 bands) of the irradiance.
+    pub irradiance_coefficients: [Vector3<f32>; 9],
+
+    /// Size of the largest specular cube face, in pixels.
+    pub specular_image_size: u32,
+
+    /// Specular cubemap, one entry per mip level (largest first) with the
+    /// faces in `+X, -X, +Y, -Y, +Z, -Z` order.
+    pub specular_images: Vec<[Arc<RgbaImage>; 6]>,
+}
+
+impl ImageBasedLight {
+    /// Load the image-based light referenced by a scene, if any.
+    pub(crate) fn load(
+        doc: &gltf::Document,
+        gltf_scene: &gltf::Scene,
+        data: &mut GltfData,
+    ) -> Option<Self> {
+        let light = light_json(doc, gltf_scene)?;
+
+        let floats = |value: &Value| -> Vec<f32> {
+            value.as_array().map_or_else(Vec::new, |values| {
+                values
+                    .iter()
+                    .filter_map(Value::as_f64)
+                    .map(|v| v as f32)
+                    .collect()
+            })
+        };
+
+        let rotation = match floats(&light["rotation"])[..] {
+            [x, y, z, w] => Quaternion::new(w, x, y, z),
+            _ => Quaternion::one(),
+        };
+        let mut irradiance_coefficients = [Vector3::zero(); 9];
+        if let Some(coefficients) = light["irradianceCoefficients"].as_array() {
+            for (dst, src) in irradiance_coefficients.iter_mut().zip(coefficients) {
+                if let [r, g, b] = floats(src)[..] {
+                    *dst = Vector3::new(r, g, b);
+                }
+            }
+        }
+
+        let mut specular_images = vec![];
+        for faces in specular_image_indices(light) {
+            let faces: Vec<_> = faces
+                .into_iter()
+                .filter_map(|face| doc.images().nth(face))
+                .map(|image| data.load_rgba_image(&image))
+                .collect();
+            if let Ok(faces) = <[_; 6]>::try_from(faces) {
+                specular_images.push(faces);
+            }
+        }
+
+        Some(Self {
+            #[cfg(feature = "names")]
+            name: light["name"].as_str().map(String::from),
+            rotation,
+            intensity: light["intensity"].as_f64().unwrap_or(1.) as f32,
+            irradiance_coefficients,
+            specular_image_size: light["specularImageSize"].as_u64().unwrap_or(0) as u32,
+            specular_images,
+        })
+    }
+}
+
+/// Indices of the images used by the image-based light of a scene.
+pub(crate) fn image_indices(doc: &gltf::Document, gltf_scene: &gltf::Scene) -> Vec<usize> {
+    light_json(doc, gltf_scene)
+        .map(|light| {
+            specular_image_indices(light)
+                .into_iter()
+                .flatten()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Light of the `EXT_lights_image_based` extension referenced by a scene.
+fn light_json<'a>(doc: &'a gltf::Document, gltf_scene: &gltf::Scene) -> Option<&'a Value> {
+    let index = gltf_scene
+        .extension_value("EXT_lights_image_based")?
+        .get("light")?
+        .as_u64()? as usize;
+    doc.as_json()
+        .extensions
+        .as_ref()?
+        .others
+        .get("EXT_lights_image_based")?
+        .get("lights")?
+        .get(index)
+}
+
+/// Image indices of the faces of every mip level of the specular cube map.
+fn specular_image_indices(light: &Value) -> Vec<Vec<usize>> {
+    light["specularImages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|faces| {
+            faces
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|face| Some(face.as_u64()? as usize))
+                .collect()
+        })
+        .collect()
+}
@@ -1,46 +1,78 @@
+use crate::utils::uniform_scale;
+use crate::LoadOptions;
 use cgmath::*;
 use gltf::khr_lights_punctual::{Kind, Light as GltfLight};
 
-/// Represents a light.
+/// Represents a light (`KHR_lights_punctual`).
+///
+/// # Example
+///
+/// ```
+/// use easy_gltf::LightKind;
+///
+/// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+/// for light in scenes[0].lights.iter() {
+///     let radiance = light.color * light.intensity;
+///     match light.kind {
+///         LightKind::Directional => println!("Sun along {:?}", light.direction()),
+///         LightKind::Point { .. } => println!("Bulb at {:?}", light.position()),
+///         LightKind::Spot { .. } => println!("Spot at {:?}", light.position()),
+///     }
+/// }
+/// ```
 #[derive(Clone, Debug)]
-pub enum Light {
+pub struct Light {
+    #[cfg(feature = "names")]
+    /// Light name. Requires the `names` feature.
+    pub name: Option<String>,
+
+    #[cfg(feature = "extras")]
+    /// Light extra data. Requires the `extras` feature.
+    pub extras: gltf::json::extras::Extras,
+
+    /// Index of the glTF node hosting the light.
+    pub node_index: usize,
+
+    #[cfg(feature = "names")]
+    /// Name of the glTF node hosting the light. Requires the `names` feature.
+    pub node_name: Option<String>,
+
+    #[cfg(feature = "extras")]
+    /// Extra data of the glTF node hosting the light. Requires the `extras`
+    /// feature.
+    pub node_extras: gltf::json::extras::Extras,
+
+    /// Transform matrix of the light node (light to world), lights shine along
+    /// their local -Z axis.
+    pub transform: Matrix4<f32>,
+
+    /// Color of the light, in linear space.
+    pub color: Vector3<f32>,
+
+    /// Intensity of the light, in lux (lm/m2) for directional lights and in
+    /// candela (lm/sr) for point and spot lights.
+    pub intensity: f32,
+
+    /// Type of the light with its specific parameters.
+    pub kind: LightKind,
+}
+
+/// Light types with their specific parameters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LightKind {
     /// Directional lights are light sources that act as though they are
     /// infinitely far away and emit light in the `direction`. Because it is at
-    /// an infinite distance, the light is not attenuated. Its intensity is
-    /// defined in lumens per metre squared, or lux (lm/m2).
-    Directional {
-        #[cfg(feature = "names")]
-        /// Light name. Requires the `names` feature.
-        name: Option<String>,
-        #[cfg(feature = "extras")]
-        /// Light extra data. Requires the `extras` feature
-        extras: gltf::json::extras::Extras,
-        /// Direction of the directional light
-        direction: Vector3<f32>,
-        /// Color of the directional light
-        color: Vector3<f32>,
-        /// Intensity of the directional light
-        intensity: f32,
-    },
+    /// an infinite distance, the light is not attenuated.
+    Directional,
 
     /// Point lights emit light in all directions from their `position` in space;
     /// The brightness of the light attenuates in a physically correct manner as
     /// distance increases from the light's position (i.e.  brightness goes like
-    /// the inverse square of the distance). Point light intensity is defined in
-    /// candela, which is lumens per square radian (lm/sr).
+    /// the inverse square of the distance).
     Point {
-        #[cfg(feature = "names")]
-        /// Light name. Requires the `names` feature.
-        name: Option<String>,
-        #[cfg(feature = "extras")]
-        /// Light extra data. Requires the `extras` feature
-        extras: gltf::json::extras::Extras,
-        /// Position of the point light
-        position: Vector3<f32>,
-        /// Color of the point light
-        color: Vector3<f32>,
-        /// Intensity of the point light
-        intensity: f32,
+        /// Distance cutoff at which the light's intensity may be considered to
+        /// have reached zero. When `None`, the range is assumed to be infinite.
+        range: Option<f32>,
     },
 
     /// Spot lights emit light in a cone in `direction`. The angle and falloff
@@ -49,25 +81,13 @@ pub enum Light {
     /// in a physically correct manner as distance increases from the light's
     /// position (i.e. brightness goes like the inverse square of the distance).
     /// Spot light intensity refers to the brightness inside the
-    /// `inner_cone_angle` (and at the location of the light) and is defined in
-    /// candela, which is lumens per square radian (lm/sr). Engines that don't
+    /// `inner_cone_angle` (and at the location of the light). Engines that don't
     /// support two angles for spotlights should use outer_cone_angle as the
     /// spotlight angle (leaving `inner_cone_angle` to implicitly be `0`).
     Spot {
-        #[cfg(feature = "names")]
-        /// Light name. Requires the `names` feature.
-        name: Option<String>,
-        #[cfg(feature = "extras")]
-        /// Light extra data. Requires the `extras` feature
-        extras: gltf::json::extras::Extras,
-        /// Position of the spot light
-        position: Vector3<f32>,
-        /// Direction of the spot light
-        direction: Vector3<f32>,
-        /// Color of the spot light
-        color: Vector3<f32>,
-        /// Intensity of the spot light
-        intensity: f32,
+        /// Distance cutoff at which the light's intensity may be considered to
+        /// have reached zero. When `None`, the range is assumed to be infinite.
+        range: Option<f32>,
         /// Inner cone angle of the spot light
         inner_cone_angle: f32,
         /// Outer cone angle of the spot light
@@ -76,42 +96,69 @@ pub enum Light {
 }
 
 impl Light {
-    pub(crate) fn load(gltf_light: GltfLight, transform: &Matrix4<f32>) -> Self {
-        match gltf_light.kind() {
-            Kind::Directional => Light::Directional {
-                #[cfg(feature = "names")]
-                name: gltf_light.name().map(String::from),
-                #[cfg(feature = "extras")]
-                extras: gltf_light.extras().clone(),
-                direction: -1.
-                    * Vector3::new(transform[2][0], transform[2][1], transform[2][2]).normalize(),
-                intensity: gltf_light.intensity(),
-                color: Vector3::from(gltf_light.color()),
-            },
-            Kind::Point => Light::Point {
-                #[cfg(feature = "names")]
-                name: gltf_light.name().map(String::from),
-                #[cfg(feature = "extras")]
-                extras: gltf_light.extras().clone(),
-                position: Vector3::new(transform[3][0], transform[3][1], transform[3][2]),
-                intensity: gltf_light.intensity(),
-                color: Vector3::from(gltf_light.color()),
-            },
-            Kind::Spot {
-                inner_cone_angle,
-                outer_cone_angle,
-            } => Light::Spot {
-                #[cfg(feature = "names")]
-                name: gltf_light.name().map(String::from),
-                #[cfg(feature = "extras")]
-                extras: gltf_light.extras().clone(),
-                position: Vector3::new(transform[3][0], transform[3][1], transform[3][2]),
-                direction: -1.
-                    * Vector3::new(transform[2][0], transform[2][1], transform[2][2]).normalize(),
-                intensity: gltf_light.intensity(),
-                color: Vector3::from(gltf_light.color()),
-                inner_cone_angle,
-                outer_cone_angle,
+    /// Position of the light. Meaningless for directional lights.
+    pub fn position(&self) -> Vector3<f32> {
+        self.transform.w.truncate()
+    }
+
+    /// Direction in which the light shines. Meaningless for point lights.
+    pub fn direction(&self) -> Vector3<f32> {
+        -self.transform.z.truncate().normalize()
+    }
+
+    /// Range of point and spot lights, `None` when infinite.
+    pub fn range(&self) -> Option<f32> {
+        match self.kind {
+            LightKind::Directional => None,
+            LightKind::Point { range } | LightKind::Spot { range, .. } => range,
+        }
+    }
+
+    /// Transform the light, scaling its range like the distances of the scene.
+    pub(crate) fn apply_transform(&mut self, transform: &Matrix4<f32>) {
+        self.transform = transform * self.transform;
+        if let LightKind::Point { range: Some(range) }
+        | LightKind::Spot {
+            range: Some(range), ..
+        } = &mut self.kind
+        {
+            *range *= uniform_scale(transform);
+        }
+    }
+
+    pub(crate) fn load(
+        node: &gltf::Node,
+        gltf_light: GltfLight,
+        transform: &Matrix4<f32>,
+        options: &LoadOptions,
+    ) -> Self {
+        let mut transform = *transform;
+        transform.w = (transform.w.truncate() * options.scale_factor).extend(1.);
+        let range = gltf_light.range().map(|r| r * options.scale_factor);
+        Light {
+            #[cfg(feature = "names")]
+            name: gltf_light.name().map(String::from),
+            #[cfg(feature = "extras")]
+            extras: gltf_light.extras().clone(),
+            node_index: node.index(),
+            #[cfg(feature = "names")]
+            node_name: node.name().map(String::from),
+            #[cfg(feature = "extras")]
+            node_extras: node.extras().clone(),
+            transform,
+            color: Vector3::from(gltf_light.color()),
+            intensity: gltf_light.intensity(),
+            kind: match gltf_light.kind() {
+                Kind::Directional => LightKind::Directional,
+                Kind::Point => LightKind::Point { range },
+                Kind::Spot {
+                    inner_cone_angle,
+                    outer_cone_angle,
+                } => LightKind::Spot {
+                    range,
+                    inner_cone_angle,
+                    outer_cone_angle,
+                },
             },
         }
     }
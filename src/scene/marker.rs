@@ -0,0 +1,54 @@
+use crate::LoadOptions;
+use cgmath::*;
+
+/// A node without mesh, camera nor light, e.g. a spawn point, an attachment
+/// socket holding a child mesh, a locator grouping other nodes or a volume
+/// placed by a designer (an "empty" in Blender). Like models, markers of
+/// children come before the marker of their parent.
+///
+/// Skeleton joints (see [`Skin`](struct.Skin.html)) are not markers.
+///
+/// # Example
+///
+/// ```
+/// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+/// for marker in scenes[0].markers.iter() {
+///     println!("Marker at {:?}", marker.position());
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Marker {
+    #[cfg(feature = "names")]
+    /// Node name. Requires the `names` feature.
+    pub name: Option<String>,
+
+    #[cfg(feature = "extras")]
+    /// Node extra data. Requires the `extras` feature.
+    pub extras: gltf::json::extras::Extras,
+
+    /// Index of the glTF node.
+    pub node_index: usize,
+
+    /// Transform matrix of the node (marker to world).
+    pub transform: Matrix4<f32>,
+}
+
+impl Marker {
+    /// Position of the marker.
+    pub fn position(&self) -> Vector3<f32> {
+        self.transform.w.truncate()
+    }
+
+    pub(crate) fn load(node: &gltf::Node, transform: &Matrix4<f32>, options: &LoadOptions) -> Self {
+        let mut transform = *transform;
+        transform.w = (transform.w.truncate() * options.scale_factor).extend(1.);
+        Self {
+            #[cfg(feature = "names")]
+            name: node.name().map(String::from),
+            #[cfg(feature = "extras")]
+            extras: node.extras().clone(),
+            node_index: node.index(),
+            transform,
+        }
+    }
+}
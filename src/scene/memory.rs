@@ -0,0 +1,99 @@
+use super::Scene;
+use image::{ImageBuffer, Pixel};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Memory used by a scene, see [`Scene::memory_report`](struct.Scene.html#method.memory_report).
+///
+/// All values are in bytes. Textures and materials shared between models are
+/// counted once.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryReport {
+    /// Vertices and indices of every model.
+    pub geometry: usize,
+    /// Number of distinct decoded textures.
+    pub texture_count: usize,
+    /// Decoded RGBA 8 bits textures (base color).
+    pub rgba8_textures: usize,
+    /// Decoded RGB 8 bits textures (normal, emissive).
+    pub rgb8_textures: usize,
+    /// Decoded single channel 8 bits textures (metallic, roughness, occlusion).
+    pub gray8_textures: usize,
+    /// Estimated GPU footprint once uploaded: RGB textures are expanded to
+    /// RGBA (most GPUs don't support 3 channels formats) and a full mipmap
+    /// chain is added to every texture.
+    pub gpu_estimate: usize,
+}
+
+impl MemoryReport {
+    /// Total CPU memory of the geometry and decoded textures.
+    pub fn total(&self) -> usize {
+        self.geometry + self.rgba8_textures + self.rgb8_textures + self.gray8_textures
+    }
+}
+
+impl Scene {
+    /// Compute how much memory is used by the geometry and textures of the
+    /// scene, and estimate the GPU memory required to upload it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/head.glb").expect("Failed to load glTF");
+    /// let report = scenes[0].memory_report();
+    /// println!("CPU: {} bytes, GPU: ~{} bytes", report.total(), report.gpu_estimate);
+    /// ```
+    pub fn memory_report(&self) -> MemoryReport {
+        let mut report = MemoryReport::default();
+        let mut textures = HashSet::new();
+        let mut gpu_textures = 0;
+
+        let mut add = |ptr: *const u8, width: u32, height: u32, channels: usize| -> usize {
+            if !textures.insert(ptr) {
+                return 0;
+            }
+            let pixels = width as usize * height as usize;
+            // RGB is padded to RGBA, mipmaps add a third
+            gpu_textures += pixels * if channels == 3 { 4 } else { channels } * 4 / 3;
+            pixels * channels
+        };
+
+        for model in self.models.iter() {
+            let stats = model.stats();
+            report.geometry += stats.memory;
+
+            let material = &model.material;
+            if let Some(t) = &material.pbr.base_color_texture {
+                report.rgba8_textures += add(ptr(t), t.width(), t.height(), 4);
+            }
+            for t in [
+                material.pbr.metallic_texture.as_ref(),
+                material.pbr.roughness_texture.as_ref(),
+                material.occlusion.as_ref().map(|o| &o.texture),
+                material.alpha_mask.as_ref(),
+            ]
+            .iter()
+            .flatten()
+            {
+                report.gray8_textures += add(ptr(t), t.width(), t.height(), 1);
+            }
+            for t in [
+                material.normal.as_ref().map(|n| &n.texture),
+                material.emissive.texture.as_ref(),
+            ]
+            .iter()
+            .flatten()
+            {
+                report.rgb8_textures += add(ptr(t), t.width(), t.height(), 3);
+            }
+        }
+
+        report.texture_count = textures.len();
+        report.gpu_estimate = report.geometry + gpu_textures;
+        report
+    }
+}
+
+fn ptr<P: Pixel>(texture: &Arc<ImageBuffer<P, Vec<P::Subpixel>>>) -> *const u8 {
+    Arc::as_ptr(texture) as *const u8
+}
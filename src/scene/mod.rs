@@ -1,18 +1,44 @@
+mod animation;
 mod camera;
+mod frustum;
+pub(crate) mod image_based_light;
 mod light;
+mod marker;
+mod memory;
 /// Contains model and material
 /// # Usage
 /// Check [Model](struct.Model.html) for more information about how to use this module.
 pub mod model;
+mod pose;
+mod resample;
+mod root_motion;
+mod skin;
+mod traversal;
+mod validation;
+mod visitor;
 
-use crate::utils::transform_to_matrix;
 use crate::GltfData;
+pub use animation::{Animation, Channel, Interpolation, Keyframes, Property, Track};
 pub use camera::{Camera, Projection};
-pub use light::Light;
+pub use frustum::Frustum;
+pub use image_based_light::ImageBasedLight;
+pub use light::{Light, LightKind};
+pub use marker::Marker;
+pub use memory::MemoryReport;
 pub use model::{Material, Model};
+pub use pose::{NodePose, Pose};
+pub use skin::Skin;
+pub use traversal::SceneItem;
+pub(crate) use traversal::Traversal;
+use traversal::{load_primitives, Visit};
+pub use validation::{Finding, Issue, Severity};
+pub use visitor::SceneVisitor;
 
-use cgmath::*;
-use gltf::scene::Node;
+use cgmath::Matrix4;
+#[cfg(feature = "names")]
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
 
 /// Contains cameras, models and lights of a scene.
 #[derive(Default, Clone, Debug)]
@@ -24,15 +50,55 @@ pub struct Scene {
     /// Scene extra data. Requires the `extras` feature.
     pub extras: gltf::json::extras::Extras,
     /// List of models in the scene
+    ///
+    /// The order is deterministic: root nodes are visited in the order of the
+    /// glTF scene, depth-first with the children of a node visited before the
+    /// node itself. A mesh produces one model per primitive, in the order of
+    /// its primitives. Cameras and lights follow the same node order.
     pub models: Vec<Model>,
     /// List of cameras in the scene
     pub cameras: Vec<Camera>,
     /// List of lights in the scene
     pub lights: Vec<Light>,
+    /// Nodes without mesh, camera nor light (spawn points, sockets...).
+    pub markers: Vec<Marker>,
+    /// Materials used by the models of the scene, in order of first use.
+    /// `Model::material_index()` is an index in this list.
+    ///
+    /// Use `set_material()` to replace a material for every model using it.
+    pub materials: Vec<Arc<Material>>,
+    /// Material of the primitives without material, the glTF default material
+    /// unless `LoadOptions::default_material` is set.
+    pub default_material: Arc<Material>,
+    /// Skins used by the models of the scene, in order of first use.
+    pub skins: Vec<Skin>,
+    /// Animations targeting nodes of the scene.
+    pub animations: Vec<Animation>,
+    /// Environment lighting of the scene (`EXT_lights_image_based`).
+    pub image_based_light: Option<ImageBasedLight>,
+
+    /// Rest transform of the nodes of the file, to pose the scene.
+    pub(crate) nodes: Vec<pose::Node>,
+
+    #[cfg(feature = "names")]
+    names: NameMaps,
+}
+
+/// Indices of the objects of a scene by name, built at load.
+#[cfg(feature = "names")]
+#[derive(Default, Clone, Debug)]
+struct NameMaps {
+    models: HashMap<String, Vec<usize>>,
+    cameras: HashMap<String, usize>,
+    lights: HashMap<String, usize>,
 }
 
 impl Scene {
-    pub(crate) fn load(gltf_scene: gltf::Scene, data: &mut GltfData) -> Self {
+    pub(crate) fn load(
+        doc: &gltf::Document,
+        gltf_scene: gltf::Scene,
+        data: &mut GltfData,
+    ) -> gltf::Result<Self> {
         let mut scene = Self::default();
 
         #[cfg(feature = "names")]
@@ -44,37 +110,353 @@ impl Scene {
             scene.extras = gltf_scene.extras().clone();
         }
 
-        for node in gltf_scene.nodes() {
-            scene.read_node(&node, &One::one(), data);
+        // Primitives are converted at the end when converting in parallel
+        let threads = data.options.thread_count();
+        let mut jobs = vec![];
+        let mut traversal = Traversal::new(&gltf_scene, data);
+        while let Some(visit) = traversal.visit(doc, data) {
+            let item = match visit {
+                Visit::Item(item) => item,
+                Visit::Primitive(job) if threads > 1 => {
+                    jobs.push(job);
+                    continue;
+                }
+                Visit::Primitive(job) => SceneItem::Model(job.load(doc, data)?),
+            };
+            match item {
+                SceneItem::Model(model) => scene.models.push(model),
+                SceneItem::Camera(camera) => scene.cameras.push(camera),
+                SceneItem::Light(light) => scene.lights.push(light),
+                SceneItem::Marker(marker) => scene.markers.push(marker),
+                SceneItem::Material(material) => {
+                    if !scene.materials.iter().any(|m| Arc::ptr_eq(m, &material)) {
+                        scene.materials.push(material);
+                    }
+                }
+            }
+        }
+        if !jobs.is_empty() {
+            scene.models = load_primitives(doc, &jobs, data, threads)?;
+        }
+
+        scene.default_material = data.default_material();
+        scene.nodes = pose::Node::load_all(doc);
+        scene.image_based_light = ImageBasedLight::load(doc, &gltf_scene, data);
+
+        let nodes = scene_nodes(&gltf_scene);
+        scene.animations = doc
+            .animations()
+            .filter(|animation| {
+                animation
+                    .channels()
+                    .any(|channel| nodes.contains(&channel.target().node().index()))
+            })
+            .map(|animation| Animation::load(animation, data))
+            .collect::<gltf::Result<_>>()?;
+
+        scene.index_materials();
+        for model in scene.models.iter() {
+            if let Some(index) = model.skin_index {
+                if !scene.skins.iter().any(|skin| skin.index == index) {
+                    let skin = doc.skins().nth(index).unwrap();
+                    scene.skins.push(Skin::load(skin, data)?);
+                }
+            }
         }
-        scene
+        #[cfg(feature = "names")]
+        scene.index_names();
+        Ok(scene)
     }
 
-    fn read_node(&mut self, node: &Node, parent_transform: &Matrix4<f32>, data: &mut GltfData) {
-        // Compute transform of the current node
-        let transform = parent_transform * transform_to_matrix(node.transform());
+    fn index_materials(&mut self) {
+        for model in self.models.iter_mut() {
+            model.material_index = match self
+                .materials
+                .iter()
+                .position(|m| Arc::ptr_eq(m, &model.material))
+            {
+                Some(i) => i,
+                None => {
+                    self.materials.push(model.material.clone());
+                    self.materials.len() - 1
+                }
+            };
+        }
+    }
 
-        // Recurse on children
-        for child in node.children() {
-            self.read_node(&child, &transform, data);
+    /// Replace the material at `index` in `materials` and in every model
+    /// referencing it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// let mut scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// let scene = &mut scenes[0];
+    /// let mut material = (*scene.materials[0]).clone();
+    /// material.pbr.roughness_factor = 0.2;
+    /// scene.set_material(0, Arc::new(material));
+    /// assert_eq!(scene.models[0].material().pbr.roughness_factor, 0.2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_material(&mut self, index: usize, material: Arc<Material>) {
+        self.materials[index] = material.clone();
+        for model in self.models.iter_mut() {
+            if model.material_index == index {
+                model.material = material.clone();
+            }
+        }
+    }
+
+    /// Transform every model, camera, light and marker of the scene, e.g. to place a
+    /// loaded prefab in world space.
+    ///
+    /// Positions are transformed by `transform`, normals by its inverse
+    /// transpose and triangles are rewound if it mirrors the scene. Camera clip
+    /// planes, orthographic magnifications and light ranges are scaled by the
+    /// uniform scale of `transform`.
+    ///
+    /// **Note**: Skins and animations are not transformed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cgmath::*;
+    /// let mut scene = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF").remove(0);
+    /// scene.apply_transform(&Matrix4::from_translation(Vector3::new(10., 0., 0.)));
+    /// ```
+    pub fn apply_transform(&mut self, transform: &Matrix4<f32>) {
+        for model in self.models.iter_mut() {
+            model.apply_transform(transform);
+        }
+        for camera in self.cameras.iter_mut() {
+            camera.apply_transform(transform);
+        }
+        for light in self.lights.iter_mut() {
+            light.apply_transform(transform);
+        }
+        for marker in self.markers.iter_mut() {
+            marker.transform = transform * marker.transform;
+        }
+    }
+
+    /// Append the models, cameras, lights, markers and animations of another scene,
+    /// e.g. to compose a level from several glTF files. Materials shared by
+    /// both scenes (same `Arc`, see [`Loader`](struct.Loader.html)) are only
+    /// listed once in `materials`.
+    ///
+    /// The name, extras, default material and image based light of `self` are
+    /// kept (the image based light of `other` is used if `self` has none).
+    ///
+    /// **Note**: glTF indices (`Model::node_index()`, `Skin::index`...) refer
+    /// to the file each object comes from, so they may collide after a merge.
+    /// Poses are evaluated with the nodes of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut loader = easy_gltf::Loader::default();
+    /// let mut level = loader.load("tests/cube.glb").expect("Failed to load glTF").remove(0);
+    /// let prop = loader.load("tests/head.glb").expect("Failed to load glTF").remove(0);
+    /// level.merge(prop);
+    /// ```
+    pub fn merge(&mut self, other: Scene) {
+        self.models.extend(other.models);
+        self.cameras.extend(other.cameras);
+        self.lights.extend(other.lights);
+        self.markers.extend(other.markers);
+        for material in other.materials {
+            if !self.materials.iter().any(|m| Arc::ptr_eq(m, &material)) {
+                self.materials.push(material);
+            }
+        }
+        self.skins.extend(other.skins);
+        self.animations.extend(other.animations);
+        if self.image_based_light.is_none() {
+            self.image_based_light = other.image_based_light;
+        }
+        if self.nodes.is_empty() {
+            self.nodes = other.nodes;
+        }
+        self.index_materials();
+        #[cfg(feature = "names")]
+        {
+            self.names = NameMaps::default();
+            self.index_names();
+        }
+    }
+
+    /// Keep only the models for which `keep` returns `true`, e.g. to strip
+    /// collision meshes or helpers after load. Materials only used by the
+    /// removed models are removed from `materials`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// // Remove untextured models and models without normals
+    /// scenes[0].retain_models(|model| {
+    ///     model.has_normals() && model.material().pbr.base_color_texture.is_some()
+    /// });
+    /// ```
+    pub fn retain_models<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&Model) -> bool,
+    {
+        self.remove_models(|model| !keep(model));
+    }
+
+    /// Remove the models for which `remove` returns `true` and return them, in
+    /// order. Materials only used by the removed models are removed from
+    /// `materials`.
+    pub fn remove_models<F>(&mut self, mut remove: F) -> Vec<Model>
+    where
+        F: FnMut(&Model) -> bool,
+    {
+        let (removed, kept) = std::mem::take(&mut self.models)
+            .into_iter()
+            .partition::<Vec<_>, _>(|model| remove(model));
+        self.models = kept;
+
+        let models = &self.models;
+        self.materials.retain(|material| {
+            let used = |model: &Model| Arc::ptr_eq(&model.material, material);
+            !removed.iter().any(used) || models.iter().any(used)
+        });
+        self.index_materials();
+        #[cfg(feature = "names")]
+        {
+            self.names = NameMaps::default();
+            self.index_names();
         }
+        removed
+    }
 
-        // Load camera
-        if let Some(camera) = node.camera() {
-            self.cameras.push(Camera::load(camera, &transform));
+    /// Merge several scenes into one, see [`merge`](#method.merge). Returns an
+    /// empty scene if `scenes` is empty.
+    pub fn merge_all(scenes: Vec<Scene>) -> Scene {
+        let mut scenes = scenes.into_iter();
+        let mut merged = scenes.next().unwrap_or_default();
+        for scene in scenes {
+            merged.merge(scene);
         }
+        merged
+    }
 
-        // Load light
-        if let Some(light) = node.light() {
-            self.lights.push(Light::load(light, &transform));
+    #[cfg(feature = "names")]
+    fn index_names(&mut self) {
+        let names = &mut self.names;
+        for (i, model) in self.models.iter().enumerate() {
+            if let Some(name) = model.mesh_name() {
+                names.models.entry(name.to_string()).or_default().push(i);
+            }
+        }
+        for (i, camera) in self.cameras.iter().enumerate() {
+            if let Some(name) = &camera.name {
+                names.cameras.entry(name.clone()).or_insert(i);
+            }
+        }
+        for (i, light) in self.lights.iter().enumerate() {
+            if let Some(name) = &light.name {
+                names.lights.entry(name.to_string()).or_insert(i);
+            }
         }
+    }
+
+    /// First model (i.e. first primitive) of the mesh with the given name.
+    /// Requires the `names` feature.
+    ///
+    /// **Note**: Lookups use maps built at load. The scene is scanned instead
+    /// when the map is out of date (objects reordered, removed or added).
+    #[cfg(feature = "names")]
+    pub fn model_by_name(&self, name: &str) -> Option<&Model> {
+        self.models_by_mesh_name(name).next()
+    }
 
-        // Load model
-        if let Some(mesh) = node.mesh() {
-            for (i, primitive) in mesh.primitives().enumerate() {
+    /// All the models (one per primitive and per instance) of the meshes with
+    /// the given name. Requires the `names` feature.
+    ///
+    /// **Note**: Lookups use maps built at load. The scene is scanned instead
+    /// when the map is out of date (objects reordered, removed or added).
+    #[cfg(feature = "names")]
+    pub fn models_by_mesh_name<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a Model> {
+        let indices = self.names.models.get(name).filter(|indices| {
+            indices.iter().all(|&i| {
                 self.models
-                    .push(Model::load(&mesh, i, primitive, &transform, data));
+                    .get(i)
+                    .is_some_and(|model| model.mesh_name() == Some(name))
+            })
+        });
+        let scan = match indices {
+            Some(_) => None,
+            None => {
+                let name = name.to_string();
+                Some(
+                    self.models
+                        .iter()
+                        .filter(move |model| model.mesh_name() == Some(name.as_str())),
+                )
             }
+        };
+        let indexed = indices.map(|indices| indices.iter().map(move |&i| &self.models[i]));
+        indexed
+            .into_iter()
+            .flatten()
+            .chain(scan.into_iter().flatten())
+    }
+
+    /// First camera with the given name. Requires the `names` feature.
+    ///
+    /// **Note**: Lookups use maps built at load. The scene is scanned instead
+    /// when the map is out of date (objects reordered, removed or added).
+    #[cfg(feature = "names")]
+    pub fn camera_by_name(&self, name: &str) -> Option<&Camera> {
+        let named = |camera: &&Camera| camera.name.as_deref() == Some(name);
+        match self
+            .names
+            .cameras
+            .get(name)
+            .and_then(|&i| self.cameras.get(i))
+        {
+            Some(camera) if named(&camera) => Some(camera),
+            _ => self.cameras.iter().find(named),
         }
     }
+
+    /// First light with the given name. Requires the `names` feature.
+    ///
+    /// **Note**: Lookups use maps built at load. The scene is scanned instead
+    /// when the map is out of date (objects reordered, removed or added).
+    #[cfg(feature = "names")]
+    pub fn light_by_name(&self, name: &str) -> Option<&Light> {
+        let named = |light: &&Light| light.name.as_deref() == Some(name);
+        match self
+            .names
+            .lights
+            .get(name)
+            .and_then(|&i| self.lights.get(i))
+        {
+            Some(light) if named(&light) => Some(light),
+            _ => self.lights.iter().find(named),
+        }
+    }
+}
+
+/// Indices of every node of a scene.
+pub(crate) fn scene_nodes(gltf_scene: &gltf::Scene) -> HashSet<usize> {
+    fn visit(node: gltf::Node, nodes: &mut HashSet<usize>) {
+        nodes.insert(node.index());
+        for child in node.children() {
+            visit(child, nodes);
+        }
+    }
+
+    let mut nodes = HashSet::new();
+    for node in gltf_scene.nodes() {
+        visit(node, &mut nodes);
+    }
+    nodes
 }
@@ -0,0 +1,53 @@
+use super::Model;
+use cgmath::*;
+
+impl Model {
+    /// Translate the model so the center of its bounding box is at the
+    /// origin. Returns the applied transform, identity for empty models.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cgmath::*;
+    /// let mut scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// let transform = scenes[0].models[0].recenter();
+    /// // Move the other objects (cameras, lights...) with `transform` too.
+    /// ```
+    pub fn recenter(&mut self) -> Matrix4<f32> {
+        let transform = match self.bounds() {
+            Some((min, max)) => Matrix4::from_translation(-(min + max) / 2.),
+            None => Matrix4::identity(),
+        };
+        self.apply_transform(&transform);
+        transform
+    }
+
+    /// Recenter the model and scale it uniformly so it fits in the cube from
+    /// `-0.5` to `0.5` on every axis, e.g. to frame arbitrary assets in
+    /// thumbnails. Returns the applied transform, identity for empty models.
+    pub fn fit_unit_cube(&mut self) -> Matrix4<f32> {
+        let transform = match self.bounds() {
+            Some((min, max)) => {
+                let size = max - min;
+                let extent = size.x.max(size.y).max(size.z);
+                let scale = if extent > 0. { 1. / extent } else { 1. };
+                Matrix4::from_scale(scale) * Matrix4::from_translation(-(min + max) / 2.)
+            }
+            None => Matrix4::identity(),
+        };
+        self.apply_transform(&transform);
+        transform
+    }
+
+    /// Minimum and maximum corners of the bounding box of the vertices.
+    pub(crate) fn bounds(&self) -> Option<(Vector3<f32>, Vector3<f32>)> {
+        let first = self.vertices.first()?.position;
+        Some(self.vertices.iter().fold((first, first), |(min, max), v| {
+            let p = v.position;
+            (
+                Vector3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)),
+                Vector3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)),
+            )
+        }))
+    }
+}
@@ -0,0 +1,56 @@
+use super::{Model, Vertex};
+use ::half::f16;
+
+/// Packed counterpart of [`Vertex`](struct.Vertex.html) with half-precision
+/// normals, tangents and texture coordinates, for bandwidth-constrained
+/// targets (mobile, WebGPU...). Requires the `half` feature.
+///
+/// Positions stay `f32` to avoid visible cracks on large models. The layout
+/// only uses formats supported by every GPU API: `Float32x3` position,
+/// `Float16x4` normal and tangent, `Float16x2` texture coordinates (and
+/// `Unorm16x4` color), 32 bytes without color instead of 48.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HalfVertex {
+    /// Position
+    pub position: [f32; 3],
+    /// Normalized normal, the w component is `0`
+    pub normal: [f16; 4],
+    /// Tangent normal
+    /// The w component is the handedness of the tangent basis (can be -1 or 1)
+    pub tangent: [f16; 4],
+    /// Texture coordinates
+    pub tex_coords: [f16; 2],
+    /// Vertex color
+    #[cfg(feature = "vertex-color")]
+    pub color: [u16; 4],
+}
+
+impl From<Vertex> for HalfVertex {
+    fn from(vertex: Vertex) -> Self {
+        let half = |v: f32| f16::from_f32(v);
+        HalfVertex {
+            position: vertex.position.into(),
+            normal: vertex.normal.extend(0.).map(half).into(),
+            tangent: vertex.tangent.map(half).into(),
+            tex_coords: vertex.tex_coords.map(half).into(),
+            #[cfg(feature = "vertex-color")]
+            color: vertex.color.into(),
+        }
+    }
+}
+
+impl Model {
+    /// Vertices converted to [`HalfVertex`](struct.HalfVertex.html). Requires
+    /// the `half` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// let vertices = scenes[0].models[0].half_vertices();
+    /// ```
+    pub fn half_vertices(&self) -> Vec<HalfVertex> {
+        self.vertices.iter().map(|&vertex| vertex.into()).collect()
+    }
+}
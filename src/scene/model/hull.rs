@@ -0,0 +1,149 @@
+use super::{Mode, Model};
+use cgmath::*;
+use std::collections::{HashMap, HashSet};
+
+/// Distance under which points are considered on a plane, relative to the
+/// largest dimension of the model bounding box.
+const EPSILON: f32 = 1e-5;
+
+/// A face of the hull with its unit outward normal.
+struct Face {
+    corners: [usize; 3],
+    normal: Vector3<f32>,
+}
+
+impl Face {
+    fn new(points: &[Vector3<f32>], corners: [usize; 3]) -> Self {
+        let [a, b, c] = corners.map(|i| points[i]);
+        Self {
+            corners,
+            normal: (b - a).cross(c - a).normalize(),
+        }
+    }
+
+    fn distance(&self, points: &[Vector3<f32>], p: Vector3<f32>) -> f32 {
+        self.normal.dot(p - points[self.corners[0]])
+    }
+}
+
+impl Model {
+    /// Convex hull of the vertices of the model, as an indexed `Triangles`
+    /// model with the faces pointing outward. The other vertex attributes
+    /// are copied from the original vertices, so the hull is meant for
+    /// collision or culling rather than rendering.
+    ///
+    /// **Note**: Returns `None` if the vertices are flat (coplanar, aligned
+    /// or fewer than 4), since the hull has no volume.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/head.glb").expect("Failed to load glTF");
+    /// let hull = scenes[0].models[0].convex_hull().unwrap();
+    /// assert!(hull.signed_volume() > 0.);
+    /// ```
+    pub fn convex_hull(&self) -> Option<Model> {
+        // Unique positions with the first vertex having each of them
+        let mut unique = HashMap::new();
+        let mut representatives = vec![];
+        let mut points = vec![];
+        for (i, vertex) in self.vertices.iter().enumerate() {
+            let p = vertex.position;
+            unique
+                .entry([p.x, p.y, p.z].map(f32::to_bits))
+                .or_insert_with(|| {
+                    representatives.push(i as u32);
+                    points.push(p);
+                });
+        }
+
+        let (min, max) = self.bounds()?;
+        let epsilon = (max - min).x.max((max - min).y).max((max - min).z) * EPSILON;
+        let mut faces = initial_simplex(&points, epsilon)?;
+        let centroid = points.iter().sum::<Vector3<f32>>() / points.len() as f32;
+
+        // Farthest points first so most inner points are skipped at once
+        let mut order: Vec<_> = (0..points.len()).collect();
+        order.sort_by(|&a, &b| {
+            let distance = |i: usize| (points[i] - centroid).magnitude2();
+            distance(b).total_cmp(&distance(a))
+        });
+        for p in order {
+            let visible: Vec<_> = faces
+                .iter()
+                .map(|face| face.distance(&points, points[p]) > epsilon)
+                .collect();
+            if !visible.contains(&true) {
+                continue;
+            }
+
+            // The horizon is made of the edges of visible faces shared with
+            // hidden faces, they are joined to the new point
+            let edges: HashSet<_> = faces
+                .iter()
+                .zip(visible.iter())
+                .filter(|(_, &visible)| visible)
+                .flat_map(|(face, _)| {
+                    let [a, b, c] = face.corners;
+                    [(a, b), (b, c), (c, a)]
+                })
+                .collect();
+            let mut visible = visible.into_iter();
+            faces.retain(|_| !visible.next().unwrap());
+            for &(a, b) in edges.iter() {
+                if !edges.contains(&(b, a)) {
+                    faces.push(Face::new(&points, [a, b, p]));
+                }
+            }
+        }
+
+        // Keep the hull points only
+        let mut remap = HashMap::new();
+        let mut vertices = vec![];
+        let indices = faces
+            .iter()
+            .flat_map(|face| face.corners)
+            .map(|p| {
+                *remap.entry(p).or_insert_with(|| {
+                    vertices.push(representatives[p]);
+                    vertices.len() as u32 - 1
+                })
+            })
+            .collect();
+        Some(self.subset(Mode::Triangles, &vertices, Some(indices)))
+    }
+}
+
+/// Tetrahedron of points spanning the most space, with outward faces. `None`
+/// if all the points are within `epsilon` of a plane.
+fn initial_simplex(points: &[Vector3<f32>], epsilon: f32) -> Option<Vec<Face>> {
+    let farthest = |distance: &dyn Fn(Vector3<f32>) -> f32| {
+        (0..points.len())
+            .map(|i| (i, distance(points[i])))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .filter(|&(_, d)| d > epsilon)
+            .map(|(i, _)| i)
+    };
+
+    let a = (0..points.len()).min_by(|&i, &j| points[i].x.total_cmp(&points[j].x))?;
+    let b = farthest(&|p| (p - points[a]).magnitude())?;
+    let axis = (points[b] - points[a]).normalize();
+    let c = farthest(&|p| (p - points[a]).cross(axis).magnitude())?;
+    let normal = axis.cross(points[c] - points[a]).normalize();
+    let d = farthest(&|p| normal.dot(p - points[a]).abs())?;
+
+    let centroid = (points[a] + points[b] + points[c] + points[d]) / 4.;
+    Some(
+        [[a, b, c], [a, b, d], [b, c, d], [c, a, d]]
+            .iter()
+            .map(|&[x, y, z]| {
+                let face = Face::new(points, [x, y, z]);
+                if face.distance(points, centroid) > 0. {
+                    Face::new(points, [x, z, y])
+                } else {
+                    face
+                }
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,76 @@
+use super::Model;
+use crate::utils::as_bytes;
+
+/// Indices of a model with the smallest index type fitting its vertices, see
+/// `Model::index_buffer()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IndexBuffer {
+    /// 16 bits indices, used when the model has at most 65535 vertices. The
+    /// index `0xFFFF` is never used, it restarts strips in most graphics APIs.
+    U16(Vec<u16>),
+    /// 32 bits indices.
+    U32(Vec<u32>),
+}
+
+impl IndexBuffer {
+    /// Number of indices.
+    pub fn len(&self) -> usize {
+        match self {
+            IndexBuffer::U16(indices) => indices.len(),
+            IndexBuffer::U32(indices) => indices.len(),
+        }
+    }
+
+    /// Whether there is no index.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Size of an index in bytes, `2` or `4`.
+    pub fn index_size(&self) -> usize {
+        match self {
+            IndexBuffer::U16(_) => 2,
+            IndexBuffer::U32(_) => 4,
+        }
+    }
+
+    /// Indices as bytes, ready to be copied into an index buffer.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            IndexBuffer::U16(indices) => as_bytes(indices),
+            IndexBuffer::U32(indices) => as_bytes(indices),
+        }
+    }
+}
+
+impl Model {
+    /// Indices of the model as `u16` when the vertex count allows it (at most
+    /// 65535 vertices, so the primitive restart index `0xFFFF` is free), `u32`
+    /// otherwise. `None` if the model isn't indexed.
+    ///
+    /// Indices out of the `u16` range (e.g. invalid indices) are kept as
+    /// `u32` rather than truncated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_gltf::model::IndexBuffer;
+    ///
+    /// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// match scenes[0].models[0].index_buffer() {
+    ///     Some(IndexBuffer::U16(indices)) => { /* GL_UNSIGNED_SHORT */ }
+    ///     Some(IndexBuffer::U32(indices)) => { /* GL_UNSIGNED_INT */ }
+    ///     None => { /* glDrawArrays */ }
+    /// }
+    /// ```
+    pub fn index_buffer(&self) -> Option<IndexBuffer> {
+        let indices = self.indices()?;
+        let fits = self.vertices.len() <= u16::MAX as usize
+            && indices.iter().all(|&i| i < u16::MAX as u32);
+        Some(if fits {
+            IndexBuffer::U16(indices.iter().map(|&i| i as u16).collect())
+        } else {
+            IndexBuffer::U32(indices.clone())
+        })
+    }
+}
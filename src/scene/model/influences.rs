@@ -0,0 +1,91 @@
+use crate::utils::{accessor, BufferData};
+use crate::LoadOptions;
+
+/// Joint influences of the vertices of a primitive, reduced to at most 4
+/// influences per vertex.
+pub(crate) struct Influences {
+    pub joints: Vec<[u16; 4]>,
+    pub weights: Vec<[f32; 4]>,
+    /// Approximation made, see `Issue::DroppedInfluences`.
+    pub dropped: Option<DroppedInfluences>,
+}
+
+/// Influences dropped from the vertices of a model, reported by
+/// `Model::validate()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct DroppedInfluences {
+    /// Number of vertices that lost influences.
+    pub count: usize,
+    /// Largest share of the weight of a vertex that was dropped.
+    pub max_weight: f32,
+}
+
+impl Influences {
+    /// Read every set of joints and weights (`JOINTS_n`, `WEIGHTS_n`) and keep
+    /// the `LoadOptions::max_influences` strongest influences of each vertex.
+    pub(crate) fn load(
+        primitive: &gltf::Primitive,
+        buffers: &BufferData,
+        options: &LoadOptions,
+    ) -> Option<Self> {
+        let mut sets = vec![];
+        for set in 0.. {
+            let joints = accessor::read_joints(primitive, set, buffers);
+            match (joints, accessor::read_weights(primitive, set, buffers)) {
+                (Some(joints), Some(weights)) => sets.push((
+                    joints.into_u16().collect::<Vec<_>>(),
+                    weights.into_f32().collect::<Vec<_>>(),
+                )),
+                _ => break,
+            }
+        }
+        let vertex_count = sets.iter().map(|(j, w)| j.len().min(w.len())).min()?;
+        let max = options.max_influences.min(4);
+
+        let mut influences = Influences {
+            joints: Vec::with_capacity(vertex_count),
+            weights: Vec::with_capacity(vertex_count),
+            dropped: None,
+        };
+        let mut all = vec![];
+        for vertex in 0..vertex_count {
+            all.clear();
+            for (joints, weights) in sets.iter() {
+                let pairs = joints[vertex].iter().zip(weights[vertex].iter());
+                all.extend(pairs.filter(|(_, &w)| w > 0.).map(|(&j, &w)| (j, w)));
+            }
+            let total: f32 = all.iter().map(|(_, w)| w).sum();
+            all.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+            let (mut joints, mut weights) = ([0; 4], [0.; 4]);
+            for (i, &(joint, weight)) in all.iter().take(max).enumerate() {
+                joints[i] = joint;
+                weights[i] = weight;
+            }
+            let kept: f32 = weights.iter().sum();
+            if all.len() > max && total > 0. {
+                let dropped = influences.dropped.get_or_insert(DroppedInfluences {
+                    count: 0,
+                    max_weight: 0.,
+                });
+                dropped.count += 1;
+                dropped.max_weight = dropped.max_weight.max(1. - kept / total);
+            }
+            if options.normalize_weights && kept > 0. {
+                weights = weights.map(|w| w / kept);
+            }
+            influences.joints.push(joints);
+            influences.weights.push(weights);
+        }
+
+        #[cfg(feature = "tracing")]
+        if let Some(dropped) = influences.dropped {
+            tracing::warn!(
+                vertices = dropped.count,
+                max_weight = dropped.max_weight,
+                "joint influences dropped"
+            );
+        }
+        Some(influences)
+    }
+}
@@ -0,0 +1,257 @@
+use super::{Model, Vertex};
+use crate::utils::as_bytes;
+use std::mem::size_of;
+
+/// Vertex attribute stored in a vertex buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VertexAttribute {
+    /// `Vertex::position`
+    Position,
+    /// `Vertex::normal`
+    Normal,
+    /// `Vertex::tangent`
+    Tangent,
+    /// `Vertex::tex_coords`
+    TexCoords,
+    /// `Vertex::color`. Requires the `vertex-color` feature.
+    #[cfg(feature = "vertex-color")]
+    Color,
+    /// `Model::joints()`, zeroed for models without skinning data.
+    Joints,
+    /// `Model::weights()` normalized to a sum of `1`, zeroed for models
+    /// without skinning data.
+    Weights,
+}
+
+/// Format of a vertex attribute in a vertex buffer, named like the WebGPU
+/// vertex formats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VertexFormat {
+    /// Two `f32`.
+    Float32x2,
+    /// Three `f32`.
+    Float32x3,
+    /// Four `f32`.
+    Float32x4,
+    /// Four `u16` normalized to `[0, 1]`.
+    Unorm16x4,
+    /// Four `u16`.
+    Uint16x4,
+}
+
+impl VertexAttribute {
+    /// Format of the attribute in `Vertex`.
+    pub fn format(self) -> VertexFormat {
+        match self {
+            VertexAttribute::Position | VertexAttribute::Normal => VertexFormat::Float32x3,
+            VertexAttribute::Tangent => VertexFormat::Float32x4,
+            VertexAttribute::TexCoords => VertexFormat::Float32x2,
+            #[cfg(feature = "vertex-color")]
+            VertexAttribute::Color => VertexFormat::Unorm16x4,
+            VertexAttribute::Joints => VertexFormat::Uint16x4,
+            VertexAttribute::Weights => VertexFormat::Float32x4,
+        }
+    }
+
+    /// Write the attribute of the vertex `index` of a model.
+    fn write(self, model: &Model, index: usize, out: &mut [u8]) {
+        let vertex = &model.vertices[index];
+        let weights;
+        let bytes = match self {
+            VertexAttribute::Position => as_bytes(std::slice::from_ref(&vertex.position)),
+            VertexAttribute::Normal => as_bytes(std::slice::from_ref(&vertex.normal)),
+            VertexAttribute::Tangent => as_bytes(std::slice::from_ref(&vertex.tangent)),
+            VertexAttribute::TexCoords => as_bytes(std::slice::from_ref(&vertex.tex_coords)),
+            #[cfg(feature = "vertex-color")]
+            VertexAttribute::Color => as_bytes(std::slice::from_ref(&vertex.color)),
+            VertexAttribute::Joints => match model.joints.as_ref() {
+                Some(joints) => as_bytes(std::slice::from_ref(&joints[index])),
+                None => return,
+            },
+            VertexAttribute::Weights => match model.weights.as_ref() {
+                Some(all) => {
+                    let sum: f32 = all[index].iter().sum();
+                    weights = all[index].map(|w| if sum > 0. { w / sum } else { 0. });
+                    as_bytes(&weights)
+                }
+                None => return,
+            },
+        };
+        out[..bytes.len()].copy_from_slice(bytes);
+    }
+}
+
+impl VertexFormat {
+    /// Size of the format in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            VertexFormat::Float32x2 => 8,
+            VertexFormat::Float32x3 => 12,
+            VertexFormat::Float32x4 => 16,
+            VertexFormat::Unorm16x4 | VertexFormat::Uint16x4 => 8,
+        }
+    }
+}
+
+/// Location of an attribute in a vertex.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AttributeLayout {
+    /// Attribute stored.
+    pub attribute: VertexAttribute,
+    /// Offset from the start of the vertex, in bytes.
+    pub offset: usize,
+    /// Format of the attribute.
+    pub format: VertexFormat,
+}
+
+/// Memory layout of the vertices of a vertex buffer.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VertexLayout {
+    /// Size of a vertex, in bytes.
+    pub stride: usize,
+    /// Attributes of a vertex, in order of offset.
+    pub attributes: Vec<AttributeLayout>,
+}
+
+impl VertexLayout {
+    /// Layout of [`Vertex`](struct.Vertex.html) and `Model::vertex_bytes()`:
+    /// position, normal, tangent, texture coordinates (and color), without
+    /// padding.
+    pub fn of_vertex() -> Self {
+        let layout = Self::packed(&[
+            VertexAttribute::Position,
+            VertexAttribute::Normal,
+            VertexAttribute::Tangent,
+            VertexAttribute::TexCoords,
+            #[cfg(feature = "vertex-color")]
+            VertexAttribute::Color,
+        ]);
+        debug_assert_eq!(layout.stride, size_of::<Vertex>());
+        layout
+    }
+
+    /// Layout of `Model::skinned_vertex_bytes()` for GPU skinning: the
+    /// attributes of `of_vertex()` followed by the joint indices as four
+    /// `u16` and the normalized weights as four `f32`.
+    ///
+    /// The joints are at byte 48 and the weights at byte 56, for a stride of
+    /// 72 bytes (56, 64 and 80 with the `vertex-color` feature).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_gltf::model::{VertexAttribute, VertexLayout};
+    ///
+    /// let layout = VertexLayout::of_skinned_vertex();
+    /// let joints = layout.attributes.iter().find(|a| a.attribute == VertexAttribute::Joints);
+    /// # #[cfg(not(feature = "vertex-color"))]
+    /// assert_eq!(joints.unwrap().offset, 48);
+    /// ```
+    pub fn of_skinned_vertex() -> Self {
+        let mut layout = Self::of_vertex();
+        let skinning = Self::packed(&[VertexAttribute::Joints, VertexAttribute::Weights]);
+        for attribute in skinning.attributes {
+            layout.attributes.push(AttributeLayout {
+                offset: layout.stride + attribute.offset,
+                ..attribute
+            });
+        }
+        layout.stride += skinning.stride;
+        layout
+    }
+
+    /// Layout with only the given attributes, in this order and without
+    /// padding, e.g. position and texture coordinates for a shadow pass.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_gltf::model::{VertexAttribute, VertexLayout};
+    ///
+    /// let layout = VertexLayout::packed(&[VertexAttribute::Position, VertexAttribute::TexCoords]);
+    /// assert_eq!(layout.stride, 20);
+    /// ```
+    pub fn packed(attributes: &[VertexAttribute]) -> Self {
+        let mut stride = 0;
+        let attributes = attributes
+            .iter()
+            .map(|&attribute| {
+                let layout = AttributeLayout {
+                    attribute,
+                    offset: stride,
+                    format: attribute.format(),
+                };
+                stride += layout.format.size();
+                layout
+            })
+            .collect();
+        VertexLayout { stride, attributes }
+    }
+}
+
+impl Model {
+    /// Vertices as bytes, ready to be copied into a vertex buffer (e.g. with
+    /// `queue.write_buffer`). The layout is described by `vertex_layout()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// let model = &scenes[0].models[0];
+    /// let bytes = model.vertex_bytes();
+    /// assert_eq!(bytes.len(), model.vertices().len() * model.vertex_layout().stride);
+    /// ```
+    pub fn vertex_bytes(&self) -> &[u8] {
+        as_bytes(&self.vertices)
+    }
+
+    /// Layout of `vertex_bytes()`, see
+    /// [`VertexLayout::of_vertex`](struct.VertexLayout.html#method.of_vertex).
+    pub fn vertex_layout(&self) -> VertexLayout {
+        VertexLayout::of_vertex()
+    }
+
+    /// Vertices as bytes following a custom layout, e.g. created with
+    /// [`VertexLayout::packed`](struct.VertexLayout.html#method.packed).
+    /// Bytes not covered by an attribute are zeroed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an attribute doesn't use the format of `Vertex` (see
+    /// `VertexAttribute::format()`) or doesn't fit in the stride.
+    pub fn pack_vertices(&self, layout: &VertexLayout) -> Vec<u8> {
+        for attribute in layout.attributes.iter() {
+            assert_eq!(attribute.format, attribute.attribute.format());
+            assert!(attribute.offset + attribute.format.size() <= layout.stride);
+        }
+        let mut bytes = vec![0; self.vertices.len() * layout.stride];
+        for (index, out) in bytes.chunks_exact_mut(layout.stride).enumerate() {
+            for attribute in layout.attributes.iter() {
+                attribute
+                    .attribute
+                    .write(self, index, &mut out[attribute.offset..]);
+            }
+        }
+        bytes
+    }
+
+    /// Vertices with their joints and weights as bytes, following
+    /// [`VertexLayout::of_skinned_vertex`](struct.VertexLayout.html#method.of_skinned_vertex).
+    /// Returns `None` if the model has no skinning data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_gltf::model::VertexLayout;
+    ///
+    /// let scenes = easy_gltf::load("tests/skin.gltf").expect("Failed to load glTF");
+    /// let bytes = scenes[0].models[0].skinned_vertex_bytes().unwrap();
+    /// assert_eq!(bytes.len(), 3 * VertexLayout::of_skinned_vertex().stride);
+    /// ```
+    pub fn skinned_vertex_bytes(&self) -> Option<Vec<u8>> {
+        if self.joints.is_none() || self.weights.is_none() {
+            return None;
+        }
+        Some(self.pack_vertices(&VertexLayout::of_skinned_vertex()))
+    }
+}
@@ -0,0 +1,61 @@
+use super::Vertex;
+use cgmath::*;
+use std::ops::{Deref, DerefMut};
+
+/// Represents the 2 vertices of a line.
+///
+/// Derefs to `[Vertex; 2]`, so vertices are accessed with `line[i]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Line(pub [Vertex; 2]);
+
+impl Line {
+    /// Length of the line.
+    pub fn length(&self) -> f32 {
+        self.0[0].position.distance(self.0[1].position)
+    }
+
+    /// Normalized direction from the first to the second vertex, `None` if both
+    /// vertices are at the same position.
+    pub fn direction(&self) -> Option<Vector3<f32>> {
+        let direction = self.0[1].position - self.0[0].position;
+        if direction.is_zero() {
+            None
+        } else {
+            Some(direction.normalize())
+        }
+    }
+
+    /// Middle of the line.
+    pub fn midpoint(&self) -> Vector3<f32> {
+        (self.0[0].position + self.0[1].position) / 2.
+    }
+}
+
+impl Deref for Line {
+    type Target = [Vertex; 2];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Line {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<[Vertex; 2]> for Line {
+    fn from(vertices: [Vertex; 2]) -> Self {
+        Line(vertices)
+    }
+}
+
+impl<'a> IntoIterator for &'a Line {
+    type Item = &'a Vertex;
+    type IntoIter = std::slice::Iter<'a, Vertex>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
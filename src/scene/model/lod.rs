@@ -0,0 +1,167 @@
+use super::{BadMode, Mode, Model};
+use cgmath::*;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Finest grid used to cluster vertices, in cells per axis.
+const MAX_RESOLUTION: u32 = 1024;
+
+/// A level of detail generated by
+/// [`Model::generate_lods`](struct.Model.html#method.generate_lods).
+#[derive(Clone, Debug)]
+pub struct Lod {
+    /// The simplified model, an indexed `Triangles` model.
+    pub model: Model,
+    /// Maximum distance a vertex moved, relative to the largest dimension of
+    /// the model bounding box (`0` for the original model). Use it to pick
+    /// the LOD from the projected size of the model on screen.
+    pub error: f32,
+}
+
+impl Model {
+    /// Generate levels of detail with about `ratio` times the number of
+    /// triangles of the model for every ratio of `ratios` (e.g.
+    /// `&[0.5, 0.25, 0.1]`).
+    ///
+    /// Vertices are clustered in a uniform grid as fine as the triangle budget
+    /// allows: the vertices of a cell are merged at their average position
+    /// and the triangles collapsed in the process are removed. Other vertex
+    /// attributes are taken from one vertex of each cell.
+    ///
+    /// **Note**: This function will return an error if the mode isn't
+    /// `Triangles`, `TriangleFan` or `TriangleStrip`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/head.glb").expect("Failed to load glTF");
+    /// let lods = scenes[0].models[0].generate_lods(&[0.5, 0.1]).unwrap();
+    /// for lod in lods.iter() {
+    ///     println!("{} triangles, error {}", lod.model.triangles().unwrap().len(), lod.error);
+    /// }
+    /// ```
+    pub fn generate_lods(&self, ratios: &[f32]) -> Result<Vec<Lod>, BadMode> {
+        if !matches!(
+            self.mode,
+            Mode::Triangles | Mode::TriangleStrip | Mode::TriangleFan
+        ) {
+            return Err(BadMode::new(self, "generate_lods"));
+        }
+
+        let (_, corners) = self.list_indices();
+        let triangle_count = corners.len() / 3;
+        Ok(ratios
+            .iter()
+            .map(|&ratio| {
+                let target = (triangle_count as f32 * ratio).ceil() as usize;
+                if target >= triangle_count {
+                    // Same vertices, indexed as a triangle list like the
+                    // simplified models
+                    let model = Model {
+                        mode: Mode::Triangles,
+                        indices: Some(Arc::new(corners.clone())),
+                        ..self.clone()
+                    };
+                    return Lod { model, error: 0. };
+                }
+
+                // Find the finest grid fitting the triangle budget
+                let (mut low, mut high) = (1, MAX_RESOLUTION);
+                let mut best = self.cluster(&corners, 1);
+                while low < high {
+                    let resolution = (low + high).div_ceil(2);
+                    let lod = self.cluster(&corners, resolution);
+                    if lod.model.indices().map_or(0, Vec::len) / 3 <= target {
+                        best = lod;
+                        low = resolution;
+                    } else {
+                        high = resolution - 1;
+                    }
+                }
+                best
+            })
+            .collect())
+    }
+
+    /// Merge the vertices of every cell of a grid of `resolution` cells per
+    /// axis covering the bounding box of the model.
+    fn cluster(&self, corners: &[u32], resolution: u32) -> Lod {
+        let positions: Vec<_> = self.vertices.iter().map(|v| v.position).collect();
+        let (min, max) = match self.bounds() {
+            Some(bounds) => bounds,
+            None => {
+                return Lod {
+                    model: self.subset(Mode::Triangles, &[], Some(vec![])),
+                    error: 0.,
+                }
+            }
+        };
+        let extent = (max - min).x.max((max - min).y).max((max - min).z);
+        let cell_size = if extent > 0. {
+            extent / resolution as f32
+        } else {
+            1.
+        };
+
+        // Cluster of every vertex, with the first vertex of each cluster
+        let mut cells = HashMap::new();
+        let mut representatives = vec![];
+        let clusters: Vec<u32> = positions
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let cell = (p - min) / cell_size;
+                let key = [cell.x, cell.y, cell.z].map(|c| (c.max(0.) as u32).min(resolution - 1));
+                *cells.entry(key).or_insert_with(|| {
+                    representatives.push(i as u32);
+                    representatives.len() as u32 - 1
+                })
+            })
+            .collect();
+
+        let mut sums = vec![(Vector3::zero(), 0.); representatives.len()];
+        for (p, &cluster) in positions.iter().zip(clusters.iter()) {
+            let (sum, count) = &mut sums[cluster as usize];
+            *sum += *p;
+            *count += 1.;
+        }
+
+        let mut seen = HashSet::new();
+        let mut indices = vec![];
+        for triangle in corners.chunks_exact(3) {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| clusters[i as usize]);
+            if a == b || b == c || c == a {
+                continue;
+            }
+            // Same triangle whatever its first corner
+            let key = if a < b && a < c {
+                [a, b, c]
+            } else if b < c {
+                [b, c, a]
+            } else {
+                [c, a, b]
+            };
+            if seen.insert(key) {
+                indices.extend([a, b, c]);
+            }
+        }
+
+        let mut model = self.subset(Mode::Triangles, &representatives, Some(indices));
+        let mut error: f32 = 0.;
+        for (vertex, (sum, count)) in model.vertices_mut().iter_mut().zip(sums.iter()) {
+            vertex.position = sum / *count;
+        }
+        for (p, &cluster) in positions.iter().zip(clusters.iter()) {
+            error = error.max((model.vertices[cluster as usize].position - p).magnitude());
+        }
+        if let Some(positions) = model.positions_f64.as_mut() {
+            for (p, vertex) in positions.iter_mut().zip(model.vertices.iter()) {
+                *p = vertex.position.cast().unwrap();
+            }
+        }
+        Lod {
+            model,
+            error: if extent > 0. { error / extent } else { 0. },
+        }
+    }
+}
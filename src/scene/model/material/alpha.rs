@@ -0,0 +1,102 @@
+use super::Material;
+use image::{GrayImage, Luma, RgbaImage};
+
+/// How the alpha of the base color is interpreted.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum AlphaMode {
+    /// The alpha is ignored, the material is fully opaque.
+    #[default]
+    Opaque,
+    /// The material is either fully opaque or fully transparent depending on
+    /// whether the alpha is greater or equal to `cutoff`.
+    Mask {
+        /// Alpha threshold, `0.5` by default.
+        cutoff: f32,
+    },
+    /// The alpha is used to blend the material with the background.
+    Blend,
+}
+
+impl AlphaMode {
+    pub(crate) fn load(gltf_mat: &gltf::Material) -> Self {
+        match gltf_mat.alpha_mode() {
+            gltf::material::AlphaMode::Opaque => Self::Opaque,
+            gltf::material::AlphaMode::Mask => Self::Mask {
+                cutoff: gltf_mat.alpha_cutoff().unwrap_or(0.5),
+            },
+            gltf::material::AlphaMode::Blend => Self::Blend,
+        }
+    }
+}
+
+/// Render pass a material belongs to, see
+/// [`Material::alpha_class`](struct.Material.html#method.alpha_class).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AlphaClass {
+    /// Fully opaque, no alpha test nor blending needed.
+    Opaque,
+    /// Cut out with an alpha test, but rendered without blending.
+    AlphaTested,
+    /// Translucent, must be blended (usually sorted back to front).
+    Blended,
+}
+
+impl Material {
+    /// Classify the material in the render pass it needs, from its alpha mode,
+    /// base color alpha and base color texture alpha channel:
+    /// - `Opaque` materials stay opaque.
+    /// - `Mask` materials are alpha tested, unless nothing is cut out.
+    /// - `Blend` materials are blended, unless they are fully opaque.
+    ///
+    /// **Note**: The base color texture is scanned, so cache the result.
+    /// Transmission (`KHR_materials_transmission`) isn't loaded and so isn't
+    /// taken into account.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_gltf::model::AlphaClass;
+    ///
+    /// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// for model in scenes[0].models.iter() {
+    ///     match model.material().alpha_class() {
+    ///         AlphaClass::Opaque => { /* opaque pass */ }
+    ///         AlphaClass::AlphaTested => { /* opaque pass with discard */ }
+    ///         AlphaClass::Blended => { /* transparent pass */ }
+    ///     }
+    /// }
+    /// ```
+    pub fn alpha_class(&self) -> AlphaClass {
+        let factor = self.pbr.base_color_factor.w;
+        let texture = self.pbr.base_color_texture.as_ref();
+        let min_alpha = || {
+            let texture_alpha = texture
+                .and_then(|t| t.pixels().map(|px| px[3]).min())
+                .unwrap_or(255);
+            texture_alpha as f32 / 255. * factor
+        };
+
+        match self.alpha_mode {
+            AlphaMode::Opaque => AlphaClass::Opaque,
+            AlphaMode::Mask { cutoff } if min_alpha() >= cutoff => AlphaClass::Opaque,
+            AlphaMode::Mask { .. } => AlphaClass::AlphaTested,
+            AlphaMode::Blend if min_alpha() >= 1. => AlphaClass::Opaque,
+            AlphaMode::Blend => AlphaClass::Blended,
+        }
+    }
+
+    /// Whether the material must be rendered with blending, see
+    /// [`alpha_class`](#method.alpha_class).
+    pub fn needs_blending(&self) -> bool {
+        self.alpha_class() == AlphaClass::Blended
+    }
+}
+
+/// Threshold the alpha channel of a base color texture: `255` where the
+/// alpha multiplied by `factor` reaches `cutoff`, `0` elsewhere.
+pub(crate) fn alpha_mask(texture: &RgbaImage, factor: f32, cutoff: f32) -> GrayImage {
+    GrayImage::from_fn(texture.width(), texture.height(), |x, y| {
+        let alpha = texture.get_pixel(x, y)[3] as f32 / 255. * factor;
+        Luma([if alpha >= cutoff { 255 } else { 0 }])
+    })
+}
@@ -1,3 +1,4 @@
+use super::Mapper;
 use crate::utils::GltfData;
 use cgmath::*;
 use image::RgbImage;
@@ -13,15 +14,20 @@ pub struct Emissive {
     /// The `emissive_factor` contains scaling factors for the red, green and
     /// blue components of this texture.
     pub factor: Vector3<f32>,
+
+    /// Texture coordinates mapping of the `texture`.
+    pub mapper: Mapper,
 }
 
 impl Emissive {
     pub(crate) fn load(gltf_mat: &gltf::Material, data: &mut GltfData) -> Self {
+        let texture = gltf_mat.emissive_texture();
         Self {
-            texture: gltf_mat
-                .emissive_texture()
+            texture: texture
+                .as_ref()
                 .map(|texture| data.load_rgb_image(&texture.texture())),
             factor: gltf_mat.emissive_factor().into(),
+            mapper: texture.as_ref().map(Mapper::load).unwrap_or_default(),
         }
     }
 }
@@ -31,6 +37,7 @@ impl Default for Emissive {
         Self {
             texture: None,
             factor: Vector3::zero(),
+            mapper: Mapper::default(),
         }
     }
 }
@@ -0,0 +1,63 @@
+use super::{Material, TextureSlot};
+use crate::cache::CachedImage;
+use crate::utils::GltfData;
+use image::{GrayImage, Luma, Rgb, RgbImage};
+use std::sync::Arc;
+
+impl Material {
+    /// Handle returned by `LoadOptions::image_consumer` for the texture of a
+    /// slot, `None` without consumer or if the slot has no texture.
+    pub fn texture_handle(&self, slot: TextureSlot) -> Option<u64> {
+        self.texture_handles[slot as usize]
+    }
+
+    /// Hand the textures to `LoadOptions::image_consumer` and replace them by
+    /// their handle.
+    pub(crate) fn consume_textures(&mut self, data: &mut GltfData) {
+        let consumer = match &data.options.image_consumer {
+            Some(consumer) => consumer.clone(),
+            None => return,
+        };
+        for slot in TextureSlot::ALL.iter().copied() {
+            let image = match self.take_texture(slot) {
+                Some(image) => image,
+                None => continue,
+            };
+            // Images modified since decoded (or synthesized) have no key
+            let key = data.pending.get(&image.id()).cloned();
+            let handle = match key.as_ref().and_then(|key| data.consumed.get(key)) {
+                Some(handle) => *handle,
+                None => {
+                    let handle = consumer.consume(slot, image);
+                    if let Some(key) = key {
+                        data.consumed.insert(key, handle);
+                    }
+                    handle
+                }
+            };
+            self.texture_handles[slot as usize] = Some(handle);
+        }
+        // The images are dropped, their ids can be reused
+        data.pending.clear();
+    }
+
+    /// Remove the texture of a slot. Normal and occlusion maps can't be
+    /// texture-less, they get a neutral 1x1 texture instead.
+    fn take_texture(&mut self, slot: TextureSlot) -> Option<CachedImage> {
+        match slot {
+            TextureSlot::BaseColor => self.pbr.base_color_texture.take().map(CachedImage::Rgba),
+            TextureSlot::Metallic => self.pbr.metallic_texture.take().map(CachedImage::Gray),
+            TextureSlot::Roughness => self.pbr.roughness_texture.take().map(CachedImage::Gray),
+            TextureSlot::Normal => self.normal.as_mut().map(|normal| {
+                let neutral = RgbImage::from_pixel(1, 1, Rgb([128, 128, 255]));
+                CachedImage::Rgb(std::mem::replace(&mut normal.texture, Arc::new(neutral)))
+            }),
+            TextureSlot::Occlusion => self.occlusion.as_mut().map(|occlusion| {
+                let neutral = GrayImage::from_pixel(1, 1, Luma([255]));
+                CachedImage::Gray(std::mem::replace(&mut occlusion.texture, Arc::new(neutral)))
+            }),
+            TextureSlot::Emissive => self.emissive.texture.take().map(CachedImage::Rgb),
+            TextureSlot::AlphaMask => self.alpha_mask.take().map(CachedImage::Gray),
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use super::{AlphaClass, AlphaMode, Mapper, Material, NormalConvention};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// Fields of a material compared by `==` and hashed. Factors are compared
+/// bitwise and textures by identity (`Arc` pointer).
+#[derive(PartialEq, Eq, Hash)]
+struct MaterialKey<'a> {
+    #[cfg(feature = "names")]
+    name: Option<&'a str>,
+    #[cfg(feature = "extras")]
+    extras: Option<&'a str>,
+    factors: [u32; 37],
+    tex_coords: [u32; 5],
+    repeat: [bool; 5],
+    textures: [usize; 7],
+    handles: [Option<u64>; 7],
+    alpha_mode: u8,
+    normal_convention: Option<NormalConvention>,
+    double_sided: bool,
+    #[cfg(not(all(feature = "names", feature = "extras")))]
+    lifetime: std::marker::PhantomData<&'a ()>,
+}
+
+impl Material {
+    fn key(&self) -> MaterialKey<'_> {
+        let mut factors = [0; 37];
+        let mut values = vec![];
+        values.extend(self.pbr.base_color_factor.as_ref() as &[f32; 4]);
+        values.push(self.pbr.metallic_factor);
+        values.push(self.pbr.roughness_factor);
+        values.push(self.normal.as_ref().map_or(0., |n| n.factor));
+        values.push(self.occlusion.as_ref().map_or(0., |o| o.factor));
+        values.extend(self.emissive.factor.as_ref() as &[f32; 3]);
+        values.push(match self.alpha_mode {
+            AlphaMode::Mask { cutoff } => cutoff,
+            _ => 0.,
+        });
+        for mapper in self.mappers().iter() {
+            values.extend([
+                mapper.offset.x,
+                mapper.offset.y,
+                mapper.rotation,
+                mapper.scale.x,
+                mapper.scale.y,
+            ]);
+        }
+        for (bits, value) in factors.iter_mut().zip(values) {
+            *bits = value.to_bits();
+        }
+
+        let [base_color, metallic_roughness, emissive, normal, occlusion] = self.mappers();
+        MaterialKey {
+            #[cfg(feature = "names")]
+            name: self.name.as_deref(),
+            #[cfg(feature = "extras")]
+            extras: self.extras.as_ref().map(|extras| extras.get()),
+            factors,
+            tex_coords: [
+                base_color.tex_coord,
+                metallic_roughness.tex_coord,
+                emissive.tex_coord,
+                normal.tex_coord,
+                occlusion.tex_coord,
+            ],
+            repeat: [
+                base_color.repeat,
+                metallic_roughness.repeat,
+                emissive.repeat,
+                normal.repeat,
+                occlusion.repeat,
+            ],
+            textures: self.texture_ids(),
+            handles: self.texture_handles,
+            alpha_mode: match self.alpha_mode {
+                AlphaMode::Opaque => 0,
+                AlphaMode::Mask { .. } => 1,
+                AlphaMode::Blend => 2,
+            },
+            normal_convention: self.normal.as_ref().map(|n| n.convention),
+            double_sided: self.double_sided,
+            #[cfg(not(all(feature = "names", feature = "extras")))]
+            lifetime: std::marker::PhantomData,
+        }
+    }
+
+    fn mappers(&self) -> [Mapper; 5] {
+        [
+            self.pbr.base_color_mapper,
+            self.pbr.metallic_roughness_mapper,
+            self.emissive.mapper,
+            self.normal
+                .as_ref()
+                .map_or_else(Mapper::default, |n| n.mapper),
+            self.occlusion
+                .as_ref()
+                .map_or_else(Mapper::default, |o| o.mapper),
+        ]
+    }
+
+    /// Identity of every texture, `0` for missing textures.
+    fn texture_ids(&self) -> [usize; 7] {
+        fn id<T>(texture: Option<&Arc<T>>) -> usize {
+            texture.map_or(0, |t| Arc::as_ptr(t) as usize)
+        }
+        [
+            id(self.pbr.base_color_texture.as_ref()),
+            id(self.pbr.metallic_texture.as_ref()),
+            id(self.pbr.roughness_texture.as_ref()),
+            id(self.normal.as_ref().map(|n| &n.texture)),
+            id(self.occlusion.as_ref().map(|o| &o.texture)),
+            id(self.emissive.texture.as_ref()),
+            id(self.alpha_mask.as_ref()),
+        ]
+    }
+
+    /// Key to sort draw calls by material: by alpha class first (opaque, then
+    /// alpha tested, then blended), then by double-sidedness, then by
+    /// textures so draws sharing the same textures are next to each other.
+    ///
+    /// **Note**: The key uses `alpha_class()` which scans the base color
+    /// texture, compute it once per material. Texture identities are memory
+    /// addresses, so keys are only stable while the textures are alive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// let models = &mut scenes[0].models;
+    /// models.sort_by_cached_key(|model| model.material().render_sort_key());
+    /// ```
+    pub fn render_sort_key(&self) -> u64 {
+        let class: u64 = match self.alpha_class() {
+            AlphaClass::Opaque => 0,
+            AlphaClass::AlphaTested => 1,
+            AlphaClass::Blended => 2,
+        };
+        let mut hasher = DefaultHasher::new();
+        self.texture_ids().hash(&mut hasher);
+        self.texture_handles.hash(&mut hasher);
+        let textures = hasher.finish() >> 3;
+        class << 62 | (self.double_sided as u64) << 61 | textures
+    }
+}
+
+/// Materials are equal when all their values are equal and they use the same
+/// textures (compared by identity, not by content).
+impl PartialEq for Material {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Material {}
+
+impl Hash for Material {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
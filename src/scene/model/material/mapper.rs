@@ -0,0 +1,134 @@
+use cgmath::*;
+
+/// How the texture coordinates of a model map to a texture: the set of
+/// texture coordinates used, its transform (`KHR_texture_transform`) and
+/// whether the texture repeats.
+///
+/// The `Material::get_*` samplers apply the transform, the selection of the
+/// set is left to the caller (see [`select`](#method.select)).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mapper {
+    /// Index of the set of texture coordinates used by the texture
+    /// (`TEXCOORD_n`).
+    pub tex_coord: u32,
+    /// Offset of the texture coordinates, applied last.
+    pub offset: Vector2<f32>,
+    /// Counter-clockwise rotation of the texture coordinates in radians.
+    pub rotation: f32,
+    /// Scale of the texture coordinates, applied first.
+    pub scale: Vector2<f32>,
+    /// Whether the sampler repeats the texture (`REPEAT` or
+    /// `MIRRORED_REPEAT`) on both axes. Otherwise texture coordinates outside
+    /// `[0, 1]` are clamped to the edge.
+    pub repeat: bool,
+}
+
+impl Mapper {
+    pub(crate) fn load(info: &gltf::texture::Info) -> Self {
+        let repeat = repeats(&info.texture());
+        match info.texture_transform() {
+            Some(transform) => Self {
+                tex_coord: transform.tex_coord().unwrap_or_else(|| info.tex_coord()),
+                offset: transform.offset().into(),
+                rotation: transform.rotation(),
+                scale: transform.scale().into(),
+                repeat,
+            },
+            None => Self {
+                tex_coord: info.tex_coord(),
+                repeat,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Mapper of a texture whose transform is only available as a raw
+    /// extension (normal and occlusion textures), `transform` being the value
+    /// of the `KHR_texture_transform` extension if any.
+    pub(crate) fn from_extension(tex_coord: u32, transform: Option<&serde_json::Value>) -> Self {
+        let transform = match transform {
+            Some(transform) => transform,
+            None => {
+                return Self {
+                    tex_coord,
+                    ..Default::default()
+                }
+            }
+        };
+        let vector = |key: &str, default: Vector2<f32>| match transform[key].as_array() {
+            Some(values) if values.len() == 2 => Vector2::new(
+                values[0].as_f64().unwrap_or_default() as f32,
+                values[1].as_f64().unwrap_or_default() as f32,
+            ),
+            _ => default,
+        };
+        Self {
+            tex_coord: transform["texCoord"]
+                .as_u64()
+                .map_or(tex_coord, |tex_coord| tex_coord as u32),
+            offset: vector("offset", Vector2::zero()),
+            rotation: transform["rotation"].as_f64().unwrap_or_default() as f32,
+            scale: vector("scale", Vector2::new(1., 1.)),
+            repeat: true,
+        }
+    }
+
+    /// Whether the transform leaves texture coordinates unchanged.
+    pub fn is_identity(&self) -> bool {
+        self.offset == Vector2::zero() && self.rotation == 0. && self.scale == Vector2::new(1., 1.)
+    }
+
+    /// Transform texture coordinates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cgmath::*;
+    /// use easy_gltf::model::Mapper;
+    ///
+    /// let mapper = Mapper {
+    ///     offset: Vector2::new(0.5, 0.),
+    ///     scale: Vector2::new(2., 2.),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(mapper.apply(Vector2::new(0.25, 0.25)), Vector2::new(1., 0.5));
+    /// ```
+    pub fn apply(&self, tex_coords: Vector2<f32>) -> Vector2<f32> {
+        if self.is_identity() {
+            return tex_coords;
+        }
+        let (sin, cos) = self.rotation.sin_cos();
+        let (u, v) = (tex_coords.x * self.scale.x, tex_coords.y * self.scale.y);
+        Vector2::new(
+            cos * u + sin * v + self.offset.x,
+            -sin * u + cos * v + self.offset.y,
+        )
+    }
+
+    /// Select the set of texture coordinates used by the texture among
+    /// `tex_coords` (`TEXCOORD_0`, `TEXCOORD_1`...). Returns `None` if the
+    /// set is missing.
+    pub fn select(&self, tex_coords: &[Vector2<f32>]) -> Option<Vector2<f32>> {
+        tex_coords.get(self.tex_coord as usize).copied()
+    }
+}
+
+impl Default for Mapper {
+    fn default() -> Self {
+        Self {
+            tex_coord: 0,
+            offset: Vector2::zero(),
+            rotation: 0.,
+            scale: Vector2::new(1., 1.),
+            repeat: true,
+        }
+    }
+}
+
+/// Whether the sampler of a texture repeats it on both axes.
+pub(crate) fn repeats(texture: &gltf::Texture) -> bool {
+    let sampler = texture.sampler();
+    [sampler.wrap_s(), sampler.wrap_t()]
+        .iter()
+        .all(|&mode| mode != gltf::texture::WrappingMode::ClampToEdge)
+}
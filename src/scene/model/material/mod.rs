@@ -1,18 +1,27 @@
+mod alpha;
 mod emissive;
+mod handles;
+mod key;
+mod mapper;
 mod normal;
 mod occlusion;
 mod pbr;
+mod placeholder;
+mod texture_slot;
 
 use crate::utils::*;
 use cgmath::*;
 use core::ops::Deref;
-use image::{ImageBuffer, Pixel};
+use image::{GrayImage, ImageBuffer, Pixel};
 use std::sync::Arc;
 
+pub use alpha::{AlphaClass, AlphaMode};
 pub use emissive::Emissive;
-pub use normal::NormalMap;
+pub use mapper::Mapper;
+pub use normal::{NormalConvention, NormalMap};
 pub use occlusion::Occlusion;
 pub use pbr::PbrMaterial;
+pub use texture_slot::{ColorSpace, TextureSlot};
 
 /// Contains material properties of models.
 #[derive(Clone, Debug, Default)]
@@ -37,18 +46,75 @@ pub struct Material {
 
     /// The emissive color of the material.
     pub emissive: Emissive,
+
+    /// How the alpha of the base color is interpreted.
+    pub alpha_mode: AlphaMode,
+
+    /// Cutout mask of `Mask` materials with a base color texture, thresholded
+    /// at the alpha cutoff: `255` where the surface is visible, `0` where it
+    /// is cut out. Only generated with `LoadOptions::alpha_masks`.
+    pub alpha_mask: Option<Arc<GrayImage>>,
+
+    /// Whether back faces must be rendered too (no back-face culling).
+    pub double_sided: bool,
+
+    /// Handles returned by `LoadOptions::image_consumer` for every texture
+    /// slot, in the order of `TextureSlot::ALL`. See
+    /// [`texture_handle`](#method.texture_handle).
+    pub texture_handles: [Option<u64>; 7],
 }
 
 impl Material {
+    /// Material used by glTF for primitives without material: white, fully
+    /// metallic and rough, without texture.
+    ///
+    /// **Note**: This differs from `Material::default()` which has no
+    /// metallic nor roughness.
+    pub fn gltf_default() -> Self {
+        Material {
+            pbr: PbrMaterial {
+                metallic_factor: 1.,
+                roughness_factor: 1.,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Copy of the material without texture transforms, so the `get_*`
+    /// samplers use the texture coordinates as is. Textures are shared.
+    pub fn without_texture_transforms(&self) -> Material {
+        let identity = |mapper: &Mapper| Mapper {
+            tex_coord: mapper.tex_coord,
+            repeat: mapper.repeat,
+            ..Default::default()
+        };
+        let mut material = self.clone();
+        material.pbr.base_color_mapper = identity(&self.pbr.base_color_mapper);
+        material.pbr.metallic_roughness_mapper = identity(&self.pbr.metallic_roughness_mapper);
+        material.emissive.mapper = identity(&self.emissive.mapper);
+        if let Some(normal) = material.normal.as_mut() {
+            normal.mapper = identity(&normal.mapper);
+        }
+        if let Some(occlusion) = material.occlusion.as_mut() {
+            occlusion.mapper = identity(&occlusion.mapper);
+        }
+        material
+    }
+
     /// Get the color base Rgb(A) (in RGB-color space) of the material given a
     /// texture coordinate. If no `base_color_texture` is available then the
     /// `base_color_factor` is returned.
     ///
+    /// The texture transform of `pbr.base_color_mapper` is applied to
+    /// `tex_coords`, as in every `get_*` sampler.
+    ///
     /// **Important**: `tex_coords` must contain values between `[0., 1.]`
     /// otherwise the function will fail.
     pub fn get_base_color_alpha(&self, tex_coords: Vector2<f32>) -> Vector4<f32> {
         let mut res = self.pbr.base_color_factor;
         if let Some(texture) = &self.pbr.base_color_texture {
+            let tex_coords = self.pbr.base_color_mapper.apply(tex_coords);
             let px_u = Self::get_pixel(tex_coords, texture);
             // Transform to float
             let mut px_f = Vector4::new(0., 0., 0., 0.);
@@ -75,6 +141,29 @@ impl Material {
         self.get_base_color_alpha(tex_coords).truncate()
     }
 
+    /// Get the alpha of the material given a texture coordinate, following
+    /// its alpha mode: always `1.` for `Opaque`, `0.` or `1.` for `Mask`
+    /// (alpha test against the cutoff), the base color alpha for `Blend`.
+    ///
+    /// **Important**: `tex_coords` must contain values between `[0., 1.]`
+    /// otherwise the function will fail.
+    pub fn get_alpha(&self, tex_coords: Vector2<f32>) -> f32 {
+        let alpha = || {
+            let mut alpha = self.pbr.base_color_factor.w;
+            if let Some(texture) = &self.pbr.base_color_texture {
+                let tex_coords = self.pbr.base_color_mapper.apply(tex_coords);
+                alpha *= Self::get_pixel(tex_coords, texture)[3] as f32 / 255.;
+            }
+            alpha
+        };
+        match self.alpha_mode {
+            AlphaMode::Opaque => 1.,
+            AlphaMode::Mask { cutoff } if alpha() >= cutoff => 1.,
+            AlphaMode::Mask { .. } => 0.,
+            AlphaMode::Blend => alpha(),
+        }
+    }
+
     /// Get the metallic value of the material given a texture coordinate. If no
     /// `metallic_texture` is available then the `metallic_factor` is returned.
     ///
@@ -83,6 +172,7 @@ impl Material {
     pub fn get_metallic(&self, tex_coords: Vector2<f32>) -> f32 {
         self.pbr.metallic_factor
             * if let Some(texture) = &self.pbr.metallic_texture {
+                let tex_coords = self.pbr.metallic_roughness_mapper.apply(tex_coords);
                 Self::get_pixel(tex_coords, texture)[0] as f32 / 255.
             } else {
                 1.
@@ -97,6 +187,7 @@ impl Material {
     pub fn get_roughness(&self, tex_coords: Vector2<f32>) -> f32 {
         self.pbr.roughness_factor
             * if let Some(texture) = &self.pbr.roughness_texture {
+                let tex_coords = self.pbr.metallic_roughness_mapper.apply(tex_coords);
                 Self::get_pixel(tex_coords, texture)[0] as f32 / 255.
             } else {
                 1.
@@ -106,16 +197,25 @@ impl Material {
     /// Get the normal vector of the material given a texture coordinate. If no
     /// `normal_texture` is available then `None` is returned.
     ///
+    /// The normal always uses the OpenGL convention (+Y up), whatever the
+    /// convention of the normal map.
+    ///
     /// **Important**: `tex_coords` must contain values between `[0., 1.]`
     /// otherwise the function will fail.
     pub fn get_normal(&self, tex_coords: Vector2<f32>) -> Option<Vector3<f32>> {
         let normal = self.normal.as_ref()?;
+        let tex_coords = normal.mapper.apply(tex_coords);
         let pixel = Self::get_pixel(tex_coords, &normal.texture);
+        let y = (pixel[1] as f32) / 127.5 - 1.;
+        let y = match normal.convention {
+            NormalConvention::OpenGl => y,
+            NormalConvention::DirectX => -y,
+        };
         Some(
             normal.factor
                 * Vector3::new(
                     (pixel[0] as f32) / 127.5 - 1.,
-                    (pixel[1] as f32) / 127.5 - 1.,
+                    y,
                     (pixel[2] as f32) / 127.5 - 1.,
                 ),
         )
@@ -124,13 +224,37 @@ impl Material {
     /// Get the occlusion value of the material given a texture coordinate. If no
     /// `occlusion_texture` is available then `None` is returned.
     ///
-    /// **Important**: `tex_coords` must contain values between `[0., 1.]`
+    /// **Important**: `tex_coords` must come from the set
+    /// `occlusion.mapper.tex_coord` and contain values between `[0., 1.]`
     /// otherwise the function will fail.
     pub fn get_occlusion(&self, tex_coords: Vector2<f32>) -> Option<f32> {
         let occlusion = self.occlusion.as_ref()?;
+        let tex_coords = occlusion.mapper.apply(tex_coords);
         Some(occlusion.factor * (Self::get_pixel(tex_coords, &occlusion.texture)[0] as f32 / 255.))
     }
 
+    /// Same as `get_occlusion()` but selecting the texture coordinates of the
+    /// set used by the occlusion texture among `tex_coords` (`TEXCOORD_0`,
+    /// `TEXCOORD_1`...). Returns `None` if the set is missing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cgmath::*;
+    /// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// let model = &scenes[0].models[0];
+    /// let material = model.material();
+    /// for (i, vertex) in model.vertices().iter().enumerate() {
+    ///     let mut sets = vec![vertex.tex_coords];
+    ///     sets.extend(model.tex_coords_1().map(|uv| uv[i]));
+    ///     let occlusion = material.get_occlusion_from_sets(&sets).unwrap_or(1.);
+    /// }
+    /// ```
+    pub fn get_occlusion_from_sets(&self, tex_coords: &[Vector2<f32>]) -> Option<f32> {
+        let occlusion = self.occlusion.as_ref()?;
+        self.get_occlusion(occlusion.mapper.select(tex_coords)?)
+    }
+
     /// Get the emissive color Rgb of the material given a texture coordinate.
     /// If no `emissive_texture` is available then the `emissive_factor` is
     /// returned.
@@ -140,6 +264,7 @@ impl Material {
     pub fn get_emissive(&self, tex_coords: Vector2<f32>) -> Vector3<f32> {
         let mut res = self.emissive.factor;
         if let Some(texture) = &self.emissive.texture {
+            let tex_coords = self.emissive.mapper.apply(tex_coords);
             let pixel = Self::get_pixel(tex_coords, texture);
             for i in 0..3 {
                 res[i] *= (pixel[i] as f32) / 255.;
@@ -166,11 +291,14 @@ impl Material {
     }
 
     pub(crate) fn load(gltf_mat: gltf::Material, data: &mut GltfData) -> Arc<Self> {
+        if data.options.skip_materials || gltf_mat.index().is_none() {
+            return data.default_material();
+        }
         if let Some(material) = data.materials.get(&gltf_mat.index()) {
             return material.clone();
         }
 
-        let material = Arc::new(Material {
+        let mut material = Material {
             #[cfg(feature = "names")]
             name: gltf_mat.name().map(String::from),
             #[cfg(feature = "extras")]
@@ -180,7 +308,25 @@ impl Material {
             normal: NormalMap::load(&gltf_mat, data),
             occlusion: Occlusion::load(&gltf_mat, data),
             emissive: Emissive::load(&gltf_mat, data),
-        });
+            alpha_mode: AlphaMode::load(&gltf_mat),
+            alpha_mask: None,
+            double_sided: gltf_mat.double_sided(),
+            texture_handles: Default::default(),
+        };
+        if let Some(material_override) = data.options.material_override {
+            material = material_override(&gltf_mat, material);
+        }
+        if data.options.alpha_masks {
+            if let (AlphaMode::Mask { cutoff }, Some(texture)) =
+                (material.alpha_mode, &material.pbr.base_color_texture)
+            {
+                let factor = material.pbr.base_color_factor.w;
+                material.alpha_mask = Some(Arc::new(alpha::alpha_mask(texture, factor, cutoff)));
+            }
+        }
+        material.fill_placeholders(data.options.placeholder_textures);
+        material.consume_textures(data);
+        let material = Arc::new(material);
 
         // Add to the collection
         data.materials.insert(gltf_mat.index(), material.clone());
@@ -1,3 +1,4 @@
+use super::{mapper, Mapper};
 use crate::utils::GltfData;
 use image::RgbImage;
 use std::sync::Arc;
@@ -13,20 +14,80 @@ pub struct NormalMap {
     /// * Green [0 to 255] maps to Y [-1 to 1].
     /// * Blue [128 to 255] maps to Z [1/255 to 1].
     ///
-    /// The normal vectors use OpenGL conventions where +X is right, +Y is up,
-    /// and +Z points toward the viewer.
+    /// The normal vectors use the `convention` of the normal map, OpenGL
+    /// conventions by default where +X is right, +Y is up, and +Z points toward
+    /// the viewer.
     pub texture: Arc<RgbImage>,
 
     /// The `normal_factor` is the normal strength to be applied to the
     /// texture value.
     pub factor: f32,
+
+    /// Convention of the Y (green) channel of the `texture`, see
+    /// `LoadOptions::normal_convention`. `Material::get_normal` reads the
+    /// texture with it.
+    ///
+    /// Setting it without touching the texture declares the convention the
+    /// pixels really use, e.g. for a DirectX normal map wrongly exported in a
+    /// glTF asset (in `LoadOptions::material_override`). Use `convert_to` to
+    /// rewrite the pixels instead.
+    pub convention: NormalConvention,
+
+    /// Set of texture coordinates and transform used by the texture.
+    pub mapper: Mapper,
+}
+
+/// Direction of the Y axis of tangent space normal maps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum NormalConvention {
+    /// +Y is up (green up), the glTF convention.
+    #[default]
+    OpenGl,
+    /// +Y is down (green down), used by DirectX engines.
+    DirectX,
 }
 
 impl NormalMap {
+    /// Convert the texture to the given convention, inverting its green
+    /// channel if it uses the other one, and update `convention`. The normals
+    /// returned by `Material::get_normal` don't change.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use easy_gltf::model::NormalConvention;
+    /// let scenes = easy_gltf::load("tests/head.glb").expect("Failed to load glTF");
+    /// let mut material = scenes[0].models[0].material().as_ref().clone();
+    /// if let Some(normal) = material.normal.as_mut() {
+    ///     // Declare the real convention of the pixels first if mislabeled
+    ///     normal.convention = NormalConvention::DirectX;
+    ///     normal.convert_to(NormalConvention::OpenGl);
+    /// }
+    /// ```
+    pub fn convert_to(&mut self, convention: NormalConvention) {
+        if self.convention == convention {
+            return;
+        }
+        let mut texture = self.texture.as_ref().clone();
+        for pixel in texture.pixels_mut() {
+            pixel[1] = 255 - pixel[1];
+        }
+        self.texture = Arc::new(texture);
+        self.convention = convention;
+    }
+
     pub(crate) fn load(gltf_mat: &gltf::Material, data: &mut GltfData) -> Option<Self> {
         gltf_mat.normal_texture().map(|texture| Self {
-            texture: data.load_rgb_image(&texture.texture()),
+            texture: data.load_normal_image(&texture.texture()),
             factor: texture.scale(),
+            convention: data.options.normal_convention,
+            mapper: Mapper {
+                repeat: mapper::repeats(&texture.texture()),
+                ..Mapper::from_extension(
+                    texture.tex_coord(),
+                    texture.extension_value("KHR_texture_transform"),
+                )
+            },
         })
     }
 }
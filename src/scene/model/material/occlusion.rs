@@ -1,3 +1,4 @@
+use super::{mapper, Mapper};
 use crate::utils::GltfData;
 use image::GrayImage;
 use std::sync::Arc;
@@ -12,6 +13,10 @@ pub struct Occlusion {
     /// The `occlusion_factor` is the occlusion strength to be applied to the
     /// texture value.
     pub factor: f32,
+
+    /// Set of texture coordinates and transform used by the texture. Baked
+    /// occlusion is usually in the second set, see `Model::tex_coords_1()`.
+    pub mapper: Mapper,
 }
 
 impl Occlusion {
@@ -19,6 +24,13 @@ impl Occlusion {
         gltf_mat.occlusion_texture().map(|texture| Self {
             texture: data.load_gray_image(&texture.texture(), 0),
             factor: texture.strength(),
+            mapper: Mapper {
+                repeat: mapper::repeats(&texture.texture()),
+                ..Mapper::from_extension(
+                    texture.tex_coord(),
+                    texture.extension_value("KHR_texture_transform"),
+                )
+            },
         })
     }
 }
@@ -1,3 +1,4 @@
+use super::Mapper;
 use crate::utils::GltfData;
 use cgmath::*;
 use image::{GrayImage, RgbaImage};
@@ -18,6 +19,9 @@ pub struct PbrMaterial {
     /// The texture contains RGB(A) components in **sRGB** color space.
     pub base_color_texture: Option<Arc<RgbaImage>>,
 
+    /// Texture coordinates mapping of the `base_color_texture`.
+    pub base_color_mapper: Mapper,
+
     /// Contains the metalness value
     pub metallic_texture: Option<Arc<GrayImage>>,
 
@@ -33,6 +37,10 @@ pub struct PbrMaterial {
     /// texture is given, then the factor define the roughness for the whole
     /// object.
     pub roughness_factor: f32,
+
+    /// Texture coordinates mapping of the `metallic_texture` and
+    /// `roughness_texture`, both come from the same glTF texture.
+    pub metallic_roughness_mapper: Mapper,
 }
 
 impl PbrMaterial {
@@ -43,12 +51,14 @@ impl PbrMaterial {
         };
         if let Some(texture) = pbr.base_color_texture() {
             material.base_color_texture = Some(data.load_base_color_image(&texture.texture()));
+            material.base_color_mapper = Mapper::load(&texture);
         }
 
         material.roughness_factor = pbr.roughness_factor();
         material.metallic_factor = pbr.metallic_factor();
 
         if let Some(texture) = pbr.metallic_roughness_texture() {
+            material.metallic_roughness_mapper = Mapper::load(&texture);
             if material.metallic_factor > 0. {
                 material.metallic_texture = Some(data.load_gray_image(&texture.texture(), 2));
             }
@@ -66,10 +76,12 @@ impl Default for PbrMaterial {
         PbrMaterial {
             base_color_factor: Vector4::new(1., 1., 1., 1.),
             base_color_texture: None,
+            base_color_mapper: Mapper::default(),
             metallic_factor: 0.,
             metallic_texture: None,
             roughness_factor: 0.,
             roughness_texture: None,
+            metallic_roughness_mapper: Mapper::default(),
         }
     }
 }
@@ -0,0 +1,69 @@
+use super::{Material, NormalMap, Occlusion};
+use crate::utils::linear_to_srgb;
+use crate::PlaceholderTextures;
+use image::{GrayImage, Luma, Rgb, RgbImage, Rgba, RgbaImage};
+use std::sync::Arc;
+
+impl Material {
+    /// Synthesize 1x1 textures for the missing texture slots.
+    pub(crate) fn fill_placeholders(&mut self, placeholders: PlaceholderTextures) {
+        let from_factors = match placeholders {
+            PlaceholderTextures::None => return,
+            PlaceholderTextures::Neutral => false,
+            PlaceholderTextures::FromFactors => true,
+        };
+        let byte = |c: f32| (c.clamp(0., 1.) * 255.).round() as u8;
+        let pbr = &mut self.pbr;
+
+        if pbr.base_color_texture.is_none() {
+            let mut pixel = Rgba([255; 4]);
+            if from_factors {
+                let [r, g, b, a]: [f32; 4] = pbr.base_color_factor.into();
+                pixel = Rgba([
+                    linear_to_srgb(r),
+                    linear_to_srgb(g),
+                    linear_to_srgb(b),
+                    byte(a),
+                ]);
+                pbr.base_color_factor = [1.; 4].into();
+            }
+            pbr.base_color_texture = Some(Arc::new(RgbaImage::from_pixel(1, 1, pixel)));
+        }
+        if pbr.metallic_texture.is_none() {
+            let mut pixel = Luma([255]);
+            if from_factors {
+                pixel = Luma([byte(pbr.metallic_factor)]);
+                pbr.metallic_factor = 1.;
+            }
+            pbr.metallic_texture = Some(Arc::new(GrayImage::from_pixel(1, 1, pixel)));
+        }
+        if pbr.roughness_texture.is_none() {
+            let mut pixel = Luma([255]);
+            if from_factors {
+                pixel = Luma([byte(pbr.roughness_factor)]);
+                pbr.roughness_factor = 1.;
+            }
+            pbr.roughness_texture = Some(Arc::new(GrayImage::from_pixel(1, 1, pixel)));
+        }
+        if self.emissive.texture.is_none() {
+            let mut pixel = Rgb([255; 3]);
+            if from_factors {
+                let [r, g, b]: [f32; 3] = self.emissive.factor.into();
+                pixel = Rgb([linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b)]);
+                self.emissive.factor = [1.; 3].into();
+            }
+            self.emissive.texture = Some(Arc::new(RgbImage::from_pixel(1, 1, pixel)));
+        }
+        self.normal.get_or_insert_with(|| NormalMap {
+            texture: Arc::new(RgbImage::from_pixel(1, 1, Rgb([128, 128, 255]))),
+            factor: 1.,
+            convention: Default::default(),
+            mapper: Default::default(),
+        });
+        self.occlusion.get_or_insert_with(|| Occlusion {
+            texture: Arc::new(GrayImage::from_pixel(1, 1, Luma([255]))),
+            mapper: Default::default(),
+            factor: 1.,
+        });
+    }
+}
@@ -0,0 +1,59 @@
+/// How the values of a texture are encoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// sRGB encoded colors, to upload with a `*_SRGB` GPU format so sampling
+    /// decodes them to linear space.
+    Srgb,
+    /// Linear data (normals, metallic, roughness, occlusion...).
+    Linear,
+}
+
+/// A texture slot of a [`Material`](struct.Material.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TextureSlot {
+    /// `pbr.base_color_texture`.
+    BaseColor,
+    /// `pbr.metallic_texture`.
+    Metallic,
+    /// `pbr.roughness_texture`.
+    Roughness,
+    /// `normal.texture`.
+    Normal,
+    /// `occlusion.texture`.
+    Occlusion,
+    /// `emissive.texture`.
+    Emissive,
+    /// `alpha_mask`.
+    AlphaMask,
+}
+
+impl TextureSlot {
+    /// Every texture slot.
+    pub const ALL: [TextureSlot; 7] = [
+        TextureSlot::BaseColor,
+        TextureSlot::Metallic,
+        TextureSlot::Roughness,
+        TextureSlot::Normal,
+        TextureSlot::Occlusion,
+        TextureSlot::Emissive,
+        TextureSlot::AlphaMask,
+    ];
+
+    /// Color space of the images stored in the slot: base color and emissive
+    /// are sRGB encoded, the other slots are linear.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_gltf::model::{ColorSpace, TextureSlot};
+    ///
+    /// assert_eq!(TextureSlot::BaseColor.color_space(), ColorSpace::Srgb);
+    /// assert_eq!(TextureSlot::Normal.color_space(), ColorSpace::Linear);
+    /// ```
+    pub fn color_space(self) -> ColorSpace {
+        match self {
+            TextureSlot::BaseColor | TextureSlot::Emissive => ColorSpace::Srgb,
+            _ => ColorSpace::Linear,
+        }
+    }
+}
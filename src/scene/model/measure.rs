@@ -0,0 +1,42 @@
+use super::{Mode, Model, Triangle};
+use cgmath::*;
+
+impl Model {
+    /// Total area of the triangles of the model, `0` for lines and points.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// println!("Area: {} m²", scenes[0].models[0].surface_area());
+    /// ```
+    pub fn surface_area(&self) -> f32 {
+        self.triangles()
+            .map_or(0., |triangles| triangles.iter().map(Triangle::area).sum())
+    }
+
+    /// Signed volume enclosed by the triangles of the model, positive when the
+    /// faces point outward. `0` for lines and points.
+    ///
+    /// **Note**: The volume is only meaningful for closed (watertight) meshes
+    /// with a consistent winding, e.g. holes or inner faces make it wrong. A
+    /// model split into several primitives has to be summed over all of them.
+    pub fn signed_volume(&self) -> f32 {
+        self.triangle_positions()
+            .map(|[a, b, c]| a.dot(b.cross(c)) / 6.)
+            .sum()
+    }
+
+    fn triangle_positions(&self) -> impl Iterator<Item = [Vector3<f32>; 3]> + '_ {
+        let (mode, indices) = self.list_indices();
+        let indices = if mode == Mode::Triangles {
+            indices
+        } else {
+            vec![]
+        };
+        (0..indices.len() / 3).map(move |i| {
+            let position = |j: usize| self.vertices[indices[3 * i + j] as usize].position;
+            [position(0), position(1), position(2)]
+        })
+    }
+}
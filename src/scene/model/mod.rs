@@ -1,13 +1,41 @@
+mod framing;
+#[cfg(feature = "half")]
+mod half;
+mod hull;
+mod index_buffer;
+mod influences;
+mod layout;
+mod line;
+mod lod;
 mod material;
+mod measure;
 mod mode;
+mod morph;
+mod stats;
+mod triangle;
 mod vertex;
 
 use crate::utils::*;
+use crate::LoadOptions;
 use cgmath::*;
+use gltf::Semantic;
+use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
 use std::sync::Arc;
 
+#[cfg(feature = "half")]
+pub use half::*;
+pub use index_buffer::*;
+pub use layout::*;
+pub use line::*;
+pub use lod::*;
 pub use material::*;
 pub use mode::*;
+pub use morph::*;
+pub use stats::*;
+pub use triangle::*;
 pub use vertex::*;
 
 /// Geometry to be rendered with the given material.
@@ -69,12 +97,30 @@ pub struct Model {
     pub(crate) mesh_extras: gltf::json::extras::Extras,
     #[cfg(feature = "extras")]
     pub(crate) primitive_extras: gltf::json::extras::Extras,
+    #[cfg(feature = "names")]
+    pub(crate) node_name: Option<String>,
+    #[cfg(feature = "extras")]
+    pub(crate) node_extras: gltf::json::extras::Extras,
 
+    pub(crate) node_index: usize,
+    pub(crate) skin_index: Option<usize>,
+    pub(crate) mesh_index: usize,
     pub(crate) primitive_index: usize,
-    pub(crate) vertices: Vec<Vertex>,
-    pub(crate) indices: Option<Vec<u32>>,
+    pub(crate) vertices: Arc<Vec<Vertex>>,
+    pub(crate) indices: Option<Arc<Vec<u32>>>,
     pub(crate) mode: Mode,
     pub(crate) material: Arc<Material>,
+    pub(crate) material_index: usize,
+    pub(crate) morph_targets: Vec<MorphTarget>,
+    pub(crate) morph_weights: Vec<f32>,
+    pub(crate) compact_colors: Option<Vec<[u8; 4]>>,
+    pub(crate) tex_coords_1: Option<Vec<Vector2<f32>>>,
+    pub(crate) positions_f64: Option<Vec<Vector3<f64>>>,
+    pub(crate) joints: Option<Vec<[u16; 4]>>,
+    pub(crate) weights: Option<Vec<[f32; 4]>>,
+    pub(crate) dropped_influences: Option<influences::DroppedInfluences>,
+    /// Transform baked into the vertices, `None` for identity.
+    pub(crate) transform: Option<Matrix4<f32>>,
     pub(crate) has_normals: bool,
     pub(crate) has_tangents: bool,
     pub(crate) has_tex_coords: bool,
@@ -93,6 +139,36 @@ impl Model {
         self.mesh_name.as_deref()
     }
 
+    /// Index of the glTF node instantiating the mesh of this `Model`.
+    pub fn node_index(&self) -> usize {
+        self.node_index
+    }
+
+    #[cfg(feature = "names")]
+    /// Name of the glTF node instantiating the mesh of this `Model`. Requires
+    /// the `names` feature.
+    pub fn node_name(&self) -> Option<&str> {
+        self.node_name.as_deref()
+    }
+
+    #[cfg(feature = "extras")]
+    /// Extra data of the glTF node instantiating the mesh of this `Model`,
+    /// usually per-instance data (tags, physics settings...). Requires the
+    /// `extras` feature.
+    pub fn node_extras(&self) -> &gltf::json::extras::Extras {
+        &self.node_extras
+    }
+
+    /// Index of the glTF skin of the node, see `Scene::skins`.
+    pub fn skin_index(&self) -> Option<usize> {
+        self.skin_index
+    }
+
+    /// Index of the glTF mesh that this `Model` comes from.
+    pub fn mesh_index(&self) -> usize {
+        self.mesh_index
+    }
+
     /// Index of the Primitive of the Mesh that this `Model` corresponds to.
     pub fn primitive_index(&self) -> usize {
         self.primitive_index
@@ -115,6 +191,36 @@ impl Model {
         self.material.clone()
     }
 
+    /// Index of the material of the model in `Scene::materials`.
+    pub fn material_index(&self) -> usize {
+        self.material_index
+    }
+
+    /// Morph targets (blend shapes) of the model.
+    pub fn morph_targets(&self) -> &[MorphTarget] {
+        &self.morph_targets
+    }
+
+    /// Default weights of the morph targets, one per target: the `weights` of
+    /// the node if any, else the ones of the mesh, else `0`. They give the
+    /// blend shape pose authored for the model, see `apply_morph_weights`.
+    pub fn morph_weights(&self) -> &[f32] {
+        &self.morph_weights
+    }
+
+    /// Indices of the joints influencing every vertex, in the `joints` of the
+    /// skin of the model. Influences of every set (`JOINTS_0`, `JOINTS_1`...)
+    /// are reduced to the `LoadOptions::max_influences` strongest ones.
+    pub fn joints(&self) -> Option<&[[u16; 4]]> {
+        self.joints.as_deref()
+    }
+
+    /// Weights of the joints influencing every vertex, see `joints()`. They
+    /// sum to `1` unless `LoadOptions::normalize_weights` is disabled.
+    pub fn weights(&self) -> Option<&[[f32; 4]]> {
+        self.weights.as_deref()
+    }
+
     /// List of raw `vertices` of the model. You might have to use the `indices`
     /// to render the model.
     ///
@@ -124,11 +230,46 @@ impl Model {
         &self.vertices
     }
 
+    /// Mutable access to the vertices. Clones of a model share their geometry,
+    /// so the vertices are copied first if they are shared (copy-on-write).
+    pub fn vertices_mut(&mut self) -> &mut Vec<Vertex> {
+        Arc::make_mut(&mut self.vertices)
+    }
+
+    /// Vertices shared by the clones of the model, to send them to other
+    /// threads or systems without copy.
+    pub fn shared_vertices(&self) -> &Arc<Vec<Vertex>> {
+        &self.vertices
+    }
+
+    /// Same as `vertices()` but using plain arrays instead of `cgmath` types.
+    pub fn raw_vertices(&self) -> &[RawVertex] {
+        // SAFETY: `RawVertex` and `Vertex` are both `repr(C)` with the same
+        // field order, and `cgmath` vectors are `repr(C)` structs of scalars.
+        unsafe {
+            std::slice::from_raw_parts(
+                self.vertices.as_ptr() as *const RawVertex,
+                self.vertices.len(),
+            )
+        }
+    }
+
     /// Potential list of `indices` to render the model using raw `vertices`.
     ///
     /// **Note**: If you're **not** rendering with **OpenGL** you probably want to use
     /// `triangles()`, `lines()` or `points()` instead.
     pub fn indices(&self) -> Option<&Vec<u32>> {
+        self.indices.as_deref()
+    }
+
+    /// Mutable access to the indices, copied first if they are shared with
+    /// clones of the model (copy-on-write).
+    pub fn indices_mut(&mut self) -> Option<&mut Vec<u32>> {
+        self.indices.as_mut().map(Arc::make_mut)
+    }
+
+    /// Indices shared by the clones of the model, see `shared_vertices()`.
+    pub fn shared_indices(&self) -> Option<&Arc<Vec<u32>>> {
         self.indices.as_ref()
     }
 
@@ -139,49 +280,32 @@ impl Model {
     /// * `vertices()` and `indices()` to arrange the data yourself (useful for **OpenGL**).
     /// * `triangles()` or `lines()` or `points()` according to the returned mode.
     pub fn mode(&self) -> Mode {
-        self.mode.clone()
+        self.mode
     }
 
     /// List of triangles ready to be rendered.
     ///
+    /// Incomplete primitives (e.g. a strip of 2 indices) give no triangle,
+    /// see `Issue::IncompletePrimitive`.
+    ///
     /// **Note**: This function will return an error if the mode isn't `Triangles`, `TriangleFan`
     /// or `TriangleStrip`.
     pub fn triangles(&self) -> Result<Vec<Triangle>, BadMode> {
-        let mut triangles = vec![];
-        let indices = (0..self.vertices.len() as u32).collect();
-        let indices = self.indices().unwrap_or(&indices);
-
         match self.mode {
-            Mode::Triangles => {
-                for i in (0..indices.len()).step_by(3) {
-                    triangles.push([
-                        self.vertices[indices[i] as usize],
-                        self.vertices[indices[i + 1] as usize],
-                        self.vertices[indices[i + 2] as usize],
-                    ]);
-                }
-            }
-            Mode::TriangleStrip => {
-                for i in 0..(indices.len() - 2) {
-                    triangles.push([
-                        self.vertices[indices[i] as usize + i % 2],
-                        self.vertices[indices[i + 1 - i % 2] as usize],
-                        self.vertices[indices[i + 2] as usize],
-                    ]);
-                }
-            }
-            Mode::TriangleFan => {
-                for i in 1..(indices.len() - 1) {
-                    triangles.push([
-                        self.vertices[indices[0] as usize],
-                        self.vertices[indices[i] as usize],
-                        self.vertices[indices[i + 1] as usize],
-                    ]);
-                }
-            }
-            _ => return Err(BadMode { mode: self.mode() }),
+            Mode::Triangles | Mode::TriangleStrip | Mode::TriangleFan => Ok(self
+                .list_indices()
+                .1
+                .chunks_exact(3)
+                .map(|triangle| {
+                    Triangle([
+                        self.vertices[triangle[0] as usize],
+                        self.vertices[triangle[1] as usize],
+                        self.vertices[triangle[2] as usize],
+                    ])
+                })
+                .collect()),
+            _ => Err(BadMode::new(self, "triangles")),
         }
-        Ok(triangles)
     }
 
     /// List of lines ready to be rendered.
@@ -189,48 +313,237 @@ impl Model {
     /// **Note**: This function will return an error if the mode isn't `Lines`, `LineLoop`
     /// or `LineStrip`.
     pub fn lines(&self) -> Result<Vec<Line>, BadMode> {
-        let mut lines = vec![];
-        let indices = (0..self.vertices.len() as u32).collect();
-        let indices = self.indices().unwrap_or(&indices);
+        let indices = self.line_list_indices().map_err(|error| BadMode {
+            method: "lines",
+            ..error
+        })?;
+        Ok(indices
+            .chunks_exact(2)
+            .map(|line| {
+                Line([
+                    self.vertices[line[0] as usize],
+                    self.vertices[line[1] as usize],
+                ])
+            })
+            .collect())
+    }
+
+    /// Indices of the vertices of every line as pairs (`GL_LINES` style),
+    /// strips and loops are expanded and loops are closed.
+    ///
+    /// **Note**: This function will return an error if the mode isn't `Lines`, `LineLoop`
+    /// or `LineStrip`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let model = easy_gltf::model::Model::default();
+    /// if let Ok(indices) = model.line_list_indices() {
+    ///     // glDrawElements(GL_LINES, indices.len(), GL_UNSIGNED_INT, 0);
+    /// }
+    /// ```
+    pub fn line_list_indices(&self) -> Result<Vec<u32>, BadMode> {
         match self.mode {
-            Mode::Lines => {
-                for i in (0..indices.len()).step_by(2) {
-                    lines.push([
-                        self.vertices[indices[i] as usize],
-                        self.vertices[indices[i + 1] as usize],
-                    ]);
+            Mode::Lines | Mode::LineStrip | Mode::LineLoop => Ok(self.list_indices().1),
+            _ => Err(BadMode::new(self, "line_list_indices")),
+        }
+    }
+
+    /// List of points ready to be renderer.
+    ///
+    /// **Note**: This function will return an error if the mode isn't `Points`.
+    pub fn points(&self) -> Result<&Vec<Vertex>, BadMode> {
+        match self.mode {
+            Mode::Points => Ok(&self.vertices),
+            _ => Err(BadMode::new(self, "points")),
+        }
+    }
+
+    /// Copy of the model without indices where every triangle, line or point
+    /// has its own vertices, for pipelines that need unique vertices per
+    /// corner.
+    ///
+    /// Strips and fans become `Triangles`, line strips and loops become `Lines`.
+    pub fn to_non_indexed(&self) -> Model {
+        let (mode, corners) = self.list_indices();
+        self.subset(mode, &corners, None)
+    }
+
+    /// Split the model in models of at most `max_vertices` vertices each
+    /// (e.g. `65535` for 16-bit indices), with their own vertices and local
+    /// indices. Strips, loops and fans are converted to lists.
+    ///
+    /// The model is returned as is if it already fits. `max_vertices` is at
+    /// least the number of vertices of a primitive (3 for triangles).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/head.glb").expect("Failed to load glTF");
+    /// for part in scenes[0].models[0].split(u16::MAX as usize) {
+    ///     let indices = part.index_buffer();
+    ///     // Upload with 16-bit indices...
+    /// }
+    /// ```
+    pub fn split(&self, max_vertices: usize) -> Vec<Model> {
+        if self.vertices.len() <= max_vertices {
+            return vec![self.clone()];
+        }
+
+        let (mode, indices) = self.list_indices();
+        let primitive = match mode {
+            Mode::Triangles => 3,
+            Mode::Lines => 2,
+            _ => 1,
+        };
+        let max_vertices = max_vertices.max(primitive);
+
+        let mut models = vec![];
+        // Local index of every vertex in the current part, `u32::MAX` if absent
+        let mut remap = vec![u32::MAX; self.vertices.len()];
+        let mut part_vertices: Vec<u32> = vec![];
+        let mut part_indices = vec![];
+        for corners in indices.chunks_exact(primitive) {
+            let new = corners
+                .iter()
+                .filter(|&&i| remap[i as usize] == u32::MAX)
+                .count();
+            if part_vertices.len() + new > max_vertices {
+                models.push(self.subset(mode, &part_vertices, Some(part_indices)));
+                for &i in part_vertices.iter() {
+                    remap[i as usize] = u32::MAX;
                 }
+                part_vertices.clear();
+                part_indices = vec![];
             }
-            Mode::LineStrip | Mode::LineLoop => {
-                for i in 0..(indices.len() - 1) {
-                    lines.push([
-                        self.vertices[indices[i] as usize],
-                        self.vertices[indices[i + 1] as usize],
-                    ]);
+            for &i in corners {
+                if remap[i as usize] == u32::MAX {
+                    remap[i as usize] = part_vertices.len() as u32;
+                    part_vertices.push(i);
                 }
+                part_indices.push(remap[i as usize]);
             }
-            _ => return Err(BadMode { mode: self.mode() }),
         }
-        if self.mode == Mode::LineLoop {
-            lines.push([
-                self.vertices[indices[0] as usize],
-                self.vertices[indices[indices.len() - 1] as usize],
-            ]);
+        if !part_indices.is_empty() {
+            models.push(self.subset(mode, &part_vertices, Some(part_indices)));
         }
+        models
+    }
 
-        Ok(lines)
+    /// Copy of the model with the given vertices (and their per-vertex data)
+    /// in order, with new indices and mode.
+    fn subset(&self, mode: Mode, vertices: &[u32], indices: Option<Vec<u32>>) -> Model {
+        let expand = |values: &[Vector3<f32>]| -> Vec<_> {
+            if values.is_empty() {
+                vec![]
+            } else {
+                vertices.iter().map(|&i| values[i as usize]).collect()
+            }
+        };
+        Model {
+            vertices: Arc::new(
+                vertices
+                    .iter()
+                    .map(|&i| self.vertices[i as usize])
+                    .collect(),
+            ),
+            indices: indices.map(Arc::new),
+            mode,
+            compact_colors: self
+                .compact_colors
+                .as_ref()
+                .map(|colors| vertices.iter().map(|&i| colors[i as usize]).collect()),
+            tex_coords_1: self
+                .tex_coords_1
+                .as_ref()
+                .map(|tex_coords| vertices.iter().map(|&i| tex_coords[i as usize]).collect()),
+            positions_f64: self
+                .positions_f64
+                .as_ref()
+                .map(|positions| vertices.iter().map(|&i| positions[i as usize]).collect()),
+            joints: self
+                .joints
+                .as_ref()
+                .map(|joints| vertices.iter().map(|&i| joints[i as usize]).collect()),
+            weights: self
+                .weights
+                .as_ref()
+                .map(|weights| vertices.iter().map(|&i| weights[i as usize]).collect()),
+            morph_targets: self
+                .morph_targets
+                .iter()
+                .map(|target| MorphTarget {
+                    #[cfg(feature = "extras")]
+                    name: target.name.clone(),
+                    positions: expand(&target.positions),
+                    normals: expand(&target.normals),
+                    tangents: expand(&target.tangents),
+                })
+                .collect(),
+            ..self.clone()
+        }
     }
 
-    /// List of points ready to be renderer.
-    ///
-    /// **Note**: This function will return an error if the mode isn't `Points`.
-    pub fn points(&self) -> Result<&Vec<Vertex>, BadMode> {
+    /// Indices of the vertices of every triangle, line or point as a list
+    /// (`Triangles`, `Lines` or `Points`) with the winding of the model kept.
+    pub(crate) fn list_indices(&self) -> (Mode, Vec<u32>) {
+        let sequential: Vec<u32>;
+        let indices = match self.indices.as_ref() {
+            Some(indices) => indices,
+            None => {
+                sequential = (0..self.vertices.len() as u32).collect();
+                &sequential
+            }
+        };
+        let n = indices.len();
         match self.mode {
-            Mode::Points => Ok(&self.vertices),
-            _ => Err(BadMode { mode: self.mode() }),
+            Mode::Points => (Mode::Points, indices.clone()),
+            Mode::Lines => (Mode::Lines, indices[..n - n % 2].to_vec()),
+            Mode::LineStrip | Mode::LineLoop => {
+                let mut list: Vec<_> = indices
+                    .windows(2)
+                    .flat_map(|line| line.iter().copied())
+                    .collect();
+                if self.mode == Mode::LineLoop && n > 2 {
+                    list.extend([indices[n - 1], indices[0]].iter());
+                }
+                (Mode::Lines, list)
+            }
+            Mode::Triangles => (Mode::Triangles, indices[..n - n % 3].to_vec()),
+            Mode::TriangleStrip => {
+                let list = (0..n.saturating_sub(2))
+                    .flat_map(|i| {
+                        let (a, b) = if i % 2 == 0 { (i, i + 1) } else { (i + 1, i) };
+                        vec![indices[a], indices[b], indices[i + 2]]
+                    })
+                    .collect();
+                (Mode::Triangles, list)
+            }
+            Mode::TriangleFan => {
+                let list = (1..n.saturating_sub(1))
+                    .flat_map(|i| vec![indices[0], indices[i], indices[i + 1]])
+                    .collect();
+                (Mode::Triangles, list)
+            }
         }
     }
 
+    /// Write the model to a binary STL file, e.g. to send it to a slicer or a CAD
+    /// tool. Strips and fans are triangulated and face normals are computed
+    /// from the vertex positions.
+    ///
+    /// **Note**: This function will return an error if the mode isn't `Triangles`,
+    /// `TriangleFan` or `TriangleStrip`.
+    pub fn export_stl<P>(&self, path: P) -> Result<(), Box<dyn Error + Send + Sync>>
+    where
+        P: AsRef<Path>,
+    {
+        let triangles = self.triangles().map_err(|err| err.to_string())?;
+        let file = BufWriter::new(File::create(path)?);
+        crate::export::write_stl(&triangles, file)?;
+        Ok(())
+    }
+
     /// Indicate if the vertices contains normal information.
     ///
     /// **Note**: If this function return `false` all vertices has a normal field
@@ -265,86 +578,331 @@ impl Model {
         self.has_colors
     }
 
-    fn apply_transform_position(pos: [f32; 3], transform: &Matrix4<f32>) -> Vector3<f32> {
-        let pos = Vector4::new(pos[0], pos[1], pos[2], 1.);
-        let res = transform * pos;
-        Vector3::new(res.x / res.w, res.y / res.w, res.z / res.w)
+    /// Indicate if the model has a second set of texture coordinates, see
+    /// `tex_coords_1()`.
+    pub fn has_tex_coords_1(&self) -> bool {
+        self.tex_coords_1.is_some()
+    }
+
+    /// Indicate if the vertices have joints and weights, see `joints()` and
+    /// `weights()`.
+    pub fn has_joints(&self) -> bool {
+        self.joints.is_some() && self.weights.is_some()
+    }
+
+    /// Indicate if the model is deformed by a skin: it has joints and its node
+    /// has a skin, see `skin_index()`.
+    pub fn is_skinned(&self) -> bool {
+        self.has_joints() && self.skin_index.is_some()
+    }
+
+    /// Indicate if the model has morph targets, see `morph_targets()`.
+    pub fn has_morph_targets(&self) -> bool {
+        !self.morph_targets.is_empty()
     }
 
-    fn apply_transform_vector(vec: [f32; 3], transform: &Matrix4<f32>) -> Vector3<f32> {
-        let vec = Vector4::new(vec[0], vec[1], vec[2], 0.);
-        (transform * vec).truncate()
+    /// Vertex colors as sRGB encoded RGBA bytes (alpha stays linear), one per
+    /// vertex. Only available with `LoadOptions::compact_colors` if the
+    /// primitive has colors, in addition to `Vertex::color` with the
+    /// `vertex-color` feature.
+    pub fn compact_colors(&self) -> Option<&[[u8; 4]]> {
+        self.compact_colors.as_deref()
+    }
+
+    /// Second set of texture coordinates (`TEXCOORD_1`) with one entry per
+    /// vertex, usually used by occlusion textures and lightmaps. See
+    /// `Material::get_occlusion_from_sets()`.
+    pub fn tex_coords_1(&self) -> Option<&[Vector2<f32>]> {
+        self.tex_coords_1.as_deref()
+    }
+
+    /// Vertex positions transformed in double precision, only available with
+    /// `LoadOptions::f64_positions`. They follow the order of `vertices()`.
+    pub fn positions_f64(&self) -> Option<&[Vector3<f64>]> {
+        self.positions_f64.as_deref()
+    }
+
+    /// Reverse the winding order of the triangles and flip the tangent
+    /// handedness in place, for engines whose front faces use the opposite
+    /// winding (clockwise). Lines and points are left untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// for model in scenes[0].models.iter_mut() {
+    ///     model.flip_winding();
+    /// }
+    /// ```
+    pub fn flip_winding(&mut self) {
+        if !matches!(
+            self.mode,
+            Mode::Triangles | Mode::TriangleStrip | Mode::TriangleFan
+        ) {
+            return;
+        }
+        for vertex in self.vertices_mut().iter_mut() {
+            vertex.tangent.w = -vertex.tangent.w;
+        }
+
+        let count = self.indices().map_or(self.vertices.len(), Vec::len);
+        let even_strip = self.mode == Mode::TriangleStrip && count.is_multiple_of(2);
+        if even_strip {
+            let vertex_count = self.vertices.len() as u32;
+            self.indices
+                .get_or_insert_with(|| Arc::new((0..vertex_count).collect()));
+        }
+
+        if let Some(indices) = self.indices.as_mut().map(Arc::make_mut) {
+            flip_winding(&self.mode, indices);
+            // Reversing an even strip keeps the winding: start with a degenerate triangle
+            if even_strip {
+                if let Some(&first) = indices.first() {
+                    indices.insert(0, first);
+                }
+            }
+        } else {
+            // Every per-vertex array must follow the vertices
+            flip_winding(&self.mode, Arc::make_mut(&mut self.vertices).as_mut_slice());
+            if let Some(colors) = self.compact_colors.as_mut() {
+                flip_winding(&self.mode, colors);
+            }
+            if let Some(tex_coords) = self.tex_coords_1.as_mut() {
+                flip_winding(&self.mode, tex_coords);
+            }
+            if let Some(positions) = self.positions_f64.as_mut() {
+                flip_winding(&self.mode, positions);
+            }
+            if let Some(joints) = self.joints.as_mut() {
+                flip_winding(&self.mode, joints);
+            }
+            if let Some(weights) = self.weights.as_mut() {
+                flip_winding(&self.mode, weights);
+            }
+            for target in self.morph_targets.iter_mut() {
+                flip_winding(&self.mode, &mut target.positions);
+                flip_winding(&self.mode, &mut target.normals);
+                flip_winding(&self.mode, &mut target.tangents);
+            }
+        }
     }
 
-    fn apply_transform_tangent(tangent: [f32; 4], transform: &Matrix4<f32>) -> Vector4<f32> {
-        let tang = Vector4::new(tangent[0], tangent[1], tangent[2], 0.);
-        let mut tang = transform * tang;
-        tang[3] = tangent[3];
-        tang
+    /// Copy of the model with a transform applied, e.g. for manual instancing.
+    ///
+    /// Positions are transformed by `transform` and normals by its inverse
+    /// transpose. Mirroring transforms rewind the triangles and flip the
+    /// tangent handedness so the tangent basis stays valid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cgmath::*;
+    /// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// let instances: Vec<_> = (0..4)
+    ///     .map(|i| {
+    ///         let offset = Vector3::new(3. * i as f32, 0., 0.);
+    ///         scenes[0].models[0].transformed(&Matrix4::from_translation(offset))
+    ///     })
+    ///     .collect();
+    /// ```
+    pub fn transformed(&self, transform: &Matrix4<f32>) -> Model {
+        let mut model = self.clone();
+        model.apply_transform(transform);
+        model
+    }
+
+    /// Apply a transform on the positions, normals, tangents and morph targets.
+    pub(crate) fn apply_transform(&mut self, transform: &Matrix4<f32>) {
+        let linear = Matrix3::from_cols(
+            transform.x.truncate(),
+            transform.y.truncate(),
+            transform.z.truncate(),
+        );
+        let normal_matrix = normal_matrix(transform);
+
+        let mut positions: Vec<[f32; 3]> =
+            self.vertices.iter().map(|v| v.position.into()).collect();
+        transform_points(&mut positions, transform);
+        for (vertex, position) in self.vertices_mut().iter_mut().zip(positions) {
+            vertex.position = position.into();
+        }
+        if self.has_normals {
+            let mut normals: Vec<[f32; 3]> =
+                self.vertices.iter().map(|v| v.normal.into()).collect();
+            transform_directions(&mut normals, &normal_matrix);
+            for (vertex, normal) in self.vertices_mut().iter_mut().zip(normals) {
+                vertex.normal = normal.into();
+            }
+        }
+        if self.has_tangents {
+            let mut tangents: Vec<[f32; 4]> =
+                self.vertices.iter().map(|v| v.tangent.into()).collect();
+            transform_directions(&mut tangents, &linear);
+            for (vertex, tangent) in self.vertices_mut().iter_mut().zip(tangents) {
+                vertex.tangent = tangent.into();
+            }
+        }
+        for target in self.morph_targets.iter_mut() {
+            target.apply_transform(&linear, &normal_matrix);
+        }
+        if let Some(positions) = self.positions_f64.as_mut() {
+            let transform = transform.cast::<f64>().unwrap();
+            for position in positions.iter_mut() {
+                *position = transform
+                    .transform_point(Point3::from_vec(*position))
+                    .to_vec();
+            }
+        }
+
+        self.transform = Some(transform * self.transform.unwrap_or_else(Matrix4::identity));
+
+        // Mirrored transforms invert the winding of the triangles
+        if transform.determinant() < 0. {
+            self.flip_winding();
+        }
     }
 
     pub(crate) fn load(
+        node: &gltf::Node,
         mesh: &gltf::Mesh,
         primitive_index: usize,
         primitive: gltf::Primitive,
-        transform: &Matrix4<f32>,
+        transform_f64: &Matrix4<f64>,
         data: &mut GltfData,
+    ) -> gltf::Result<Self> {
+        let buffers = data.buffers.get(primitive_views(&primitive))?;
+        let mut model = Self::convert(
+            node,
+            mesh,
+            primitive_index,
+            &primitive,
+            transform_f64,
+            &buffers,
+            &data.options,
+            &mut data.scratch,
+        );
+        model.material = Material::load(primitive.material(), data);
+        Ok(model)
+    }
+
+    /// Convert the geometry of a primitive, the material is left to the
+    /// caller so primitives can be converted on worker threads.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn convert(
+        node: &gltf::Node,
+        mesh: &gltf::Mesh,
+        primitive_index: usize,
+        primitive: &gltf::Primitive,
+        transform_f64: &Matrix4<f64>,
+        buffers: &BufferData,
+        options: &LoadOptions,
+        scratch: &mut Scratch,
     ) -> Self {
-        #[cfg(not(feature = "names"))]
-        {
-            let _ = mesh;
+        let transform = &transform_f64.cast::<f32>().unwrap();
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "primitive",
+            mesh = mesh.index(),
+            primitive = primitive_index
+        )
+        .entered();
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+
+        let indices =
+            accessor::read_indices(primitive, buffers).map(|indices| indices.into_u32().collect());
+
+        // Attributes are transformed in contiguous arrays before being
+        // interleaved, the hot loops are then easy to vectorize.
+        let attribute = |semantic| primitive.get(&semantic);
+        // Primitives without positions are loaded empty, see `Issue::EmptyPrimitive`
+        let positions = &mut scratch.positions;
+        positions.clear();
+        if let Some(accessor) = attribute(Semantic::Positions) {
+            read_f32_into(&accessor, buffers, positions);
         }
-
-        let buffers = &data.buffers;
-        let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
-        let indices = reader
-            .read_indices()
-            .map(|indices| indices.into_u32().collect());
-
-        // Init vertices with the position
-        let mut vertices: Vec<_> = reader
-            .read_positions()
-            .unwrap_or_else(|| panic!("The model primitive doesn't contain positions"))
-            .map(|pos| Vertex {
-                position: Self::apply_transform_position(pos, transform),
+        #[cfg(feature = "tracing")]
+        if positions.is_empty() {
+            tracing::warn!("primitive without positions");
+        }
+        let positions_f64 = options.f64_positions.then(|| {
+            positions
+                .iter()
+                .map(|&p| {
+                    let p = Point3::from(p).cast::<f64>().unwrap();
+                    transform_f64.transform_point(p).to_vec()
+                })
+                .collect::<Vec<_>>()
+        });
+        match &positions_f64 {
+            // Converted to f32 only at the end
+            Some(positions_f64) => {
+                for (p, p64) in positions.iter_mut().zip(positions_f64) {
+                    *p = p64.cast::<f32>().unwrap().into();
+                }
+            }
+            None => transform_points(positions, transform),
+        }
+        let mut vertices: Vec<_> = positions
+            .iter()
+            .map(|&position| Vertex {
+                position: position.into(),
                 ..Default::default()
             })
             .collect();
 
         // Fill normals
-        let has_normals = if let Some(normals) = reader.read_normals() {
-            for (i, normal) in normals.enumerate() {
-                vertices[i].normal = Self::apply_transform_vector(normal, transform).normalize();
+        let normals = &mut scratch.normals;
+        let has_normals =
+            attribute(Semantic::Normals).is_some_and(|a| read_f32_into(&a, buffers, normals));
+        if has_normals {
+            let normal_matrix = normal_matrix(transform);
+            if options.normalize_directions {
+                transform_directions(normals, &normal_matrix);
+            } else {
+                transform_vectors(normals, &normal_matrix);
             }
-            true
-        } else {
-            false
-        };
+            for (vertex, &normal) in vertices.iter_mut().zip(normals.iter()) {
+                vertex.normal = normal.into();
+            }
+        }
 
         // Fill tangents
-        let has_tangents = if let Some(tangents) = reader.read_tangents() {
-            for (i, tangent) in tangents.enumerate() {
-                let tangent = Self::apply_transform_tangent(tangent, transform);
-                vertices[i].tangent = tangent.truncate().normalize().extend(tangent.w);
+        let tangents = &mut scratch.tangents;
+        let has_tangents =
+            attribute(Semantic::Tangents).is_some_and(|a| read_f32_into(&a, buffers, tangents));
+        if has_tangents {
+            let linear = Matrix3::from_cols(
+                transform.x.truncate(),
+                transform.y.truncate(),
+                transform.z.truncate(),
+            );
+            if options.normalize_directions {
+                transform_directions(tangents, &linear);
+            } else {
+                transform_vectors(tangents, &linear);
             }
-            true
-        } else {
-            false
-        };
+            for (vertex, &tangent) in vertices.iter_mut().zip(tangents.iter()) {
+                vertex.tangent = tangent.into();
+            }
+        }
 
         // Texture coordinates
-        let has_tex_coords = if let Some(tex_coords) = reader.read_tex_coords(0) {
-            for (i, tex_coords) in tex_coords.into_f32().enumerate() {
-                vertices[i].tex_coords = Vector2::from(tex_coords);
+        let tex_coords = &mut scratch.tex_coords;
+        let has_tex_coords = attribute(Semantic::TexCoords(0))
+            .is_some_and(|a| read_f32_into(&a, buffers, tex_coords));
+        if has_tex_coords {
+            for (vertex, &tex_coords) in vertices.iter_mut().zip(tex_coords.iter()) {
+                vertex.tex_coords = Vector2::from(tex_coords);
+                if options.flip_v {
+                    vertex.tex_coords.y = 1. - tex_coords[1];
+                }
             }
-            true
-        } else {
-            false
-        };
+        }
 
         // Colors
         #[cfg(feature = "vertex-color")]
-        let has_colors = if let Some(colors) = reader.read_colors(0) {
+        let has_colors = if let Some(colors) = accessor::read_colors(primitive, 0, buffers) {
             for (i, color) in colors.into_rgba_u16().enumerate() {
                 vertices[i].color = Vector4::from(color);
             }
@@ -353,23 +911,105 @@ impl Model {
             false
         };
 
-        Model {
+        let tex_coords_1 = attribute(Semantic::TexCoords(1))
+            .and_then(|a| read_f32(&a, buffers))
+            .map(|tex_coords: Vec<[f32; 2]>| {
+                tex_coords
+                    .into_iter()
+                    .map(|[u, v]| Vector2::new(u, if options.flip_v { 1. - v } else { v }))
+                    .collect()
+            });
+
+        let compact_colors = accessor::read_colors(primitive, 0, buffers)
+            .filter(|_| options.compact_colors)
+            .map(|colors| {
+                colors
+                    .into_rgba_f32()
+                    .map(|[r, g, b, a]| {
+                        let alpha = (a.clamp(0., 1.) * 255.).round() as u8;
+                        [
+                            linear_to_srgb(r),
+                            linear_to_srgb(g),
+                            linear_to_srgb(b),
+                            alpha,
+                        ]
+                    })
+                    .collect()
+            });
+
+        let morph_targets = MorphTarget::load_all(mesh, primitive, transform, buffers);
+        let mut morph_weights = node
+            .weights()
+            .or_else(|| mesh.weights())
+            .map_or_else(Vec::new, <[f32]>::to_vec);
+        morph_weights.resize(morph_targets.len(), 0.);
+        let influences = influences::Influences::load(primitive, buffers, options);
+        let dropped_influences = influences.as_ref().and_then(|i| i.dropped);
+        let (joints, weights) = match influences {
+            Some(influences) => (Some(influences.joints), Some(influences.weights)),
+            None => (None, None),
+        };
+
+        let mut model = Model {
             #[cfg(feature = "names")]
             mesh_name: mesh.name().map(String::from),
             #[cfg(feature = "extras")]
             mesh_extras: mesh.extras().clone(),
             #[cfg(feature = "extras")]
             primitive_extras: primitive.extras().clone(),
+            #[cfg(feature = "names")]
+            node_name: node.name().map(String::from),
+            #[cfg(feature = "extras")]
+            node_extras: node.extras().clone(),
+            node_index: node.index(),
+            skin_index: node.skin().map(|skin| skin.index()),
+            mesh_index: mesh.index(),
             primitive_index,
-            vertices,
-            indices,
-            material: Material::load(primitive.material(), data),
+            vertices: Arc::new(vertices),
+            indices: indices.map(Arc::new),
+            material: Default::default(),
+            material_index: 0,
+            morph_targets,
+            morph_weights,
+            compact_colors,
+            tex_coords_1,
+            positions_f64,
+            joints,
+            weights,
+            dropped_influences,
+            transform: Some(*transform),
             mode: primitive.mode().into(),
             has_normals,
             has_tangents,
             has_tex_coords,
             #[cfg(feature = "vertex-color")]
             has_colors,
+        };
+        // Mirrored transforms invert the winding of the baked triangles
+        if transform.determinant() < 0. {
+            model.flip_winding();
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            vertices = model.vertices.len(),
+            indices = model.indices().map_or(0, Vec::len),
+            elapsed = ?start.elapsed(),
+            "primitive converted"
+        );
+        model
+    }
+}
+
+fn flip_winding<T>(mode: &Mode, elements: &mut [T]) {
+    match mode {
+        Mode::Triangles => {
+            for triangle in elements.chunks_exact_mut(3) {
+                triangle.swap(1, 2);
+            }
         }
+        Mode::TriangleStrip => elements.reverse(),
+        Mode::TriangleFan if !elements.is_empty() => elements[1..].reverse(),
+        _ => {}
     }
 }
@@ -1,10 +1,12 @@
+use super::Model;
+use std::error::Error;
 use std::fmt;
 
 /// The type of primitives to render.
 ///
 /// To find more information for each mode and how to render them check
 /// [Khronos Primitive Documentation](https://www.khronos.org/opengl/wiki/Primitive).
-#[derive(Clone, Debug, PartialEq, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
 pub enum Mode {
     /// Corresponds to `GL_POINTS`.
     Points,
@@ -23,6 +25,36 @@ pub enum Mode {
     TriangleFan,
 }
 
+impl Mode {
+    /// Value of the mode in glTF, which is also the OpenGL enum of the
+    /// primitive (e.g. `GL_TRIANGLES` is `4`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_gltf::model::Mode;
+    ///
+    /// assert_eq!(Mode::Triangles.as_gl_enum(), 0x0004);
+    /// ```
+    pub fn as_gl_enum(self) -> u32 {
+        gltf::mesh::Mode::from(self).as_gl_enum()
+    }
+}
+
+impl From<Mode> for gltf::mesh::Mode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Points => Self::Points,
+            Mode::Lines => Self::Lines,
+            Mode::LineLoop => Self::LineLoop,
+            Mode::LineStrip => Self::LineStrip,
+            Mode::Triangles => Self::Triangles,
+            Mode::TriangleFan => Self::TriangleFan,
+            Mode::TriangleStrip => Self::TriangleStrip,
+        }
+    }
+}
+
 impl From<gltf::mesh::Mode> for Mode {
     fn from(mode: gltf::mesh::Mode) -> Self {
         match mode {
@@ -38,15 +70,53 @@ impl From<gltf::mesh::Mode> for Mode {
 }
 
 /// Represents a runtime error. This error is triggered when an expected mode
-/// doesn't match the model mode .
+/// doesn't match the model mode, it identifies the model so logs point at
+/// the offending primitive.
 #[derive(Clone, Debug)]
 pub struct BadMode {
     /// The current mode of the model.
     pub mode: Mode,
+    /// Name of the mesh of the model. Requires the `names` feature.
+    #[cfg(feature = "names")]
+    pub mesh_name: Option<String>,
+    /// Index of the mesh of the model.
+    pub mesh_index: usize,
+    /// Index of the primitive of the model in its mesh.
+    pub primitive_index: usize,
+    /// Method that rejected the model, e.g. `"triangles"`.
+    pub method: &'static str,
+}
+
+impl BadMode {
+    pub(crate) fn new(model: &Model, method: &'static str) -> Self {
+        Self {
+            mode: model.mode(),
+            #[cfg(feature = "names")]
+            mesh_name: model.mesh_name().map(String::from),
+            mesh_index: model.mesh_index(),
+            primitive_index: model.primitive_index(),
+            method,
+        }
+    }
 }
 
 impl fmt::Display for BadMode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Invalid mode \"{:?}\"", self.mode,)
+        write!(
+            f,
+            "Invalid mode \"{:?}\" for {}() on mesh ",
+            self.mode, self.method
+        )?;
+        #[cfg(feature = "names")]
+        if let Some(name) = &self.mesh_name {
+            write!(f, "\"{}\" ", name)?;
+        }
+        write!(
+            f,
+            "#{}, primitive #{}",
+            self.mesh_index, self.primitive_index
+        )
     }
 }
+
+impl Error for BadMode {}
@@ -0,0 +1,143 @@
+use super::Model;
+use crate::utils::*;
+use cgmath::*;
+
+/// Morph target (blend shape) of a model.
+///
+/// Displacements are already transformed like the vertices and have one entry
+/// per vertex, or none if the target doesn't displace the attribute.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MorphTarget {
+    /// Name of the target, from the mesh `extras.targetNames` exported by
+    /// Blender (shape key names). Requires the `extras` feature.
+    #[cfg(feature = "extras")]
+    pub name: Option<String>,
+    /// Position displacements.
+    pub positions: Vec<Vector3<f32>>,
+    /// Normal displacements.
+    pub normals: Vec<Vector3<f32>>,
+    /// Tangent displacements (without handedness).
+    pub tangents: Vec<Vector3<f32>>,
+}
+
+impl MorphTarget {
+    pub(crate) fn load_all(
+        mesh: &gltf::Mesh,
+        primitive: &gltf::Primitive,
+        transform: &Matrix4<f32>,
+        buffers: &BufferData,
+    ) -> Vec<Self> {
+        let linear = Matrix3::from_cols(
+            transform.x.truncate(),
+            transform.y.truncate(),
+            transform.z.truncate(),
+        );
+        let normal_matrix = normal_matrix(transform);
+        let load = |accessor: Option<gltf::Accessor>, matrix: &Matrix3<f32>| {
+            let mut vectors: Vec<[f32; 3]> = accessor
+                .and_then(|accessor| read_f32(&accessor, buffers))
+                .unwrap_or_default();
+            transform_vectors(&mut vectors, matrix);
+            vectors.into_iter().map(Vector3::from).collect()
+        };
+
+        #[cfg_attr(not(feature = "extras"), allow(unused_mut))]
+        let mut targets: Vec<_> = primitive
+            .morph_targets()
+            .map(|target| MorphTarget {
+                #[cfg(feature = "extras")]
+                name: None,
+                positions: load(target.positions(), &linear),
+                normals: load(target.normals(), &normal_matrix),
+                tangents: load(target.tangents(), &linear),
+            })
+            .collect();
+
+        #[cfg(feature = "extras")]
+        for (target, name) in targets.iter_mut().zip(target_names(mesh)) {
+            target.name = Some(name);
+        }
+        #[cfg(not(feature = "extras"))]
+        let _ = mesh;
+        targets
+    }
+
+    /// Transform the displacements, `linear` is the upper 3x3 of the transform.
+    pub(crate) fn apply_transform(&mut self, linear: &Matrix3<f32>, normal_matrix: &Matrix3<f32>) {
+        for position in self.positions.iter_mut() {
+            *position = linear * *position;
+        }
+        for normal in self.normals.iter_mut() {
+            *normal = normal_matrix * *normal;
+        }
+        for tangent in self.tangents.iter_mut() {
+            *tangent = linear * *tangent;
+        }
+    }
+}
+
+impl Model {
+    /// Displace the vertices by the morph targets scaled by their weight, e.g.
+    /// to bake a blend shape. Extra weights are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// let model = &mut scenes[0].models[0];
+    /// let weights = vec![0.5; model.morph_targets().len()];
+    /// model.apply_morph_weights(&weights);
+    /// ```
+    pub fn apply_morph_weights(&mut self, weights: &[f32]) {
+        let targets = std::mem::take(&mut self.morph_targets);
+        let (has_normals, has_tangents) = (self.has_normals, self.has_tangents);
+        for (target, &weight) in targets.iter().zip(weights) {
+            if weight == 0. {
+                continue;
+            }
+            let vertices = self.vertices_mut();
+            for (vertex, delta) in vertices.iter_mut().zip(target.positions.iter()) {
+                vertex.position += delta * weight;
+            }
+            if has_normals {
+                for (vertex, delta) in vertices.iter_mut().zip(target.normals.iter()) {
+                    vertex.normal += delta * weight;
+                }
+            }
+            if has_tangents {
+                for (vertex, delta) in vertices.iter_mut().zip(target.tangents.iter()) {
+                    vertex.tangent += (delta * weight).extend(0.);
+                }
+            }
+            if let Some(positions) = self.positions_f64.as_mut() {
+                for (p, delta) in positions.iter_mut().zip(target.positions.iter()) {
+                    *p += delta.cast().unwrap() * weight as f64;
+                }
+            }
+        }
+        for vertex in self.vertices_mut().iter_mut() {
+            if has_normals && vertex.normal != Vector3::zero() {
+                vertex.normal = vertex.normal.normalize();
+            }
+        }
+        self.morph_targets = targets;
+    }
+}
+
+#[cfg(feature = "extras")]
+fn target_names(mesh: &gltf::Mesh) -> Vec<String> {
+    let extras = match mesh.extras() {
+        Some(extras) => extras,
+        None => return vec![],
+    };
+    let value: serde_json::Value = serde_json::from_str(extras.get()).unwrap_or_default();
+    value["targetNames"]
+        .as_array()
+        .map(|names| {
+            names
+                .iter()
+                .map(|name| name.as_str().unwrap_or_default().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,71 @@
+use super::{Mode, Model, Vertex};
+
+/// Statistics about a model, see [`Model::stats`](struct.Model.html#method.stats).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModelStats {
+    /// Primitive mode of the model.
+    pub mode: Mode,
+    /// Number of vertices.
+    pub vertex_count: usize,
+    /// Number of indices, `None` if the model isn't indexed.
+    pub index_count: Option<usize>,
+    /// Number of triangles, lines or points depending on the mode.
+    pub primitive_count: usize,
+    /// Whether the vertices contain normals.
+    pub has_normals: bool,
+    /// Whether the vertices contain tangents.
+    pub has_tangents: bool,
+    /// Whether the vertices contain texture coordinates.
+    pub has_tex_coords: bool,
+    /// Whether the vertices contain colors. Requires the `vertex-color` feature.
+    #[cfg(feature = "vertex-color")]
+    pub has_colors: bool,
+    /// Approximate CPU memory used by the vertices and indices, in bytes.
+    /// The material isn't included since it may be shared between models.
+    pub memory: usize,
+}
+
+impl Model {
+    /// Compute statistics about the model (counts, attributes and memory).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// let stats = scenes[0].models[0].stats();
+    /// println!("{} triangles, {} bytes", stats.primitive_count, stats.memory);
+    /// ```
+    pub fn stats(&self) -> ModelStats {
+        let vertex_count = self.vertices.len();
+        let index_count = self.indices().map(Vec::len);
+        let count = index_count.unwrap_or(vertex_count);
+        let primitive_count = match self.mode {
+            Mode::Points => count,
+            Mode::Lines => count / 2,
+            Mode::LineStrip => count.saturating_sub(1),
+            Mode::LineLoop if count > 1 => count,
+            Mode::LineLoop => 0,
+            Mode::Triangles => count / 3,
+            Mode::TriangleStrip | Mode::TriangleFan => count.saturating_sub(2),
+        };
+
+        ModelStats {
+            mode: self.mode(),
+            vertex_count,
+            index_count,
+            primitive_count,
+            has_normals: self.has_normals,
+            has_tangents: self.has_tangents,
+            has_tex_coords: self.has_tex_coords,
+            #[cfg(feature = "vertex-color")]
+            has_colors: self.has_colors,
+            memory: vertex_count * std::mem::size_of::<Vertex>()
+                + index_count.unwrap_or(0) * std::mem::size_of::<u32>()
+                + self.compact_colors.as_ref().map_or(0, |c| c.len() * 4)
+                + self.positions_f64.as_ref().map_or(0, |p| p.len() * 24)
+                + self.tex_coords_1.as_ref().map_or(0, |t| t.len() * 8)
+                + self.joints.as_ref().map_or(0, |j| j.len() * 8)
+                + self.weights.as_ref().map_or(0, |w| w.len() * 16),
+        }
+    }
+}
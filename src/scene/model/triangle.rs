@@ -0,0 +1,117 @@
+use super::Vertex;
+use cgmath::*;
+use std::ops::{Deref, DerefMut};
+
+/// Represents the 3 vertices of a triangle.
+///
+/// Derefs to `[Vertex; 3]`, so vertices are accessed with `triangle[i]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Triangle(pub [Vertex; 3]);
+
+impl Triangle {
+    /// Normalized normal of the triangle face, following the counter-clockwise
+    /// winding. `None` for degenerate triangles.
+    pub fn face_normal(&self) -> Option<Vector3<f32>> {
+        let normal = self.cross();
+        if normal.is_zero() {
+            None
+        } else {
+            Some(normal.normalize())
+        }
+    }
+
+    /// Area of the triangle.
+    pub fn area(&self) -> f32 {
+        self.cross().magnitude() / 2.
+    }
+
+    /// Center of mass of the triangle.
+    pub fn centroid(&self) -> Vector3<f32> {
+        (self.0[0].position + self.0[1].position + self.0[2].position) / 3.
+    }
+
+    /// Cross product of the edges, its length is twice the area.
+    fn cross(&self) -> Vector3<f32> {
+        let [a, b, c] = self.0.map(|v| v.position);
+        (b - a).cross(c - a)
+    }
+
+    /// Tangent space basis of the triangle computed from its positions and
+    /// texture coordinates: the columns are the tangent (direction of +U), the
+    /// bitangent (direction of -V, up in the texture as glTF texture
+    /// coordinates start at the top-left corner) and the face normal, all
+    /// normalized.
+    ///
+    /// Returns `None` for degenerate triangles or texture coordinates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// for triangle in scenes[0].models[0].triangles().unwrap() {
+    ///     if let Some(tbn) = triangle.tangent_basis() {
+    ///         let (tangent, bitangent, normal) = (tbn.x, tbn.y, tbn.z);
+    ///     }
+    /// }
+    /// ```
+    pub fn tangent_basis(&self) -> Option<Matrix3<f32>> {
+        let [a, b, c] = self.0;
+        let (e1, e2) = (b.position - a.position, c.position - a.position);
+        let (uv1, uv2) = (b.tex_coords - a.tex_coords, c.tex_coords - a.tex_coords);
+        let normal = e1.cross(e2);
+        let det = uv1.x * uv2.y - uv2.x * uv1.y;
+        if normal.magnitude2() <= f32::EPSILON * f32::EPSILON || det.abs() <= f32::EPSILON {
+            return None;
+        }
+        let tangent = (e1 * uv2.y - e2 * uv1.y) / det;
+        let bitangent = (e1 * uv2.x - e2 * uv1.x) / det;
+        Some(Matrix3::from_cols(
+            tangent.normalize(),
+            bitangent.normalize(),
+            normal.normalize(),
+        ))
+    }
+
+    /// Tangent of the triangle in the format of `Vertex::tangent`: the w
+    /// component is the handedness of the tangent basis (`-1` for mirrored
+    /// texture coordinates). A cheap fallback when a model has no tangents,
+    /// see [`tangent_basis`](#method.tangent_basis).
+    pub fn tangent(&self) -> Option<Vector4<f32>> {
+        let tbn = self.tangent_basis()?;
+        let handedness = if tbn.z.cross(tbn.x).dot(tbn.y) < 0. {
+            -1.
+        } else {
+            1.
+        };
+        Some(tbn.x.extend(handedness))
+    }
+}
+
+impl Deref for Triangle {
+    type Target = [Vertex; 3];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Triangle {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<[Vertex; 3]> for Triangle {
+    fn from(vertices: [Vertex; 3]) -> Self {
+        Triangle(vertices)
+    }
+}
+
+impl<'a> IntoIterator for &'a Triangle {
+    type Item = &'a Vertex;
+    type IntoIter = std::slice::Iter<'a, Vertex>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
@@ -1,11 +1,5 @@
 use cgmath::*;
 
-/// Represents the 3 vertices of a triangle.
-pub type Triangle = [Vertex; 3];
-
-/// Represents the 2 vertices of a line.
-pub type Line = [Vertex; 2];
-
 /// Contains a position, normal and texture coordinates vectors.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -36,3 +30,73 @@ impl Default for Vertex {
         }
     }
 }
+
+impl Vertex {
+    /// Vertex color as linear RGBA in `[0, 1]`. Requires the `vertex-color`
+    /// feature.
+    ///
+    /// Colors are stored as `u16` whatever the type of the glTF accessor
+    /// (`u8`, `u16` or `f32`), this converts them back to floats.
+    #[cfg(feature = "vertex-color")]
+    pub fn color_f32(&self) -> Vector4<f32> {
+        self.color.map(|c| c as f32 / u16::MAX as f32)
+    }
+}
+
+/// Plain-array counterpart of [`Vertex`](struct.Vertex.html) for code that
+/// doesn't use `cgmath` types, e.g. to send bytes directly to the GPU.
+///
+/// The memory layout is identical to `Vertex`.
+///
+/// **Note**: This is only a view of the vertices, easy-gltf itself still
+/// depends on `cgmath` and the other types of the API use it.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RawVertex {
+    /// Position
+    pub position: [f32; 3],
+    /// Normalized normal
+    pub normal: [f32; 3],
+    /// Tangent normal
+    /// The w component is the handedness of the tangent basis (can be -1 or 1)
+    pub tangent: [f32; 4],
+    /// Texture coordinates
+    pub tex_coords: [f32; 2],
+    /// Vertex color
+    #[cfg(feature = "vertex-color")]
+    pub color: [u16; 4],
+}
+
+const _: () = assert!(std::mem::size_of::<RawVertex>() == std::mem::size_of::<Vertex>());
+
+impl From<Vertex> for RawVertex {
+    fn from(vertex: Vertex) -> Self {
+        RawVertex {
+            position: vertex.position.into(),
+            normal: vertex.normal.into(),
+            tangent: vertex.tangent.into(),
+            tex_coords: vertex.tex_coords.into(),
+            #[cfg(feature = "vertex-color")]
+            color: vertex.color.into(),
+        }
+    }
+}
+
+impl From<RawVertex> for Vertex {
+    fn from(vertex: RawVertex) -> Self {
+        Vertex {
+            position: vertex.position.into(),
+            normal: vertex.normal.into(),
+            tangent: vertex.tangent.into(),
+            tex_coords: vertex.tex_coords.into(),
+            #[cfg(feature = "vertex-color")]
+            color: vertex.color.into(),
+        }
+    }
+}
+
+impl Default for RawVertex {
+    fn default() -> Self {
+        Vertex::default().into()
+    }
+}
@@ -0,0 +1,327 @@
+use super::animation::slerp;
+use super::{Animation, Model, Scene};
+use crate::utils::normal_matrix;
+use cgmath::*;
+use std::collections::HashMap;
+
+/// Values of the animated properties of the nodes of a scene at a given
+/// time, see [`Animation::sample`](struct.Animation.html#method.sample).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Pose {
+    /// Animated properties by node index, the other nodes keep their rest
+    /// transform.
+    pub nodes: HashMap<usize, NodePose>,
+}
+
+/// Animated properties of a node, `None` for the properties keeping their
+/// rest value.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NodePose {
+    /// Local translation.
+    pub translation: Option<Vector3<f32>>,
+    /// Local rotation.
+    pub rotation: Option<Quaternion<f32>>,
+    /// Local scale.
+    pub scale: Option<Vector3<f32>>,
+    /// Weights of the morph targets of the node mesh.
+    pub weights: Option<Vec<f32>>,
+}
+
+impl Pose {
+    /// Mix two poses, `weight` going from `0` (only `self`) to `1` (only
+    /// `other`), e.g. to cross-fade two clips. Translations, scales and morph
+    /// target weights are interpolated linearly and rotations along the
+    /// shortest path.
+    ///
+    /// Properties set in only one pose are kept as is: fill both poses with
+    /// the rest pose first (see [`fill`](#method.fill)) to fade properties
+    /// animated by a single clip.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/skin.gltf").expect("Failed to load glTF");
+    /// let (scene, clip) = (&scenes[0], &scenes[0].animations[0]);
+    /// let rest = scene.rest_pose();
+    /// let mut from = clip.sample(0.2);
+    /// let mut to = clip.sample(0.8);
+    /// from.fill(&rest);
+    /// to.fill(&rest);
+    /// let frame = scene.posed(&from.blend(&to, 0.25));
+    /// ```
+    pub fn blend(&self, other: &Pose, weight: f32) -> Pose {
+        let mut pose = self.clone();
+        for (index, b) in other.nodes.iter() {
+            let a = pose.nodes.entry(*index).or_default();
+            a.translation = mix(a.translation, b.translation, |a, b| a.lerp(b, weight));
+            a.rotation = mix(a.rotation, b.rotation, |a, b| slerp(a, b, weight));
+            a.scale = mix(a.scale, b.scale, |a, b| a.lerp(b, weight));
+            a.weights = mix(a.weights.take(), b.weights.clone(), |a, b| {
+                let len = a.len().max(b.len());
+                let get = |w: &[f32], i: usize| w.get(i).copied().unwrap_or(0.);
+                (0..len)
+                    .map(|i| get(&a, i) + (get(&b, i) - get(&a, i)) * weight)
+                    .collect()
+            });
+        }
+        pose
+    }
+
+    /// Set the properties missing from the pose to their value in `rest`,
+    /// see [`Scene::rest_pose`](struct.Scene.html#method.rest_pose).
+    pub fn fill(&mut self, rest: &Pose) {
+        for (index, rest) in rest.nodes.iter() {
+            let node = self.nodes.entry(*index).or_default();
+            node.translation = node.translation.or(rest.translation);
+            node.rotation = node.rotation.or(rest.rotation);
+            node.scale = node.scale.or(rest.scale);
+            if node.weights.is_none() {
+                node.weights = rest.weights.clone();
+            }
+        }
+    }
+}
+
+/// Mix two optional values, keeping the one set if the other is missing.
+fn mix<T, F>(a: Option<T>, b: Option<T>, f: F) -> Option<T>
+where
+    F: FnOnce(T, T) -> T,
+{
+    match (a, b) {
+        (Some(a), Some(b)) => Some(f(a, b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Rest transform of a glTF node, to evaluate poses.
+#[derive(Clone, Debug)]
+pub(crate) struct Node {
+    parent: Option<usize>,
+    translation: Vector3<f32>,
+    rotation: Quaternion<f32>,
+    scale: Vector3<f32>,
+}
+
+impl Node {
+    /// Rest transform of every node of the document, by node index.
+    pub(crate) fn load_all(doc: &gltf::Document) -> Vec<Node> {
+        let mut nodes: Vec<_> = doc
+            .nodes()
+            .map(|node| {
+                let (translation, [x, y, z, w], scale) = node.transform().decomposed();
+                Node {
+                    parent: None,
+                    translation: translation.into(),
+                    rotation: Quaternion::new(w, x, y, z),
+                    scale: scale.into(),
+                }
+            })
+            .collect();
+        for node in doc.nodes() {
+            for child in node.children() {
+                nodes[child.index()].parent = Some(node.index());
+            }
+        }
+        nodes
+    }
+}
+
+impl Scene {
+    /// Copy of the scene posed at `time` (in seconds) of an animation clip:
+    /// node transforms, skinning and morph target weights are baked into the
+    /// models, cameras, lights and markers. Handy to render a given frame
+    /// without an animation system, see [`posed`](#method.posed).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/skin.gltf").expect("Failed to load glTF");
+    /// let frame = scenes[0].pose_at(&scenes[0].animations[0], 0.5);
+    /// // Render `frame.models`...
+    /// ```
+    pub fn pose_at(&self, clip: &Animation, time: f32) -> Scene {
+        self.posed(&clip.sample(time))
+    }
+
+    /// Rest translation, rotation and scale of every node.
+    pub fn rest_pose(&self) -> Pose {
+        let nodes = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| {
+                let pose = NodePose {
+                    translation: Some(node.translation),
+                    rotation: Some(node.rotation),
+                    scale: Some(node.scale),
+                    weights: None,
+                };
+                (index, pose)
+            })
+            .collect();
+        Pose { nodes }
+    }
+
+    /// Copy of the scene in the given pose, see
+    /// [`pose_at`](#method.pose_at).
+    ///
+    /// Skinned models are skinned with their first 4 joint influences and
+    /// lose their skin, the posed models lose their morph targets (applied with
+    /// the pose weights, or `Model::morph_weights` if not animated). The posed
+    /// scene keeps its animations, with the pose as rest transform.
+    ///
+    /// **Note**: Models, cameras, lights and markers are moved from their
+    /// transform at load, so objects moved individually since then (not
+    /// through `Scene::apply_transform`) may be posed incorrectly.
+    pub fn posed(&self, pose: &Pose) -> Scene {
+        let rest = self.global_transforms(None);
+        let posed = self.global_transforms(Some(pose));
+        // Transform moving an object of a node from its rest to its pose
+        let delta = |node: usize| -> Option<Matrix4<f32>> {
+            let (rest, posed) = (rest.get(node)?, posed.get(node)?);
+            Some(rest.invert()? * posed).filter(|delta| !delta.is_identity())
+        };
+
+        let mut scene = self.clone();
+        for model in scene.models.iter_mut() {
+            // Nodes without animated weights keep their default pose
+            let defaults = std::mem::take(&mut model.morph_weights);
+            let weights = pose
+                .nodes
+                .get(&model.node_index)
+                .and_then(|node| node.weights.as_deref())
+                .unwrap_or(&defaults);
+            model.apply_morph_weights(weights);
+            model.morph_targets.clear();
+
+            // Transform baked into the vertices at load
+            let bind = model.transform.unwrap_or_else(Matrix4::identity);
+            let inverse_bind = match bind.invert() {
+                Some(inverse) => inverse,
+                None => continue,
+            };
+            let skin = model
+                .skin_index
+                .and_then(|i| self.skins.iter().find(|s| s.index == i));
+            if let Some(skin) = skin {
+                // Transform of the scene above the nodes
+                let scene_transform = match rest.get(model.node_index).and_then(|m| m.invert()) {
+                    Some(inverse) => bind * inverse,
+                    None => continue,
+                };
+                let joints: Vec<_> = skin
+                    .joints
+                    .iter()
+                    .zip(skin.inverse_bind_matrices.iter())
+                    .map(|(&joint, ibm)| {
+                        let joint = posed.get(joint).copied().unwrap_or_else(Matrix4::identity);
+                        scene_transform * joint * ibm * inverse_bind
+                    })
+                    .collect();
+                model.skin(&joints);
+                model.skin_index = None;
+                model.transform = Some(scene_transform * posed[model.node_index]);
+            } else if let Some(delta) = delta(model.node_index) {
+                model.apply_transform(&(bind * delta * inverse_bind));
+            }
+        }
+        for camera in scene.cameras.iter_mut() {
+            if let Some(delta) = delta(camera.node_index) {
+                camera.transform = camera.transform * delta;
+            }
+        }
+        for light in scene.lights.iter_mut() {
+            if let Some(delta) = delta(light.node_index) {
+                light.transform = light.transform * delta;
+            }
+        }
+        for marker in scene.markers.iter_mut() {
+            if let Some(delta) = delta(marker.node_index) {
+                marker.transform = marker.transform * delta;
+            }
+        }
+
+        for (index, node) in pose.nodes.iter() {
+            if let Some(rest) = scene.nodes.get_mut(*index) {
+                rest.translation = node.translation.unwrap_or(rest.translation);
+                rest.rotation = node.rotation.unwrap_or(rest.rotation);
+                rest.scale = node.scale.unwrap_or(rest.scale);
+            }
+        }
+        scene
+    }
+
+    /// Transform of every node relative to the scene, in the rest pose or
+    /// the given pose.
+    fn global_transforms(&self, pose: Option<&Pose>) -> Vec<Matrix4<f32>> {
+        let local = |index: usize| {
+            let node = &self.nodes[index];
+            let animated = pose.and_then(|pose| pose.nodes.get(&index));
+            let translation = animated
+                .and_then(|n| n.translation)
+                .unwrap_or(node.translation);
+            let rotation = animated.and_then(|n| n.rotation).unwrap_or(node.rotation);
+            let scale = animated.and_then(|n| n.scale).unwrap_or(node.scale);
+            Matrix4::from_translation(translation)
+                * Matrix4::from(rotation)
+                * Matrix4::from_nonuniform_scale(scale.x, scale.y, scale.z)
+        };
+        let mut globals: Vec<Option<Matrix4<f32>>> = vec![None; self.nodes.len()];
+        for index in 0..self.nodes.len() {
+            // Walk up to the first node with a known transform
+            let mut chain = vec![index];
+            while let Some(parent) = self.nodes[*chain.last().unwrap()].parent {
+                if globals[parent].is_some() {
+                    break;
+                }
+                chain.push(parent);
+            }
+            for &node in chain.iter().rev() {
+                if globals[node].is_some() {
+                    continue;
+                }
+                let parent = self.nodes[node].parent.and_then(|parent| globals[parent]);
+                globals[node] = Some(parent.unwrap_or_else(Matrix4::identity) * local(node));
+            }
+        }
+        globals.into_iter().map(Option::unwrap).collect()
+    }
+}
+
+impl Model {
+    /// Skin the vertices with the transform of every joint (from the current
+    /// vertices to the posed vertices).
+    fn skin(&mut self, joints: &[Matrix4<f32>]) {
+        let (indices, weights) = match (self.joints.clone(), self.weights.clone()) {
+            (Some(indices), Some(weights)) => (indices, weights),
+            _ => return,
+        };
+        let (has_normals, has_tangents) = (self.has_normals, self.has_tangents);
+        let vertices = self.vertices_mut();
+        for (vertex, (indices, weights)) in vertices.iter_mut().zip(indices.iter().zip(&weights)) {
+            let mut matrix = Matrix4::zero();
+            for (&joint, &weight) in indices.iter().zip(weights.iter()) {
+                if let Some(joint) = joints.get(joint as usize) {
+                    matrix += joint * weight;
+                }
+            }
+            vertex.position = matrix
+                .transform_point(Point3::from_vec(vertex.position))
+                .to_vec();
+            if has_normals {
+                vertex.normal = (normal_matrix(&matrix) * vertex.normal).normalize();
+            }
+            if has_tangents {
+                let tangent = matrix
+                    .transform_vector(vertex.tangent.truncate())
+                    .normalize();
+                vertex.tangent = tangent.extend(vertex.tangent.w);
+            }
+        }
+        if let Some(positions) = self.positions_f64.as_mut() {
+            for (p, vertex) in positions.iter_mut().zip(self.vertices.iter()) {
+                *p = vertex.position.cast().unwrap();
+            }
+        }
+    }
+}
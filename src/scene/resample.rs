@@ -0,0 +1,149 @@
+use super::animation::slerp;
+use super::{Animation, Interpolation, Keyframes, Track};
+use cgmath::*;
+
+impl Animation {
+    /// Copy of the clip with its tracks resampled at `fps` frames per second,
+    /// keeping only the frames that can't be interpolated from their
+    /// neighbours within `tolerance`. Runtime engines usually want such
+    /// uniform and compact tracks rather than the raw keys of exporters.
+    ///
+    /// The tolerance is in the units of the animated property: distance for
+    /// translations, angle in radians for rotations, factor for scales and
+    /// weights. Cubic spline tracks become linear, step tracks stay step.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/skin.gltf").expect("Failed to load glTF");
+    /// let clip = scenes[0].animations[0].resampled(30., 0.001);
+    /// ```
+    pub fn resampled(&self, fps: f32, tolerance: f32) -> Animation {
+        Animation {
+            tracks: self
+                .tracks
+                .iter()
+                .map(|track| track.resampled(fps, tolerance))
+                .collect(),
+            ..self.clone()
+        }
+    }
+}
+
+impl Track {
+    fn resampled(&self, fps: f32, tolerance: f32) -> Track {
+        let (start, end) = match (self.times.first(), self.times.last()) {
+            (Some(&start), Some(&end)) if fps > 0. => (start, end),
+            _ => return self.clone(),
+        };
+        let count = ((end - start) * fps).ceil() as usize + 1;
+        let times: Vec<_> = (0..count)
+            .map(|i| (start + i as f32 / fps).min(end))
+            .collect();
+        let frames: Vec<_> = times.iter().map(|&time| self.frame(time)).collect();
+        let interpolation = match self.interpolation {
+            Interpolation::Step => Interpolation::Step,
+            _ => Interpolation::Linear,
+        };
+        let rotation = matches!(self.values, Keyframes::Rotation(_));
+
+        // Greedily drop the frames interpolated well enough by the kept ones
+        let mut kept = vec![0];
+        for next in 2..frames.len() {
+            let last = *kept.last().unwrap();
+            let redundant = (last + 1..next).all(|i| {
+                let expected = match interpolation {
+                    Interpolation::Step => frames[last].clone(),
+                    _ => {
+                        let t = (times[i] - times[last]) / (times[next] - times[last]);
+                        lerp(&frames[last], &frames[next], t, rotation)
+                    }
+                };
+                error(&expected, &frames[i], rotation) <= tolerance
+            });
+            if !redundant {
+                kept.push(next - 1);
+            }
+        }
+        if frames.len() > 1 {
+            kept.push(frames.len() - 1);
+        }
+
+        let values: Vec<f32> = kept.iter().flat_map(|&i| frames[i].clone()).collect();
+        Track {
+            times: kept.iter().map(|&i| times[i]).collect(),
+            values: match self.values {
+                Keyframes::Translation(_) => Keyframes::Translation(
+                    values
+                        .chunks_exact(3)
+                        .map(|v| Vector3::new(v[0], v[1], v[2]))
+                        .collect(),
+                ),
+                Keyframes::Rotation(_) => Keyframes::Rotation(
+                    values
+                        .chunks_exact(4)
+                        .map(|v| Quaternion::new(v[3], v[0], v[1], v[2]))
+                        .collect(),
+                ),
+                Keyframes::Scale(_) => Keyframes::Scale(
+                    values
+                        .chunks_exact(3)
+                        .map(|v| Vector3::new(v[0], v[1], v[2]))
+                        .collect(),
+                ),
+                Keyframes::MorphTargetWeights(_) => Keyframes::MorphTargetWeights(values),
+            },
+            interpolation,
+        }
+    }
+
+    /// Value of the track at `time` as a flat list of floats (`x, y, z, w`
+    /// for rotations).
+    fn frame(&self, time: f32) -> Vec<f32> {
+        let lerp = |a: Vector3<f32>, b, t| a.lerp(b, t);
+        let vector = |v: Option<Vector3<f32>>| v.map_or(vec![], |v| vec![v.x, v.y, v.z]);
+        match &self.values {
+            Keyframes::Translation(values) => vector(self.interpolate(values, 1, 0, time, lerp)),
+            Keyframes::Scale(values) => vector(self.interpolate(values, 1, 0, time, lerp)),
+            Keyframes::Rotation(values) => {
+                self.interpolate(values, 1, 0, time, slerp)
+                    .map_or(vec![], |q| {
+                        let q = q.normalize();
+                        vec![q.v.x, q.v.y, q.v.z, q.s]
+                    })
+            }
+            Keyframes::MorphTargetWeights(values) => {
+                let width = values.len() / self.runs();
+                (0..width)
+                    .filter_map(|i| {
+                        self.interpolate(values, width, i, time, |a, b, t| a + (b - a) * t)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+fn lerp(a: &[f32], b: &[f32], t: f32, rotation: bool) -> Vec<f32> {
+    if rotation {
+        let q = slerp(quaternion(a), quaternion(b), t);
+        return vec![q.v.x, q.v.y, q.v.z, q.s];
+    }
+    a.iter().zip(b).map(|(a, b)| a + (b - a) * t).collect()
+}
+
+/// Largest difference between two values, the angle between rotations.
+fn error(a: &[f32], b: &[f32], rotation: bool) -> f32 {
+    if rotation {
+        let dot = quaternion(a).dot(quaternion(b)).abs().min(1.);
+        return 2. * dot.acos();
+    }
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (a - b).abs())
+        .fold(0., f32::max)
+}
+
+fn quaternion(v: &[f32]) -> Quaternion<f32> {
+    Quaternion::new(v[3], v[0], v[1], v[2])
+}
@@ -0,0 +1,59 @@
+use super::{Animation, Interpolation, Keyframes, Property, Track};
+use cgmath::*;
+
+impl Animation {
+    /// Move the translation of `node` (usually the root joint) along `axes`
+    /// out of the clip and return it as a separate motion track, e.g. to let
+    /// the game move a character with the distance walked by its animation.
+    ///
+    /// `axes` selects the extracted components: `Vector3::new(1., 0., 1.)`
+    /// extracts the motion on the ground (glTF is Y up) and keeps jumps in the
+    /// clip. The clip keeps the node at its starting position on those axes,
+    /// the motion track holds the displacement from that position. Returns
+    /// `None` if the clip doesn't translate the node.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cgmath::*;
+    /// let mut scenes = easy_gltf::load("tests/skin.gltf").expect("Failed to load glTF");
+    /// let root = scenes[0].skins[0].skeleton.unwrap();
+    /// let clip = &mut scenes[0].animations[0];
+    /// let motion = clip.extract_root_motion(root, Vector3::new(1., 0., 1.));
+    /// ```
+    pub fn extract_root_motion(&mut self, node: usize, axes: Vector3<f32>) -> Option<Track> {
+        let index = self.channels.iter().position(|channel| {
+            channel.node == node && channel.property == Property::Translation
+        })?;
+        let track = &mut self.tracks[index];
+        let cubic = track.interpolation == Interpolation::CubicSpline;
+        let values = match &mut track.values {
+            Keyframes::Translation(values) => values,
+            _ => return None,
+        };
+        let start = *values.get(if cubic { 1 } else { 0 })?;
+        let keep = Vector3::new(1., 1., 1.) - axes;
+
+        let motion = values
+            .iter_mut()
+            .enumerate()
+            .map(|(i, value)| {
+                if cubic && i % 3 != 1 {
+                    // Tangents
+                    let extracted = value.mul_element_wise(axes);
+                    *value = value.mul_element_wise(keep);
+                    extracted
+                } else {
+                    let extracted = (*value - start).mul_element_wise(axes);
+                    *value = value.mul_element_wise(keep) + start.mul_element_wise(axes);
+                    extracted
+                }
+            })
+            .collect();
+        Some(Track {
+            times: track.times.clone(),
+            values: Keyframes::Translation(motion),
+            interpolation: track.interpolation,
+        })
+    }
+}
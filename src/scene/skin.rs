@@ -0,0 +1,53 @@
+use crate::utils::{accessor, accessor_views, GltfData};
+use cgmath::*;
+
+/// Skin (skeleton) used by some models of a scene.
+///
+/// **Note**: Inverse bind matrices are given as in the glTF, `LoadOptions`
+/// transforms (up axis, scale...) are not applied to them.
+#[derive(Clone, Debug, Default)]
+pub struct Skin {
+    #[cfg(feature = "names")]
+    /// Skin name. Requires the `names` feature.
+    pub name: Option<String>,
+
+    #[cfg(feature = "extras")]
+    /// Skin extra data. Requires the `extras` feature.
+    pub extras: gltf::json::extras::Extras,
+
+    /// Index of the glTF skin, see `Model::skin_index()`.
+    pub index: usize,
+
+    /// Index of the node declared as the skeleton root, if any.
+    pub skeleton: Option<usize>,
+
+    /// Indices of the joint nodes.
+    pub joints: Vec<usize>,
+
+    /// Inverse bind matrix of each joint.
+    pub inverse_bind_matrices: Vec<Matrix4<f32>>,
+}
+
+impl Skin {
+    pub(crate) fn load(gltf_skin: gltf::Skin, data: &GltfData) -> gltf::Result<Self> {
+        let matrices = gltf_skin.inverse_bind_matrices();
+        let buffers = data.buffers.get(matrices.iter().flat_map(accessor_views))?;
+        let joints: Vec<_> = gltf_skin.joints().map(|joint| joint.index()).collect();
+        let matrices =
+            matrices.and_then(|matrices| accessor::iter::<[[f32; 4]; 4]>(&matrices, &buffers));
+        let inverse_bind_matrices = match matrices {
+            Some(matrices) => matrices.map(Matrix4::<f32>::from).collect(),
+            None => vec![Matrix4::identity(); joints.len()],
+        };
+        Ok(Self {
+            #[cfg(feature = "names")]
+            name: gltf_skin.name().map(String::from),
+            #[cfg(feature = "extras")]
+            extras: gltf_skin.extras().clone(),
+            index: gltf_skin.index(),
+            skeleton: gltf_skin.skeleton().map(|node| node.index()),
+            joints,
+            inverse_bind_matrices,
+        })
+    }
+}
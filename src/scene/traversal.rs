@@ -0,0 +1,287 @@
+use super::{Camera, Light, Marker, Material, Model};
+use crate::utils::{primitive_views, transform_to_matrix, Buffers, GltfData, Scratch};
+use crate::{LoadOptions, NodeAction};
+use cgmath::*;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+/// Object converted from a glTF node.
+// Models are by far the most common items, boxing them would only add an
+// allocation per primitive
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+pub enum SceneItem {
+    /// Model converted from a mesh primitive.
+    Model(Model),
+    /// Camera attached to a node.
+    Camera(Camera),
+    /// Light attached to a node.
+    Light(Light),
+    /// Node without mesh, camera nor light.
+    Marker(Marker),
+    /// Material of a mesh primitive, only yielded with
+    /// `LoadOptions::materials_only`.
+    Material(Arc<Material>),
+}
+
+enum Step {
+    Visit(usize, Matrix4<f64>),
+    Camera(usize, Matrix4<f64>),
+    Light(usize, Matrix4<f64>),
+    Marker(usize, Matrix4<f64>),
+    Primitive(usize, Matrix4<f64>, usize),
+    Material(usize, usize),
+}
+
+/// Object reached by a [`Traversal`], primitives can be converted later.
+// Short-lived, moved once per node object
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum Visit {
+    Item(SceneItem),
+    Primitive(PrimitiveJob),
+}
+
+/// Mesh primitive of a node, to convert into a model.
+pub(crate) struct PrimitiveJob {
+    node: usize,
+    transform: Matrix4<f64>,
+    primitive: usize,
+}
+
+impl PrimitiveJob {
+    /// Convert the geometry of the primitive, see `Model::convert`.
+    fn convert(
+        &self,
+        doc: &gltf::Document,
+        buffers: &Buffers,
+        options: &LoadOptions,
+        scratch: &mut Scratch,
+    ) -> gltf::Result<Model> {
+        let node = doc.nodes().nth(self.node).unwrap();
+        let mesh = node.mesh().unwrap();
+        let primitive = mesh.primitives().nth(self.primitive).unwrap();
+        let buffers = buffers.get(primitive_views(&primitive))?;
+        Ok(Model::convert(
+            &node,
+            &mesh,
+            self.primitive,
+            &primitive,
+            &self.transform,
+            &buffers,
+            options,
+            scratch,
+        ))
+    }
+
+    fn material(&self, doc: &gltf::Document, data: &mut GltfData) -> Arc<Material> {
+        let mesh = doc.nodes().nth(self.node).unwrap().mesh().unwrap();
+        let primitive = mesh.primitives().nth(self.primitive).unwrap();
+        Material::load(primitive.material(), data)
+    }
+
+    pub fn load(&self, doc: &gltf::Document, data: &mut GltfData) -> gltf::Result<Model> {
+        let node = doc.nodes().nth(self.node).unwrap();
+        let mesh = node.mesh().unwrap();
+        let primitive = mesh.primitives().nth(self.primitive).unwrap();
+        Model::load(
+            &node,
+            &mesh,
+            self.primitive,
+            primitive,
+            &self.transform,
+            data,
+        )
+    }
+}
+
+/// Convert primitives with `threads` worker threads, in the order of `jobs`.
+///
+/// The workers convert the geometry and send the models through a bounded
+/// queue while the calling thread loads the materials (and decodes their
+/// images), so both stages run at the same time.
+pub(crate) fn load_primitives(
+    doc: &gltf::Document,
+    jobs: &[PrimitiveJob],
+    data: &mut GltfData,
+    threads: usize,
+) -> gltf::Result<Vec<Model>> {
+    let (buffers, options) = (data.buffers.clone(), data.options.clone());
+    let next = AtomicUsize::new(0);
+    let mut models: Vec<Option<gltf::Result<Model>>> = jobs.iter().map(|_| None).collect();
+    let mut materials = Vec::with_capacity(jobs.len());
+    thread::scope(|scope| {
+        let (sender, receiver) = mpsc::sync_channel(threads * 2);
+        for _ in 0..threads.min(jobs.len()) {
+            let sender = sender.clone();
+            let (next, buffers, options) = (&next, &buffers, &options);
+            scope.spawn(move || {
+                let mut scratch = Scratch::default();
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let job = match jobs.get(i) {
+                        Some(job) => job,
+                        None => return,
+                    };
+                    let model = job.convert(doc, buffers, options, &mut scratch);
+                    if sender.send((i, model)).is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        for job in jobs {
+            materials.push(job.material(doc, data));
+            for (i, model) in receiver.try_iter() {
+                models[i] = Some(model);
+            }
+        }
+        for (i, model) in receiver {
+            models[i] = Some(model);
+        }
+    });
+    models
+        .into_iter()
+        .zip(materials)
+        .map(|(model, material)| {
+            let mut model = model.expect("primitive conversion failed")?;
+            model.material = material;
+            Ok(model)
+        })
+        .collect()
+}
+
+/// Depth-first walk over the nodes of a scene, converting one object at a time.
+///
+/// The children of a node are visited before the node itself, then its camera,
+/// its light and the primitives of its mesh in order.
+pub(crate) struct Traversal {
+    stack: Vec<Step>,
+    /// Nodes used as skeleton joints, they are not markers.
+    joints: Option<HashSet<usize>>,
+}
+
+impl Traversal {
+    pub fn new(gltf_scene: &gltf::Scene, data: &GltfData) -> Self {
+        let root_transform = data.options.root_transform();
+        let mut stack: Vec<_> = gltf_scene
+            .nodes()
+            .map(|node| Step::Visit(node.index(), root_transform))
+            .collect();
+        stack.reverse();
+        Self {
+            stack,
+            joints: None,
+        }
+    }
+
+    pub fn next(
+        &mut self,
+        doc: &gltf::Document,
+        data: &mut GltfData,
+    ) -> Option<gltf::Result<SceneItem>> {
+        match self.visit(doc, data)? {
+            Visit::Item(item) => Some(Ok(item)),
+            Visit::Primitive(job) => Some(job.load(doc, data).map(SceneItem::Model)),
+        }
+    }
+
+    /// Same as `next` but primitives are returned unconverted.
+    pub fn visit(&mut self, doc: &gltf::Document, data: &mut GltfData) -> Option<Visit> {
+        while let Some(step) = self.stack.pop() {
+            match step {
+                Step::Visit(index, parent_transform) => {
+                    let node = doc.nodes().nth(index).unwrap();
+                    let action = data
+                        .options
+                        .node_filter
+                        .map_or(NodeAction::Load, |f| f(&node));
+                    if action == NodeAction::SkipSubtree {
+                        continue;
+                    }
+                    let transform = parent_transform * transform_to_matrix(node.transform());
+                    let options = &data.options;
+                    let load_self = action == NodeAction::Load;
+                    if let Some(mesh) = node.mesh().filter(|_| load_self) {
+                        let scale = Matrix4::from_scale(options.scale_factor as f64);
+                        let transform = scale * transform;
+                        for i in (0..mesh.primitives().len()).rev() {
+                            if options.materials_only {
+                                self.stack.push(Step::Material(index, i));
+                            } else if !options.skip_geometry {
+                                self.stack.push(Step::Primitive(index, transform, i));
+                            }
+                        }
+                    }
+                    let skip_all = options.materials_only || !load_self;
+                    if node.light().is_some() && !options.skip_lights && !skip_all {
+                        self.stack.push(Step::Light(index, transform));
+                    }
+                    if node.camera().is_some() && !options.skip_cameras && !skip_all {
+                        self.stack.push(Step::Camera(index, transform));
+                    }
+                    let empty = node.mesh().is_none() && node.camera().is_none();
+                    if empty && node.light().is_none() {
+                        let joints = self.joints.get_or_insert_with(|| {
+                            doc.skins()
+                                .flat_map(|skin| skin.joints().map(|joint| joint.index()))
+                                .collect()
+                        });
+                        if !skip_all && !joints.contains(&index) {
+                            self.stack.push(Step::Marker(index, transform));
+                        }
+                    }
+                    let children: Vec<_> = node.children().map(|child| child.index()).collect();
+                    for child in children.into_iter().rev() {
+                        self.stack.push(Step::Visit(child, transform));
+                    }
+                }
+                Step::Camera(index, transform) => {
+                    let node = doc.nodes().nth(index).unwrap();
+                    return Some(Visit::Item(SceneItem::Camera(Camera::load(
+                        &node,
+                        node.camera().unwrap(),
+                        &transform.cast().unwrap(),
+                        &data.options,
+                    ))));
+                }
+                Step::Light(index, transform) => {
+                    let node = doc.nodes().nth(index).unwrap();
+                    return Some(Visit::Item(SceneItem::Light(Light::load(
+                        &node,
+                        node.light().unwrap(),
+                        &transform.cast().unwrap(),
+                        &data.options,
+                    ))));
+                }
+                Step::Marker(index, transform) => {
+                    let node = doc.nodes().nth(index).unwrap();
+                    return Some(Visit::Item(SceneItem::Marker(Marker::load(
+                        &node,
+                        &transform.cast().unwrap(),
+                        &data.options,
+                    ))));
+                }
+                Step::Primitive(node, transform, primitive) => {
+                    return Some(Visit::Primitive(PrimitiveJob {
+                        node,
+                        transform,
+                        primitive,
+                    }));
+                }
+                Step::Material(index, i) => {
+                    let mesh = doc.nodes().nth(index).unwrap().mesh().unwrap();
+                    let primitive = mesh.primitives().nth(i).unwrap();
+                    return Some(Visit::Item(SceneItem::Material(Material::load(
+                        primitive.material(),
+                        data,
+                    ))));
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,271 @@
+use super::Scene;
+use crate::model::{Mode, Model, Vertex};
+use cgmath::*;
+use std::fmt;
+
+/// Texture coordinates farther than this from `[0, 1]` are reported.
+const TEX_COORDS_TOLERANCE: f32 = 1.;
+
+/// A problem found by [`Scene::validate`](struct.Scene.html#method.validate)
+/// in a model.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Finding {
+    /// Index of the model in `Scene::models`.
+    pub model: usize,
+    /// The problem found.
+    pub issue: Issue,
+}
+
+/// How bad an [`Issue`](enum.Issue.html) is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The model can be rendered but may look wrong.
+    Warning,
+    /// The model is invalid and may crash or produce garbage.
+    Error,
+}
+
+/// Problems detected in a model.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Issue {
+    /// The model has no vertex, e.g. its primitive has no positions. It is
+    /// loaded empty.
+    EmptyPrimitive,
+    /// Some vertex positions are NaN or infinite.
+    NonFinitePositions {
+        /// Number of affected vertices.
+        count: usize,
+    },
+    /// Some indices reference vertices that don't exist.
+    IndicesOutOfRange {
+        /// Number of invalid indices.
+        count: usize,
+    },
+    /// The number of indices (or vertices) doesn't match the mode, e.g. not a
+    /// multiple of 3 for `Triangles`.
+    IncompletePrimitive {
+        /// Number of indices, or vertices if the model isn't indexed.
+        count: usize,
+    },
+    /// Some normals have a zero length or aren't finite.
+    InvalidNormals {
+        /// Number of affected vertices.
+        count: usize,
+    },
+    /// Some texture coordinates are far outside `[0, 1]` while a texture of
+    /// the material clamps them (see `Mapper::repeat`). Only the first set
+    /// of texture coordinates is checked.
+    TexCoordsOutOfRange {
+        /// Number of affected vertices.
+        count: usize,
+    },
+    /// The material has a normal map but the model has no tangents.
+    MissingTangents,
+    /// The material has textures but the model has no texture coordinates.
+    MissingTexCoords,
+    /// Some vertices have more joint influences than
+    /// `LoadOptions::max_influences`, the weakest ones were dropped.
+    DroppedInfluences {
+        /// Number of affected vertices.
+        count: usize,
+        /// Largest share of the weight of a vertex that was dropped.
+        max_weight: f32,
+    },
+}
+
+impl Issue {
+    /// Severity of the issue.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Issue::NonFinitePositions { .. }
+            | Issue::IndicesOutOfRange { .. }
+            | Issue::IncompletePrimitive { .. } => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Issue::NonFinitePositions { count } => {
+                write!(f, "{} positions are NaN or infinite", count)
+            }
+            Issue::EmptyPrimitive => write!(f, "primitive without vertices"),
+            Issue::IndicesOutOfRange { count } => write!(f, "{} indices are out of range", count),
+            Issue::IncompletePrimitive { count } => {
+                write!(f, "{} indices don't form complete primitives", count)
+            }
+            Issue::InvalidNormals { count } => {
+                write!(f, "{} normals are zero-length or invalid", count)
+            }
+            Issue::TexCoordsOutOfRange { count } => {
+                write!(f, "{} texture coordinates are far outside [0, 1]", count)
+            }
+            Issue::MissingTangents => write!(f, "normal map without tangents"),
+            Issue::MissingTexCoords => write!(f, "textured material without texture coordinates"),
+            Issue::DroppedInfluences { count, max_weight } => write!(
+                f,
+                "{} vertices lost joint influences (up to {:.1}% of their weight)",
+                count,
+                max_weight * 100.
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:?} in model #{}: {}",
+            self.issue.severity(),
+            self.model,
+            self.issue
+        )
+    }
+}
+
+impl Model {
+    /// Check the model for common problems (invalid values, out of range
+    /// indices, missing attributes...).
+    pub fn validate(&self) -> Vec<Issue> {
+        let mut issues = vec![];
+        let vertices = self.vertices();
+        if vertices.is_empty() {
+            issues.push(Issue::EmptyPrimitive);
+        }
+
+        let count = count_if(vertices, |v| !v.position.sum().is_finite());
+        if count > 0 {
+            issues.push(Issue::NonFinitePositions { count });
+        }
+
+        if let Some(indices) = self.indices() {
+            let count = indices
+                .iter()
+                .filter(|&&i| i as usize >= vertices.len())
+                .count();
+            if count > 0 {
+                issues.push(Issue::IndicesOutOfRange { count });
+            }
+        }
+
+        let count = self.indices().map_or(vertices.len(), Vec::len);
+        let incomplete = match self.mode {
+            Mode::Triangles => !count.is_multiple_of(3),
+            Mode::TriangleStrip | Mode::TriangleFan => count > 0 && count < 3,
+            Mode::Lines => !count.is_multiple_of(2),
+            Mode::LineStrip | Mode::LineLoop => count == 1,
+            Mode::Points => false,
+        };
+        if incomplete {
+            issues.push(Issue::IncompletePrimitive { count });
+        }
+
+        if self.has_normals() {
+            let count = count_if(vertices, |v| {
+                v.normal.magnitude2().is_nan() || v.normal.magnitude2() < 0.5
+            });
+            if count > 0 {
+                issues.push(Issue::InvalidNormals { count });
+            }
+        }
+
+        let material = &self.material;
+        let textured = material.pbr.base_color_texture.is_some()
+            || material.pbr.metallic_texture.is_some()
+            || material.pbr.roughness_texture.is_some()
+            || material.normal.is_some()
+            || material.occlusion.is_some()
+            || material.emissive.texture.is_some();
+        // Tiling textures use texture coordinates outside [0, 1] on purpose
+        let clamped = [
+            (
+                material.pbr.base_color_texture.is_some(),
+                material.pbr.base_color_mapper,
+            ),
+            (
+                material.pbr.metallic_texture.is_some() || material.pbr.roughness_texture.is_some(),
+                material.pbr.metallic_roughness_mapper,
+            ),
+            (
+                material.emissive.texture.is_some(),
+                material.emissive.mapper,
+            ),
+            (
+                material.normal.is_some(),
+                material
+                    .normal
+                    .as_ref()
+                    .map_or_else(Default::default, |n| n.mapper),
+            ),
+            (
+                material.occlusion.is_some(),
+                material
+                    .occlusion
+                    .as_ref()
+                    .map_or_else(Default::default, |o| o.mapper),
+            ),
+        ]
+        .iter()
+        .any(|(textured, mapper)| *textured && mapper.tex_coord == 0 && !mapper.repeat);
+        if !self.has_tex_coords() {
+            if textured {
+                issues.push(Issue::MissingTexCoords);
+            }
+        } else if clamped {
+            let range = -TEX_COORDS_TOLERANCE..=1. + TEX_COORDS_TOLERANCE;
+            let count = count_if(vertices, |v| {
+                !(range.contains(&v.tex_coords.x) && range.contains(&v.tex_coords.y))
+            });
+            if count > 0 {
+                issues.push(Issue::TexCoordsOutOfRange { count });
+            }
+        }
+
+        if material.normal.is_some() && !self.has_tangents() {
+            issues.push(Issue::MissingTangents);
+        }
+
+        if let Some(dropped) = self.dropped_influences {
+            issues.push(Issue::DroppedInfluences {
+                count: dropped.count,
+                max_weight: dropped.max_weight,
+            });
+        }
+        issues
+    }
+}
+
+fn count_if<F>(vertices: &[Vertex], f: F) -> usize
+where
+    F: Fn(&Vertex) -> bool,
+{
+    vertices.iter().filter(|v| f(v)).count()
+}
+
+impl Scene {
+    /// Check every model of the scene for common problems, see
+    /// [`Issue`](enum.Issue.html) for the list of checks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+    /// for finding in scenes[0].validate() {
+    ///     println!("{}", finding);
+    /// }
+    /// ```
+    pub fn validate(&self) -> Vec<Finding> {
+        self.models
+            .iter()
+            .enumerate()
+            .flat_map(|(model, m)| {
+                m.validate()
+                    .into_iter()
+                    .map(move |issue| Finding { model, issue })
+            })
+            .collect()
+    }
+}
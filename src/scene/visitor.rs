@@ -0,0 +1,61 @@
+use super::{Camera, Light, Material, Model, Scene};
+use std::sync::Arc;
+
+/// Callbacks called by [`Scene::visit`](struct.Scene.html#method.visit) for
+/// every object of a scene. Every method does nothing by default, implement
+/// the ones needed.
+///
+/// # Example
+///
+/// ```
+/// use easy_gltf::{Model, SceneVisitor};
+///
+/// #[derive(Default)]
+/// struct Counter {
+///     triangles: usize,
+/// }
+///
+/// impl SceneVisitor for Counter {
+///     fn visit_model(&mut self, _index: usize, model: &Model) {
+///         self.triangles += model.triangles().map_or(0, |t| t.len());
+///     }
+/// }
+///
+/// let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+/// let mut counter = Counter::default();
+/// scenes[0].visit(&mut counter);
+/// assert_eq!(counter.triangles, 12);
+/// ```
+pub trait SceneVisitor {
+    /// Called for every model, `index` is its index in `Scene::models`.
+    fn visit_model(&mut self, _index: usize, _model: &Model) {}
+
+    /// Called for every camera, `index` is its index in `Scene::cameras`.
+    fn visit_camera(&mut self, _index: usize, _camera: &Camera) {}
+
+    /// Called for every light, `index` is its index in `Scene::lights`.
+    fn visit_light(&mut self, _index: usize, _light: &Light) {}
+
+    /// Called for every material, `index` is its index in `Scene::materials`
+    /// (see `Model::material_index()`).
+    fn visit_material(&mut self, _index: usize, _material: &Arc<Material>) {}
+}
+
+impl Scene {
+    /// Walk every object of the scene in one pass: materials first, then
+    /// models, cameras and lights, each in the order of their list.
+    pub fn visit<V: SceneVisitor + ?Sized>(&self, visitor: &mut V) {
+        for (index, material) in self.materials.iter().enumerate() {
+            visitor.visit_material(index, material);
+        }
+        for (index, model) in self.models.iter().enumerate() {
+            visitor.visit_model(index, model);
+        }
+        for (index, camera) in self.cameras.iter().enumerate() {
+            visitor.visit_camera(index, camera);
+        }
+        for (index, light) in self.lights.iter().enumerate() {
+            visitor.visit_light(index, light);
+        }
+    }
+}
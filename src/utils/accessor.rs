@@ -0,0 +1,170 @@
+use super::BufferData;
+use gltf::accessor::util::{ItemIter, SparseIndicesIter, SparseIter};
+use gltf::accessor::{DataType, Dimensions, Item, Iter};
+use gltf::animation::util::{MorphTargetWeights, ReadOutputs, Rotations};
+use gltf::animation::Property;
+use gltf::mesh::util::{ReadColors, ReadIndices, ReadJoints, ReadWeights};
+use gltf::Semantic;
+use std::mem::size_of;
+
+/// Items of an accessor, like `gltf::accessor::Iter::new` but reading the
+/// views from buffers that may only hold a range of their bytes.
+///
+/// Unlike the gltf readers, accessors with an unexpected type or out of
+/// their buffer are `None` instead of panicking.
+pub fn iter<'s, T: Item>(
+    accessor: &gltf::Accessor,
+    buffers: &'s BufferData,
+) -> Option<Iter<'s, T>> {
+    // Bytes of `count` items of `size` bytes from `offset` in a view
+    let items = |view: gltf::buffer::View, offset: usize, count: usize, size: usize| {
+        let stride = view.stride().unwrap_or(size);
+        let data = buffers.view(&view)?;
+        let data = match count {
+            0 => &[],
+            _ => data.get(offset..offset + stride * (count - 1) + size)?,
+        };
+        Some((data, stride))
+    };
+    if size_of::<T>() != accessor.size() {
+        return None;
+    }
+
+    let sparse = match accessor.sparse() {
+        Some(sparse) => sparse,
+        None => {
+            let (data, stride) = items(
+                accessor.view()?,
+                accessor.offset(),
+                accessor.count(),
+                size_of::<T>(),
+            )?;
+            return Some(Iter::Standard(ItemIter::new(data, stride)));
+        }
+    };
+    let base = match accessor.view() {
+        Some(view) => {
+            let (data, stride) = items(view, accessor.offset(), accessor.count(), size_of::<T>())?;
+            Some(ItemIter::new(data, stride))
+        }
+        None => None,
+    };
+    let indices = sparse.indices();
+    let index_size = indices.index_type().size();
+    let (data, stride) = items(indices.view(), indices.offset(), sparse.count(), index_size)?;
+    let indices = match indices.index_type() {
+        gltf::accessor::sparse::IndexType::U8 => SparseIndicesIter::U8(ItemIter::new(data, stride)),
+        gltf::accessor::sparse::IndexType::U16 => {
+            SparseIndicesIter::U16(ItemIter::new(data, stride))
+        }
+        gltf::accessor::sparse::IndexType::U32 => {
+            SparseIndicesIter::U32(ItemIter::new(data, stride))
+        }
+    };
+    let values = sparse.values();
+    let (data, stride) = items(
+        values.view(),
+        values.offset(),
+        sparse.count(),
+        size_of::<T>(),
+    )?;
+    Some(Iter::Sparse(SparseIter::with_base_count(
+        base,
+        accessor.count(),
+        indices,
+        ItemIter::new(data, stride),
+    )))
+}
+
+/// Indices of a primitive, see `gltf::mesh::Reader::read_indices`.
+pub fn read_indices<'s>(
+    primitive: &gltf::Primitive,
+    buffers: &'s BufferData,
+) -> Option<ReadIndices<'s>> {
+    let accessor = primitive.indices()?;
+    match accessor.data_type() {
+        DataType::U8 => iter(&accessor, buffers).map(ReadIndices::U8),
+        DataType::U16 => iter(&accessor, buffers).map(ReadIndices::U16),
+        DataType::U32 => iter(&accessor, buffers).map(ReadIndices::U32),
+        _ => None,
+    }
+}
+
+/// Colors of a set, see `gltf::mesh::Reader::read_colors`.
+pub fn read_colors<'s>(
+    primitive: &gltf::Primitive,
+    set: u32,
+    buffers: &'s BufferData,
+) -> Option<ReadColors<'s>> {
+    let accessor = primitive.get(&Semantic::Colors(set))?;
+    match (accessor.data_type(), accessor.dimensions()) {
+        (DataType::U8, Dimensions::Vec3) => iter(&accessor, buffers).map(ReadColors::RgbU8),
+        (DataType::U16, Dimensions::Vec3) => iter(&accessor, buffers).map(ReadColors::RgbU16),
+        (DataType::F32, Dimensions::Vec3) => iter(&accessor, buffers).map(ReadColors::RgbF32),
+        (DataType::U8, Dimensions::Vec4) => iter(&accessor, buffers).map(ReadColors::RgbaU8),
+        (DataType::U16, Dimensions::Vec4) => iter(&accessor, buffers).map(ReadColors::RgbaU16),
+        (DataType::F32, Dimensions::Vec4) => iter(&accessor, buffers).map(ReadColors::RgbaF32),
+        _ => None,
+    }
+}
+
+/// Joints of a set, see `gltf::mesh::Reader::read_joints`.
+pub fn read_joints<'s>(
+    primitive: &gltf::Primitive,
+    set: u32,
+    buffers: &'s BufferData,
+) -> Option<ReadJoints<'s>> {
+    let accessor = primitive.get(&Semantic::Joints(set))?;
+    match accessor.data_type() {
+        DataType::U8 => iter(&accessor, buffers).map(ReadJoints::U8),
+        DataType::U16 => iter(&accessor, buffers).map(ReadJoints::U16),
+        _ => None,
+    }
+}
+
+/// Weights of a set, see `gltf::mesh::Reader::read_weights`.
+pub fn read_weights<'s>(
+    primitive: &gltf::Primitive,
+    set: u32,
+    buffers: &'s BufferData,
+) -> Option<ReadWeights<'s>> {
+    let accessor = primitive.get(&Semantic::Weights(set))?;
+    match accessor.data_type() {
+        DataType::U8 => iter(&accessor, buffers).map(ReadWeights::U8),
+        DataType::U16 => iter(&accessor, buffers).map(ReadWeights::U16),
+        DataType::F32 => iter(&accessor, buffers).map(ReadWeights::F32),
+        _ => None,
+    }
+}
+
+/// Keyframe values of a channel, see
+/// `gltf::animation::util::Reader::read_outputs`.
+pub fn read_outputs<'s>(
+    channel: &gltf::animation::Channel,
+    buffers: &'s BufferData,
+) -> Option<ReadOutputs<'s>> {
+    let output = channel.sampler().output();
+    let data_type = output.data_type();
+    match channel.target().property() {
+        Property::Translation => iter(&output, buffers).map(ReadOutputs::Translations),
+        Property::Scale => iter(&output, buffers).map(ReadOutputs::Scales),
+        Property::Rotation => match data_type {
+            DataType::I8 => iter(&output, buffers).map(Rotations::I8),
+            DataType::U8 => iter(&output, buffers).map(Rotations::U8),
+            DataType::I16 => iter(&output, buffers).map(Rotations::I16),
+            DataType::U16 => iter(&output, buffers).map(Rotations::U16),
+            DataType::F32 => iter(&output, buffers).map(Rotations::F32),
+            DataType::U32 => None,
+        }
+        .map(ReadOutputs::Rotations),
+        Property::MorphTargetWeights => match data_type {
+            DataType::I8 => iter(&output, buffers).map(MorphTargetWeights::I8),
+            DataType::U8 => iter(&output, buffers).map(MorphTargetWeights::U8),
+            DataType::I16 => iter(&output, buffers).map(MorphTargetWeights::I16),
+            DataType::U16 => iter(&output, buffers).map(MorphTargetWeights::U16),
+            DataType::F32 => iter(&output, buffers).map(MorphTargetWeights::F32),
+            DataType::U32 => None,
+        }
+        .map(ReadOutputs::MorphTargetWeights),
+    }
+}
@@ -0,0 +1,292 @@
+use crate::scene::{image_based_light, scene_nodes};
+use crate::LoadOptions;
+use gltf::buffer::{Data, Source, View};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::{fs, thread};
+
+/// Bytes of the buffers of a document, each buffer holding its bytes from a
+/// given offset, read with the readers of `utils::accessor`.
+#[derive(Default)]
+pub struct BufferData(Vec<(usize, Vec<u8>)>);
+
+impl BufferData {
+    /// Bytes of a view, `None` if they are not loaded.
+    pub fn view(&self, view: &View) -> Option<&[u8]> {
+        let (offset, data) = self.0.get(view.buffer().index())?;
+        let start = view.offset().checked_sub(*offset)?;
+        data.get(start..start + view.length())
+    }
+}
+
+impl From<Vec<Data>> for BufferData {
+    fn from(buffers: Vec<Data>) -> Self {
+        Self(buffers.into_iter().map(|data| (0, data.0)).collect())
+    }
+}
+
+/// Buffers of a document, loaded upfront or read on demand from their files
+/// (`LoadOptions::stream_buffers`).
+#[derive(Clone)]
+pub enum Buffers {
+    Loaded(Arc<BufferData>),
+    /// Files of the used buffers.
+    Streamed(Arc<Vec<Option<PathBuf>>>),
+}
+
+impl Buffers {
+    /// Buffers holding at least the data of `views`.
+    ///
+    /// Streamed buffers are read from the first to the last byte used by the
+    /// views of each buffer, an error is returned if a file can't be read
+    /// anymore.
+    pub fn get<'a>(
+        &self,
+        views: impl IntoIterator<Item = View<'a>>,
+    ) -> gltf::Result<Arc<BufferData>> {
+        let files = match self {
+            Buffers::Loaded(buffers) => return Ok(buffers.clone()),
+            Buffers::Streamed(files) => files,
+        };
+        let mut ranges = vec![None; files.len()];
+        for view in views {
+            let (start, end) = (view.offset(), view.offset() + view.length());
+            let range = ranges[view.buffer().index()].get_or_insert((start, end));
+            *range = (range.0.min(start), range.1.max(end));
+        }
+        let buffers = files
+            .iter()
+            .zip(ranges)
+            .map(|(file, range)| match (file, range) {
+                (Some(file), Some((start, end))) => {
+                    let mut data = vec![0; end - start];
+                    let mut file = File::open(file)?;
+                    file.seek(SeekFrom::Start(start as u64))?;
+                    file.read_exact(&mut data)?;
+                    Ok((start, data))
+                }
+                _ => Ok((0, vec![])),
+            })
+            .collect::<gltf::Result<_>>()?;
+        Ok(Arc::new(BufferData(buffers)))
+    }
+
+    /// Number of buffers and bytes in memory.
+    #[cfg(feature = "tracing")]
+    pub fn size(&self) -> (usize, usize) {
+        match self {
+            Buffers::Loaded(buffers) => (
+                buffers.0.len(),
+                buffers.0.iter().map(|(_, data)| data.len()).sum(),
+            ),
+            Buffers::Streamed(files) => (files.len(), 0),
+        }
+    }
+}
+
+/// Views of an accessor, sparse ones included.
+pub fn accessor_views<'a>(accessor: &gltf::Accessor<'a>) -> Vec<View<'a>> {
+    let mut views: Vec<_> = accessor.view().into_iter().collect();
+    if let Some(sparse) = accessor.sparse() {
+        views.push(sparse.indices().view());
+        views.push(sparse.values().view());
+    }
+    views
+}
+
+/// Views of the attributes, indices and morph targets of a primitive.
+pub fn primitive_views<'a>(primitive: &gltf::Primitive<'a>) -> Vec<View<'a>> {
+    let mut accessors: Vec<_> = primitive
+        .attributes()
+        .map(|(_, accessor)| accessor)
+        .collect();
+    accessors.extend(primitive.indices());
+    for target in primitive.morph_targets() {
+        accessors.extend(target.positions());
+        accessors.extend(target.normals());
+        accessors.extend(target.tangents());
+    }
+    accessors.iter().flat_map(accessor_views).collect()
+}
+
+/// Read the `used` buffers, other buffers are left empty. External buffers
+/// are read concurrently with `LoadOptions::threads`, or streamed with
+/// `LoadOptions::stream_buffers` when all the used buffers are external
+/// files.
+pub fn import_buffers(
+    doc: &gltf::Document,
+    used: &HashSet<usize>,
+    options: &LoadOptions,
+    base: &Path,
+    mut blob: Option<Vec<u8>>,
+) -> gltf::Result<Buffers> {
+    if options.stream_buffers {
+        if let Some(files) = buffer_files(doc, used, base)? {
+            return Ok(Buffers::Streamed(Arc::new(files)));
+        }
+    }
+    let read = |buffer: gltf::Buffer, blob: &mut Option<Vec<u8>>| {
+        if !used.contains(&buffer.index()) {
+            return Ok(Data(vec![]));
+        }
+        let data = Data::from_source_and_blob(buffer.source(), Some(base), blob)?;
+        if data.len() < buffer.length() {
+            return Err(gltf::Error::BufferLength {
+                buffer: buffer.index(),
+                expected: buffer.length(),
+                actual: data.len(),
+            });
+        }
+        Ok(data)
+    };
+
+    if options.thread_count() <= 1 {
+        let buffers = doc.buffers().map(|buffer| read(buffer, &mut blob));
+        let buffers: Vec<_> = buffers.collect::<Result<_, _>>()?;
+        return Ok(Buffers::Loaded(Arc::new(buffers.into())));
+    }
+    let buffers = thread::scope(|scope| {
+        let read = &read;
+        let buffers: Vec<_> = doc
+            .buffers()
+            .map(|buffer| match buffer.source() {
+                Source::Uri(_) if used.contains(&buffer.index()) => {
+                    Err(scope.spawn(move || read(buffer, &mut None)))
+                }
+                _ => Ok(read(buffer, &mut blob)),
+            })
+            .collect();
+        buffers
+            .into_iter()
+            .map(|buffer| match buffer {
+                Ok(data) => data,
+                Err(worker) => worker.join().unwrap(),
+            })
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+    Ok(Buffers::Loaded(Arc::new(buffers.into())))
+}
+
+/// Files of the used buffers, checked to be long enough. `None` if a used
+/// buffer isn't an external file.
+fn buffer_files(
+    doc: &gltf::Document,
+    used: &HashSet<usize>,
+    base: &Path,
+) -> gltf::Result<Option<Vec<Option<PathBuf>>>> {
+    let mut files = vec![];
+    for buffer in doc.buffers() {
+        if !used.contains(&buffer.index()) {
+            files.push(None);
+            continue;
+        }
+        let path = match buffer.source() {
+            Source::Uri(uri) if !uri.starts_with("data:") && !uri.contains("://") => {
+                match urlencoding::decode(uri) {
+                    Ok(path) => base.join(path.as_ref()),
+                    Err(_) => base.join(uri),
+                }
+            }
+            _ => return Ok(None),
+        };
+        let length = fs::metadata(&path)?.len() as usize;
+        if length < buffer.length() {
+            return Err(gltf::Error::BufferLength {
+                buffer: buffer.index(),
+                expected: buffer.length(),
+                actual: length,
+            });
+        }
+        files.push(Some(path));
+    }
+    Ok(Some(files))
+}
+
+/// Indices of the buffers holding the images of the document, whether or not
+/// a scene uses them.
+pub(crate) fn image_buffers(doc: &gltf::Document) -> HashSet<usize> {
+    doc.images()
+        .filter_map(|image| match image.source() {
+            gltf::image::Source::View { view, .. } => Some(view.buffer().index()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Indices of the buffers needed to load the given scenes.
+pub(crate) fn used_buffers(
+    doc: &gltf::Document,
+    scenes: &[gltf::Scene],
+    options: &LoadOptions,
+) -> HashSet<usize> {
+    let mut used = HashSet::new();
+    let mut use_view = |view: Option<View>| {
+        if let Some(view) = view {
+            used.insert(view.buffer().index());
+        }
+    };
+
+    let mut images = HashSet::new();
+    let mut nodes = HashSet::new();
+    for scene in scenes {
+        images.extend(image_based_light::image_indices(doc, scene));
+        nodes.extend(scene_nodes(scene));
+    }
+    let load_materials =
+        !options.skip_materials && (options.materials_only || !options.skip_geometry);
+    for node in doc.nodes().filter(|n| nodes.contains(&n.index())) {
+        if let Some(mesh) = node.mesh() {
+            for primitive in mesh.primitives() {
+                if !options.skip_geometry && !options.materials_only {
+                    primitive_views(&primitive)
+                        .into_iter()
+                        .for_each(|v| use_view(Some(v)));
+                }
+                if load_materials {
+                    images.extend(material_images(&primitive.material()));
+                }
+            }
+        }
+        if let Some(skin) = node.skin() {
+            use_view(skin.inverse_bind_matrices().and_then(|a| a.view()));
+        }
+    }
+
+    for image in doc.images().filter(|i| images.contains(&i.index())) {
+        if let gltf::image::Source::View { view, .. } = image.source() {
+            use_view(Some(view));
+        }
+    }
+    // Same animations as `Scene::load`
+    let animations = doc.animations().filter(|animation| {
+        animation
+            .channels()
+            .any(|channel| nodes.contains(&channel.target().node().index()))
+    });
+    for animation in animations {
+        for sampler in animation.samplers() {
+            use_view(sampler.input().view());
+            use_view(sampler.output().view());
+        }
+    }
+    used
+}
+
+/// Indices of the images of the textures of a material.
+fn material_images(material: &gltf::Material) -> Vec<usize> {
+    let pbr = material.pbr_metallic_roughness();
+    [
+        pbr.base_color_texture().map(|t| t.texture()),
+        pbr.metallic_roughness_texture().map(|t| t.texture()),
+        material.normal_texture().map(|t| t.texture()),
+        material.occlusion_texture().map(|t| t.texture()),
+        material.emissive_texture().map(|t| t.texture()),
+    ]
+    .iter()
+    .flatten()
+    .map(|texture| texture.source().index())
+    .collect()
+}
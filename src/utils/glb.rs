@@ -0,0 +1,98 @@
+use gltf::binary::{ChunkType, Error};
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Read a glTF or GLB file, without validation.
+///
+/// `gltf::Gltf::from_reader` reads the whole GLB file in memory then copies
+/// its BIN chunk. Here the chunks are read one after the other, the BIN chunk
+/// directly in the buffer returned, so the peak memory is about the size of
+/// the file instead of twice as much.
+pub fn read_gltf<R>(mut reader: R) -> gltf::Result<(gltf::Document, Option<Vec<u8>>)>
+where
+    R: Read + Seek,
+{
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != b"glTF" {
+        reader.seek(SeekFrom::Current(-4))?;
+        let json = gltf::json::deserialize::from_reader(reader)?;
+        return Ok((gltf::Document::from_json_without_validation(json), None));
+    }
+
+    let version = read_u32(&mut reader)?;
+    if version != 2 {
+        return Err(gltf::Error::Binary(Error::Version(version)));
+    }
+    let _length = read_u32(&mut reader)?;
+
+    let json = match read_chunk_header(&mut reader)? {
+        Some((ChunkType::Json, length)) => read_chunk(&mut reader, ChunkType::Json, length)?,
+        Some((ty, _)) => return Err(gltf::Error::Binary(Error::ChunkType(ty))),
+        None => return Err(gltf::Error::Binary(Error::Io(eof()))),
+    };
+    let json = gltf::json::deserialize::from_slice(&json)?;
+
+    let blob = match read_chunk_header(&mut reader)? {
+        Some((ChunkType::Bin, length)) => {
+            // Padded like buffers, the capacity is already there
+            let mut blob = read_chunk(&mut reader, ChunkType::Bin, length)?;
+            blob.resize((blob.len() + 3) & !3, 0);
+            Some(blob)
+        }
+        Some((ty, _)) => return Err(gltf::Error::Binary(Error::ChunkType(ty))),
+        None => None,
+    };
+    Ok((gltf::Document::from_json_without_validation(json), blob))
+}
+
+/// Type and length of the next chunk, `None` at the end of the file.
+fn read_chunk_header<R: Read>(reader: &mut R) -> gltf::Result<Option<(ChunkType, u32)>> {
+    let mut header = [0; 8];
+    let read = reader.by_ref().take(8).read(&mut header)?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if read < 8 {
+        reader.read_exact(&mut header[read..])?;
+    }
+    let length = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    let ty = match &header[4..] {
+        b"JSON" => ChunkType::Json,
+        b"BIN\0" => ChunkType::Bin,
+        _ => {
+            let ty = [header[4], header[5], header[6], header[7]];
+            return Err(gltf::Error::Binary(Error::UnknownChunkType(ty)));
+        }
+    };
+    Ok(Some((ty, length)))
+}
+
+/// Data of a chunk, allocated with room for its padding to a multiple of 4
+/// bytes. The padding in the file is skipped.
+fn read_chunk<R: Read>(reader: &mut R, ty: ChunkType, length: u32) -> gltf::Result<Vec<u8>> {
+    let padded = (length as usize + 3) & !3;
+    let mut data = Vec::with_capacity(padded);
+    reader.by_ref().take(length as u64).read_to_end(&mut data)?;
+    if data.len() < length as usize {
+        return Err(gltf::Error::Binary(Error::ChunkLength {
+            ty,
+            length,
+            length_read: data.len(),
+        }));
+    }
+    io::copy(
+        &mut reader.by_ref().take((padded - data.len()) as u64),
+        &mut io::sink(),
+    )?;
+    Ok(data)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn eof() -> io::Error {
+    io::Error::from(io::ErrorKind::UnexpectedEof)
+}
@@ -1,56 +1,196 @@
-use crate::Material;
+use super::{premultiply_alpha, Buffers};
+use crate::cache::{CachedImage, ImageCache, ImageKey, ImageSource, PixelFormat};
+use crate::model::NormalConvention;
+use crate::{LoadOptions, Material};
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use base64::Engine;
 use gltf::image::Source;
 use image::*;
 use std::collections::HashMap;
+use std::error::Error;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
 /// Helps to simplify the signature of import related functions.
 pub struct GltfData {
-    pub buffers: Vec<gltf::buffer::Data>,
+    pub options: LoadOptions,
+    /// Shared with the threads converting primitives.
+    pub buffers: Buffers,
     pub base_dir: PathBuf,
+    /// Path of the glTF file, identifies its embedded images in the cache.
+    pub file: PathBuf,
     pub materials: HashMap<Option<usize>, Arc<Material>>,
-    pub rgb_images: HashMap<usize, Arc<RgbImage>>,
-    pub rgba_images: HashMap<usize, Arc<RgbaImage>>,
-    pub gray_images: HashMap<(usize, usize), Arc<GrayImage>>,
+    pub images: Arc<Mutex<dyn ImageCache>>,
+    /// Handles of the images given to `LoadOptions::image_consumer`, so an
+    /// image used by several materials is consumed once.
+    pub consumed: HashMap<ImageKey, u64>,
+    /// Keys of the images decoded for the material being loaded, by
+    /// `CachedImage::id`, until they are consumed.
+    pub pending: HashMap<usize, ImageKey>,
+    /// Temporary attribute arrays reused from one primitive to the next.
+    pub scratch: Scratch,
+}
+
+/// Buffers reused across primitive conversions, so converting thousands of
+/// primitives doesn't allocate (and free) temporary arrays for each of them.
+#[derive(Default)]
+pub struct Scratch {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub tangents: Vec<[f32; 4]>,
+    pub tex_coords: Vec<[f32; 2]>,
 }
 
 impl GltfData {
-    pub fn new<P>(buffers: Vec<gltf::buffer::Data>, path: P) -> Self
+    pub fn new<P>(
+        buffers: Buffers,
+        path: P,
+        options: &LoadOptions,
+        images: Arc<Mutex<dyn ImageCache>>,
+    ) -> Self
     where
         P: AsRef<Path>,
     {
+        let file = PathBuf::from(path.as_ref());
+        let file = file.canonicalize().unwrap_or(file);
         let mut base_dir = PathBuf::from(path.as_ref());
         base_dir.pop();
+        {
+            let mut cache = images.lock().unwrap_or_else(PoisonError::into_inner);
+            cache.set_budget(options.texture_budget);
+        }
         GltfData {
+            options: options.clone(),
             buffers,
             base_dir,
+            file,
             materials: Default::default(),
-            rgb_images: Default::default(),
-            rgba_images: Default::default(),
-            gray_images: Default::default(),
+            images,
+            consumed: Default::default(),
+            pending: Default::default(),
+            scratch: Default::default(),
+        }
+    }
+
+    /// Material of the primitives without material.
+    pub fn default_material(&mut self) -> Arc<Material> {
+        if let Some(material) = self.materials.get(&None) {
+            return material.clone();
         }
+        let mut material = match &self.options.default_material {
+            Some(material) => material.as_ref().clone(),
+            None => Material::gltf_default(),
+        };
+        material.fill_placeholders(self.options.placeholder_textures);
+        material.consume_textures(self);
+        let material = Arc::new(material);
+        self.materials.insert(None, material.clone());
+        material
+    }
+
+    /// Path of a relative URI, URIs are percent-encoded (spaces, unicode...).
+    fn uri_path(&self, uri: &str) -> PathBuf {
+        match urlencoding::decode(uri) {
+            Ok(path) => self.base_dir.join(path.as_ref()),
+            Err(_) => self.base_dir.join(uri),
+        }
+    }
+
+    fn image_source(&self, image: &gltf::Image<'_>) -> ImageSource {
+        match image.source() {
+            Source::Uri { uri, .. } if !uri.starts_with("data:") => {
+                let path = self.uri_path(uri);
+                ImageSource::File(path.canonicalize().unwrap_or(path))
+            }
+            _ => ImageSource::Embedded(self.file.clone(), image.index()),
+        }
+    }
+
+    /// Get an image from the cache, or decode it and cache it. Files loaded
+    /// concurrently may decode the same image, the first one cached wins.
+    ///
+    /// With `LoadOptions::image_consumer`, decoded images are not cached: they
+    /// are dropped once consumed.
+    fn cached(&mut self, key: ImageKey, decode: impl FnOnce(&Self) -> CachedImage) -> CachedImage {
+        let cached = self.cache().get(&key);
+        let image = match cached {
+            Some(image) => image,
+            None if self.options.image_consumer.is_none() => {
+                let image = decode(self);
+                return self.cache().insert(key, image);
+            }
+            None => decode(self),
+        };
+        if self.options.image_consumer.is_some() {
+            self.pending.insert(image.id(), key);
+        }
+        image
+    }
+
+    fn cache(&self) -> MutexGuard<'_, dyn ImageCache + 'static> {
+        self.images.lock().unwrap_or_else(PoisonError::into_inner)
     }
 
     pub fn load_rgb_image(&mut self, texture: &gltf::Texture<'_>) -> Arc<RgbImage> {
-        if let Some(image) = self.rgb_images.get(&texture.index()) {
-            return image.clone();
+        let key = (self.image_source(&texture.source()), PixelFormat::Rgb);
+        match self.cached(key, |data| {
+            CachedImage::Rgb(Arc::new(data.load_image(&texture.source()).to_rgb8()))
+        }) {
+            CachedImage::Rgb(image) => image,
+            _ => unreachable!("pixel format is part of the key"),
         }
+    }
 
-        let img = Arc::new(self.load_texture(texture).to_rgb8());
-        self.rgb_images.insert(texture.index(), img.clone());
-        img
+    /// Normal map in the convention of `LoadOptions::normal_convention`, the
+    /// converted image being shared by the materials using it.
+    pub fn load_normal_image(&mut self, texture: &gltf::Texture<'_>) -> Arc<RgbImage> {
+        if self.options.normal_convention == NormalConvention::OpenGl {
+            return self.load_rgb_image(texture);
+        }
+
+        let key = (
+            self.image_source(&texture.source()),
+            PixelFormat::DirectXRgb,
+        );
+        match self.cached(key, |data| {
+            let mut img = data.load_image(&texture.source()).to_rgb8();
+            for pixel in img.pixels_mut() {
+                pixel[1] = 255 - pixel[1];
+            }
+            CachedImage::Rgb(Arc::new(img))
+        }) {
+            CachedImage::Rgb(image) => image,
+            _ => unreachable!("pixel format is part of the key"),
+        }
     }
 
     pub fn load_base_color_image(&mut self, texture: &gltf::Texture<'_>) -> Arc<RgbaImage> {
-        if let Some(image) = self.rgba_images.get(&texture.index()) {
-            return image.clone();
+        if !self.options.premultiply_alpha {
+            return self.load_rgba_image(&texture.source());
+        }
+
+        let key = (
+            self.image_source(&texture.source()),
+            PixelFormat::PremultipliedRgba,
+        );
+        match self.cached(key, |data| {
+            let mut img = data.load_image(&texture.source()).to_rgba8();
+            premultiply_alpha(&mut img);
+            CachedImage::Rgba(Arc::new(img))
+        }) {
+            CachedImage::Rgba(image) => image,
+            _ => unreachable!("pixel format is part of the key"),
+        }
+    }
+
+    pub fn load_rgba_image(&mut self, image: &gltf::Image<'_>) -> Arc<RgbaImage> {
+        let key = (self.image_source(image), PixelFormat::Rgba);
+        match self.cached(key, |data| {
+            CachedImage::Rgba(Arc::new(data.load_image(image).to_rgba8()))
+        }) {
+            CachedImage::Rgba(image) => image,
+            _ => unreachable!("pixel format is part of the key"),
         }
-        let img = Arc::new(self.load_texture(texture).to_rgba8());
-        self.rgba_images.insert(texture.index(), img.clone());
-        img
     }
 
     pub fn load_gray_image(
@@ -58,62 +198,79 @@ impl GltfData {
         texture: &gltf::Texture<'_>,
         channel: usize,
     ) -> Arc<GrayImage> {
-        if let Some(image) = self.gray_images.get(&(texture.index(), channel)) {
-            return image.clone();
-        }
-        let img = self.load_texture(texture).to_rgba8();
-        let mut extract_img = GrayImage::new(img.width(), img.height());
-        for (x, y, px) in img.enumerate_pixels() {
-            extract_img[(x, y)][0] = px[channel];
+        let key = (
+            self.image_source(&texture.source()),
+            PixelFormat::Gray(channel),
+        );
+        match self.cached(key, |data| {
+            let img = data.load_image(&texture.source()).to_rgba8();
+            let mut extract_img = GrayImage::new(img.width(), img.height());
+            for (x, y, px) in img.enumerate_pixels() {
+                extract_img[(x, y)][0] = px[channel];
+            }
+            CachedImage::Gray(Arc::new(extract_img))
+        }) {
+            CachedImage::Gray(image) => image,
+            _ => unreachable!("pixel format is part of the key"),
         }
-        let img = Arc::new(extract_img);
-        self.gray_images
-            .insert((texture.index(), channel), img.clone());
+    }
+
+    /// Decode an image. Missing or corrupt images are replaced by a 1x1
+    /// white image, like a texture whose factor is used as is, with a warning.
+    pub fn load_image(&self, g_img: &gltf::Image<'_>) -> DynamicImage {
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+        let img = match self.decode_image(g_img) {
+            Ok(img) => img,
+            Err(_error) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(image = g_img.index(), error = %_error, "image not decoded");
+                DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([255; 4])))
+            }
+        };
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            image = g_img.index(),
+            width = img.width(),
+            height = img.height(),
+            elapsed = ?start.elapsed(),
+            "image decoded"
+        );
         img
     }
 
-    pub fn load_texture(&self, texture: &gltf::Texture<'_>) -> DynamicImage {
-        let g_img = texture.source();
-        let buffers = &self.buffers;
-        match g_img.source() {
+    fn decode_image(&self, g_img: &gltf::Image<'_>) -> Result<DynamicImage, Box<dyn Error>> {
+        Ok(match g_img.source() {
             Source::View { view, mime_type } => {
-                let parent_buffer_data = &buffers[view.buffer().index()].0;
-                let data = &parent_buffer_data[view.offset()..view.offset() + view.length()];
-                let mime_type = mime_type.replace('/', ".");
-                image::load_from_memory_with_format(
-                    data,
-                    ImageFormat::from_path(mime_type).unwrap(),
-                )
-                .unwrap()
+                let buffers = self.buffers.get([view.clone()])?;
+                let data = buffers.view(&view).ok_or("image out of its buffer")?;
+                decode_image(data, Some(mime_type))?
             }
             Source::Uri { uri, mime_type } => {
                 if uri.starts_with("data:") {
-                    let encoded = uri.split(',').nth(1).unwrap();
-                    let data = URL_SAFE_NO_PAD.decode(encoded).unwrap();
-                    let mime_type = if let Some(ty) = mime_type {
-                        ty
-                    } else {
-                        uri.split(',')
-                            .next()
-                            .unwrap()
-                            .split(':')
-                            .nth(1)
-                            .unwrap()
-                            .split(';')
-                            .next()
-                            .unwrap()
-                    };
-                    let mime_type = mime_type.replace('/', ".");
-                    image::load_from_memory_with_format(
-                        &data,
-                        ImageFormat::from_path(mime_type).unwrap(),
-                    )
-                    .unwrap()
+                    let encoded = uri.split(',').nth(1).ok_or("data URI without data")?;
+                    let data = URL_SAFE_NO_PAD.decode(encoded)?;
+                    let mime_type = mime_type
+                        .or_else(|| uri.split(',').next()?.split(':').nth(1)?.split(';').next());
+                    decode_image(&data, mime_type)?
                 } else {
-                    let path = self.base_dir.join(uri);
-                    open(path).unwrap()
+                    let path = self.uri_path(uri);
+                    // The content is more reliable than the file extension
+                    ImageReader::open(path)?.with_guessed_format()?.decode()?
                 }
             }
-        }
+        })
+    }
+}
+
+/// Decode an image with its mime type, falling back to the magic bytes when
+/// the mime type is missing or wrong.
+pub fn decode_image(data: &[u8], mime_type: Option<&str>) -> ImageResult<DynamicImage> {
+    match mime_type
+        .and_then(ImageFormat::from_mime_type)
+        .and_then(|format| image::load_from_memory_with_format(data, format).ok())
+    {
+        Some(image) => Ok(image),
+        None => image::load_from_memory(data),
     }
 }
@@ -1,14 +1,213 @@
+pub(crate) mod accessor;
+mod buffers;
+mod glb;
 mod gltf_data;
 
-pub(crate) use gltf_data::GltfData;
+pub(crate) use buffers::{
+    accessor_views, image_buffers, import_buffers, primitive_views, used_buffers, BufferData,
+    Buffers,
+};
+pub(crate) use glb::read_gltf;
+
+#[cfg(test)]
+pub(crate) use gltf_data::decode_image;
+pub(crate) use gltf_data::{GltfData, Scratch};
 
 use cgmath::*;
+use gltf::accessor::{DataType, Item};
 use gltf::scene::Transform;
 
-pub fn transform_to_matrix(transform: Transform) -> Matrix4<f32> {
-    let tr = transform.matrix();
+/// Node transform in double precision, so long node hierarchies and large
+/// coordinates don't accumulate `f32` rounding errors.
+pub fn transform_to_matrix(transform: Transform) -> Matrix4<f64> {
+    let tr = transform.matrix().map(|column| column.map(f64::from));
     Matrix4::new(
         tr[0][0], tr[0][1], tr[0][2], tr[0][3], tr[1][0], tr[1][1], tr[1][2], tr[1][3], tr[2][0],
         tr[2][1], tr[2][2], tr[2][3], tr[3][0], tr[3][1], tr[3][2], tr[3][3],
     )
 }
+
+/// Inverse-transpose of the upper 3x3 of a transform, used to transform
+/// normals correctly under non-uniform scale and shear.
+pub fn normal_matrix(transform: &Matrix4<f32>) -> Matrix3<f32> {
+    let linear = Matrix3::from_cols(
+        transform.x.truncate(),
+        transform.y.truncate(),
+        transform.z.truncate(),
+    );
+    linear.invert().unwrap_or(linear).transpose()
+}
+
+/// Scale factor of a transform for lengths: the cube root of the volume
+/// scale, exact for uniform scales.
+pub fn uniform_scale(transform: &Matrix4<f32>) -> f32 {
+    Matrix3::from_cols(
+        transform.x.truncate(),
+        transform.y.truncate(),
+        transform.z.truncate(),
+    )
+    .determinant()
+    .abs()
+    .cbrt()
+}
+
+/// Read a float attribute, dequantizing integer components
+/// (`KHR_mesh_quantization`) to `f32`.
+pub fn read_f32<const N: usize>(
+    accessor: &gltf::Accessor,
+    buffers: &BufferData,
+) -> Option<Vec<[f32; N]>>
+where
+    [f32; N]: Item,
+    [i8; N]: Item,
+    [u8; N]: Item,
+    [i16; N]: Item,
+    [u16; N]: Item,
+{
+    let mut values = vec![];
+    read_f32_into(accessor, buffers, &mut values).then_some(values)
+}
+
+/// Same as `read_f32` but reading in `values`, cleared first, so a scratch
+/// buffer can be reused. Returns whether the attribute could be read.
+pub fn read_f32_into<const N: usize>(
+    accessor: &gltf::Accessor,
+    buffers: &BufferData,
+    values: &mut Vec<[f32; N]>,
+) -> bool
+where
+    [f32; N]: Item,
+    [i8; N]: Item,
+    [u8; N]: Item,
+    [i16; N]: Item,
+    [u16; N]: Item,
+{
+    fn read<T: Copy, const N: usize>(
+        accessor: &gltf::Accessor,
+        buffers: &BufferData,
+        values: &mut Vec<[f32; N]>,
+        convert: impl Fn(T) -> f32,
+    ) -> bool
+    where
+        [T; N]: Item,
+    {
+        match accessor::iter::<[T; N]>(accessor, buffers) {
+            Some(iter) => {
+                values.reserve_exact(accessor.count());
+                values.extend(iter.map(|v| v.map(&convert)));
+                true
+            }
+            None => false,
+        }
+    }
+
+    values.clear();
+    let normalized = accessor.normalized();
+    match accessor.data_type() {
+        DataType::F32 => read(accessor, buffers, values, |c: f32| c),
+        DataType::I8 if normalized => read(accessor, buffers, values, |c: i8| {
+            (c as f32 / 127.).max(-1.)
+        }),
+        DataType::U8 if normalized => read(accessor, buffers, values, |c: u8| c as f32 / 255.),
+        DataType::I16 if normalized => read(accessor, buffers, values, |c: i16| {
+            (c as f32 / 32767.).max(-1.)
+        }),
+        DataType::U16 if normalized => read(accessor, buffers, values, |c: u16| c as f32 / 65535.),
+        DataType::I8 => read(accessor, buffers, values, |c: i8| c as f32),
+        DataType::U8 => read(accessor, buffers, values, |c: u8| c as f32),
+        DataType::I16 => read(accessor, buffers, values, |c: i16| c as f32),
+        DataType::U16 => read(accessor, buffers, values, |c: u16| c as f32),
+        DataType::U32 => false,
+    }
+}
+
+/// Transform points in place. Affine transforms (the common case) skip the
+/// perspective divide so the loop is branch-free and auto-vectorized.
+pub fn transform_points(points: &mut [[f32; 3]], transform: &Matrix4<f32>) {
+    let m: &[[f32; 4]; 4] = transform.as_ref();
+    let [c0, c1, c2, c3] = *m;
+    if c0[3] == 0. && c1[3] == 0. && c2[3] == 0. && c3[3] == 1. {
+        for p in points.iter_mut() {
+            let [x, y, z] = *p;
+            *p = [
+                c0[0] * x + c1[0] * y + c2[0] * z + c3[0],
+                c0[1] * x + c1[1] * y + c2[1] * z + c3[1],
+                c0[2] * x + c1[2] * y + c2[2] * z + c3[2],
+            ];
+        }
+    } else {
+        for p in points.iter_mut() {
+            let [x, y, z] = *p;
+            let w = c0[3] * x + c1[3] * y + c2[3] * z + c3[3];
+            *p = [
+                (c0[0] * x + c1[0] * y + c2[0] * z + c3[0]) / w,
+                (c0[1] * x + c1[1] * y + c2[1] * z + c3[1]) / w,
+                (c0[2] * x + c1[2] * y + c2[2] * z + c3[2]) / w,
+            ];
+        }
+    }
+}
+
+/// Transform the `xyz` part of vectors in place, other components are left
+/// untouched.
+pub fn transform_vectors<const N: usize>(vectors: &mut [[f32; N]], matrix: &Matrix3<f32>) {
+    let m: &[[f32; 3]; 3] = matrix.as_ref();
+    let [c0, c1, c2] = *m;
+    for v in vectors.iter_mut() {
+        let (x, y, z) = (v[0], v[1], v[2]);
+        v[0] = c0[0] * x + c1[0] * y + c2[0] * z;
+        v[1] = c0[1] * x + c1[1] * y + c2[1] * z;
+        v[2] = c0[2] * x + c1[2] * y + c2[2] * z;
+    }
+}
+
+/// Transform and normalize the `xyz` part of directions (normals, tangents...)
+/// in place, other components are left untouched.
+pub fn transform_directions<const N: usize>(directions: &mut [[f32; N]], matrix: &Matrix3<f32>) {
+    transform_vectors(directions, matrix);
+    for d in directions.iter_mut() {
+        let inv_len = 1. / (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+        d[0] *= inv_len;
+        d[1] *= inv_len;
+        d[2] *= inv_len;
+    }
+}
+
+/// Encode a linear color component in `[0, 1]` to an sRGB byte.
+pub fn linear_to_srgb(c: f32) -> u8 {
+    let c = c.clamp(0., 1.);
+    let srgb = if c <= 0.003_130_8 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1. / 2.4) - 0.055
+    };
+    (srgb * 255.).round() as u8
+}
+
+/// Decode an sRGB byte to a linear color component in `[0, 1]`.
+pub fn srgb_to_linear(c: u8) -> f32 {
+    let c = c as f32 / 255.;
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Multiply the sRGB encoded color of every pixel by its alpha, in linear
+/// space.
+pub fn premultiply_alpha(image: &mut image::RgbaImage) {
+    let linear: Vec<f32> = (0..=255).map(srgb_to_linear).collect();
+    for px in image.pixels_mut() {
+        let alpha = px[3] as f32 / 255.;
+        for c in px.0.iter_mut().take(3) {
+            *c = linear_to_srgb(linear[*c as usize] * alpha);
+        }
+    }
+}
+
+/// View a slice of plain data (vertices, indices...) as bytes.
+pub fn as_bytes<T: Copy>(slice: &[T]) -> &[u8] {
+    // SAFETY: Only used with padding-free `repr(C)` types and primitives.
+    unsafe { std::slice::from_raw_parts(slice.as_ptr() as *const u8, std::mem::size_of_val(slice)) }
+}
@@ -0,0 +1,260 @@
+//! Helpers to upload models and materials with [wgpu](https://wgpu.rs).
+//! Requires the `wgpu` feature.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # fn render(device: &wgpu::Device, queue: &wgpu::Queue) {
+//! let scenes = easy_gltf::load("tests/cube.glb").expect("Failed to load glTF");
+//! let model = &scenes[0].models[0];
+//! let vertex_buffer = easy_gltf::wgpu::create_vertex_buffer(device, model);
+//! let index_buffer = easy_gltf::wgpu::create_index_buffer(device, model);
+//! let textures = easy_gltf::wgpu::MaterialTextures::new(device, queue, &model.material());
+//! // Use `easy_gltf::wgpu::vertex_buffer_layout()` in your render pipeline...
+//! # }
+//! ```
+
+use crate::model::{Material, Model, Vertex};
+use crate::utils::as_bytes;
+use ::wgpu::util::{BufferInitDescriptor, DeviceExt, TextureDataOrder};
+use ::wgpu::*;
+use image::{EncodableLayout, ImageBuffer, Pixel, RgbImage, RgbaImage};
+use std::ops::Deref;
+
+/// Vertex attributes matching the memory layout of [`Vertex`](../model/struct.Vertex.html).
+///
+/// Shader locations are: position `0`, normal `1`, tangent `2`, texture
+/// coordinates `3` and color `4` (only with the `vertex-color` feature).
+#[cfg(not(feature = "vertex-color"))]
+pub const VERTEX_ATTRIBUTES: [VertexAttribute; 4] =
+    vertex_attr_array![0 => Float32x3, 1 => Float32x3, 2 => Float32x4, 3 => Float32x2];
+
+/// Vertex attributes matching the memory layout of [`Vertex`](../model/struct.Vertex.html).
+///
+/// Shader locations are: position `0`, normal `1`, tangent `2`, texture
+/// coordinates `3` and color `4` (only with the `vertex-color` feature).
+#[cfg(feature = "vertex-color")]
+pub const VERTEX_ATTRIBUTES: [VertexAttribute; 5] = vertex_attr_array![
+    0 => Float32x3, 1 => Float32x3, 2 => Float32x4, 3 => Float32x2, 4 => Unorm16x4
+];
+
+/// Vertex attributes of `Model::skinned_vertex_bytes()`: the
+/// `VERTEX_ATTRIBUTES` followed by the joints at shader location `5` and the
+/// weights at shader location `6`.
+#[cfg(not(feature = "vertex-color"))]
+pub const SKINNED_VERTEX_ATTRIBUTES: [VertexAttribute; 6] = vertex_attr_array![
+    0 => Float32x3, 1 => Float32x3, 2 => Float32x4, 3 => Float32x2, 5 => Uint16x4, 6 => Float32x4
+];
+
+/// Vertex attributes of `Model::skinned_vertex_bytes()`: the
+/// `VERTEX_ATTRIBUTES` followed by the joints at shader location `5` and the
+/// weights at shader location `6`.
+#[cfg(feature = "vertex-color")]
+pub const SKINNED_VERTEX_ATTRIBUTES: [VertexAttribute; 7] = vertex_attr_array![
+    0 => Float32x3, 1 => Float32x3, 2 => Float32x4, 3 => Float32x2, 4 => Unorm16x4,
+    5 => Uint16x4, 6 => Float32x4
+];
+
+/// Vertex attributes matching the memory layout of
+/// [`HalfVertex`](../model/struct.HalfVertex.html), with the same shader
+/// locations as `VERTEX_ATTRIBUTES`. Requires the `half` feature.
+#[cfg(all(feature = "half", not(feature = "vertex-color")))]
+pub const HALF_VERTEX_ATTRIBUTES: [VertexAttribute; 4] =
+    vertex_attr_array![0 => Float32x3, 1 => Float16x4, 2 => Float16x4, 3 => Float16x2];
+
+/// Vertex attributes matching the memory layout of
+/// [`HalfVertex`](../model/struct.HalfVertex.html), with the same shader
+/// locations as `VERTEX_ATTRIBUTES`. Requires the `half` feature.
+#[cfg(all(feature = "half", feature = "vertex-color"))]
+pub const HALF_VERTEX_ATTRIBUTES: [VertexAttribute; 5] = vertex_attr_array![
+    0 => Float32x3, 1 => Float16x4, 2 => Float16x4, 3 => Float16x2, 4 => Unorm16x4
+];
+
+/// Vertex buffer layout of a buffer filled with `Model::half_vertices()`.
+/// Requires the `half` feature.
+#[cfg(feature = "half")]
+pub fn half_vertex_buffer_layout() -> VertexBufferLayout<'static> {
+    VertexBufferLayout {
+        array_stride: std::mem::size_of::<crate::model::HalfVertex>() as BufferAddress,
+        step_mode: VertexStepMode::Vertex,
+        attributes: &HALF_VERTEX_ATTRIBUTES,
+    }
+}
+
+/// Vertex buffer layout of a buffer created by [`create_vertex_buffer`](fn.create_vertex_buffer.html).
+pub fn vertex_buffer_layout() -> VertexBufferLayout<'static> {
+    VertexBufferLayout {
+        array_stride: std::mem::size_of::<Vertex>() as BufferAddress,
+        step_mode: VertexStepMode::Vertex,
+        attributes: &VERTEX_ATTRIBUTES,
+    }
+}
+
+/// Vertex buffer layout of a buffer filled with
+/// `Model::skinned_vertex_bytes()`.
+pub fn skinned_vertex_buffer_layout() -> VertexBufferLayout<'static> {
+    VertexBufferLayout {
+        array_stride: crate::model::VertexLayout::of_skinned_vertex().stride as BufferAddress,
+        step_mode: VertexStepMode::Vertex,
+        attributes: &SKINNED_VERTEX_ATTRIBUTES,
+    }
+}
+
+/// Create a vertex buffer containing the vertices of the model.
+pub fn create_vertex_buffer(device: &Device, model: &Model) -> Buffer {
+    device.create_buffer_init(&BufferInitDescriptor {
+        label: Some("easy-gltf vertices"),
+        contents: as_bytes(model.vertices()),
+        usage: BufferUsages::VERTEX,
+    })
+}
+
+/// Create an index buffer (of `IndexFormat::Uint32`) if the model has indices.
+pub fn create_index_buffer(device: &Device, model: &Model) -> Option<Buffer> {
+    model.indices().map(|indices| {
+        device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("easy-gltf indices"),
+            contents: as_bytes(indices),
+            usage: BufferUsages::INDEX,
+        })
+    })
+}
+
+/// GPU textures of a material.
+///
+/// Color textures (base color and emissive) use `*Srgb` formats so the
+/// sampled values are already in linear space. Data textures are linear.
+#[derive(Debug)]
+pub struct MaterialTextures {
+    /// Base color texture (`Rgba8UnormSrgb`).
+    pub base_color: Option<Texture>,
+    /// Metallic texture (`R8Unorm`).
+    pub metallic: Option<Texture>,
+    /// Roughness texture (`R8Unorm`).
+    pub roughness: Option<Texture>,
+    /// Normal map (`Rgba8Unorm`).
+    pub normal: Option<Texture>,
+    /// Occlusion texture (`R8Unorm`).
+    pub occlusion: Option<Texture>,
+    /// Emissive texture (`Rgba8UnormSrgb`).
+    pub emissive: Option<Texture>,
+}
+
+impl MaterialTextures {
+    /// Upload every texture of the material.
+    pub fn new(device: &Device, queue: &Queue, material: &Material) -> Self {
+        Self {
+            base_color: material
+                .pbr
+                .base_color_texture
+                .as_ref()
+                .map(|t| create_texture(device, queue, t, TextureFormat::Rgba8UnormSrgb)),
+            metallic: material
+                .pbr
+                .metallic_texture
+                .as_ref()
+                .map(|t| create_texture(device, queue, t, TextureFormat::R8Unorm)),
+            roughness: material
+                .pbr
+                .roughness_texture
+                .as_ref()
+                .map(|t| create_texture(device, queue, t, TextureFormat::R8Unorm)),
+            normal: material.normal.as_ref().map(|n| {
+                create_texture(
+                    device,
+                    queue,
+                    &to_rgba(&n.texture),
+                    TextureFormat::Rgba8Unorm,
+                )
+            }),
+            occlusion: material
+                .occlusion
+                .as_ref()
+                .map(|o| create_texture(device, queue, &o.texture, TextureFormat::R8Unorm)),
+            emissive: material
+                .emissive
+                .texture
+                .as_ref()
+                .map(|t| create_texture(device, queue, &to_rgba(t), TextureFormat::Rgba8UnormSrgb)),
+        }
+    }
+}
+
+/// Create a 2D texture from an image. The format must match the pixel type of
+/// the image (e.g. `Rgba8UnormSrgb` for an `RgbaImage`, `R8Unorm` for a `GrayImage`).
+pub fn create_texture<P, Container>(
+    device: &Device,
+    queue: &Queue,
+    image: &ImageBuffer<P, Container>,
+    format: TextureFormat,
+) -> Texture
+where
+    P: Pixel<Subpixel = u8>,
+    Container: Deref<Target = [u8]>,
+{
+    device.create_texture_with_data(
+        queue,
+        &TextureDescriptor {
+            label: Some("easy-gltf texture"),
+            size: Extent3d {
+                width: image.width(),
+                height: image.height(),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            view_formats: &[],
+        },
+        TextureDataOrder::LayerMajor,
+        image.as_raw().as_bytes(),
+    )
+}
+
+/// Copy the pixels of an image with every row padded to a multiple of
+/// `alignment` bytes, e.g. `COPY_BYTES_PER_ROW_ALIGNMENT` to fill a buffer for
+/// `copy_buffer_to_texture`. Returns the data and the padded number of bytes
+/// per row.
+///
+/// # Example
+///
+/// ```
+/// let image = image::RgbaImage::new(10, 4);
+/// let (data, bytes_per_row) =
+///     easy_gltf::wgpu::padded_rows(&image, wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+/// assert_eq!(bytes_per_row, 256);
+/// assert_eq!(data.len(), 256 * 4);
+/// ```
+pub fn padded_rows<P, Container>(
+    image: &ImageBuffer<P, Container>,
+    alignment: u32,
+) -> (Vec<u8>, u32)
+where
+    P: Pixel<Subpixel = u8>,
+    Container: Deref<Target = [u8]>,
+{
+    let row_len = image.width() as usize * P::CHANNEL_COUNT as usize;
+    let alignment = alignment.max(1) as usize;
+    let bytes_per_row = row_len.div_ceil(alignment) * alignment;
+
+    let mut data = vec![0; bytes_per_row * image.height() as usize];
+    if row_len > 0 {
+        for (row, padded) in image
+            .as_raw()
+            .chunks_exact(row_len)
+            .zip(data.chunks_exact_mut(bytes_per_row))
+        {
+            padded[..row_len].copy_from_slice(row);
+        }
+    }
+    (data, bytes_per_row as u32)
+}
+
+// wgpu has no 3 channels texture format
+fn to_rgba(image: &RgbImage) -> RgbaImage {
+    RgbaImage::from_fn(image.width(), image.height(), |x, y| {
+        image[(x, y)].to_rgba()
+    })
+}